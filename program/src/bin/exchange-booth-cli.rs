@@ -0,0 +1,342 @@
+// Operates exchange booths from scripts: every subcommand builds its instructions with
+// exchangebooth::client, signs them with the keypair file and sends them to the RPC url.
+use std::{
+    error::Error,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::{crate_version, value_t, App, AppSettings, Arg, ArgMatches, SubCommand};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+use borsh::BorshDeserialize;
+
+use exchangebooth::{
+    client::{self, BoothAccounts},
+    inspect::BoothSummary,
+    pricing,
+    processor::{oracle, token},
+    state::ExchangeBooth,
+};
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+
+struct Config {
+    rpc: RpcClient,
+    payer: Keypair,
+    program_id: Pubkey,
+}
+
+fn main() {
+    let matches = App::new("exchange-booth-cli")
+        .version(crate_version!())
+        .about("Operate exchange booths")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(Arg::with_name("url").long("url").short("u").global(true).takes_value(true)
+            .default_value("http://localhost:8899").help("JSON RPC url of the cluster"))
+        .arg(Arg::with_name("keypair").long("keypair").short("k").global(true).takes_value(true)
+            .help("Keypair file that signs and pays [default: ~/.config/solana/id.json]"))
+        .arg(Arg::with_name("program_id").long("program-id").global(true).takes_value(true)
+            .help("Address the exchange booth program is deployed at"))
+        .subcommand(SubCommand::with_name("init").about("Create a booth, and an oracle for it unless --oracle is given")
+            .arg(pubkey_arg("mint_a", "mint-a").required(true))
+            .arg(pubkey_arg("mint_b", "mint-b").required(true))
+            .arg(Arg::with_name("index").long("index").takes_value(true).default_value("0")
+                .help("Tells apart booths of the same admin on the same pair"))
+            .arg(pubkey_arg("oracle", "oracle").help("Existing oracle to price with"))
+            .arg(keypair_arg("oracle_keypair", "oracle-keypair").required_unless("oracle").conflicts_with("oracle")
+                .help("Keypair file for the new oracle, set-rate needs it to sign"))
+            .arg(Arg::with_name("rate").long("rate").takes_value(true).default_value("1")
+                .help("A to B rate written to the new oracle")))
+        .subcommand(SubCommand::with_name("deposit").about("Put tokens into a booth vault, through AddLiquidity once the booth has lp shares")
+            .arg(pubkey_arg("booth", "booth").required(true))
+            .arg(pubkey_arg("mint", "mint").required(true))
+            .arg(amount_arg())
+            .arg(pubkey_arg("from", "from").help("Token account to debit [default: associated token account]")))
+        .subcommand(SubCommand::with_name("withdraw").about("Withdraw tokens from a booth vault, admin only")
+            .arg(pubkey_arg("booth", "booth").required(true))
+            .arg(pubkey_arg("mint", "mint").required(true))
+            .arg(amount_arg())
+            .arg(pubkey_arg("to", "to").help("Token account to credit [default: associated token account]")))
+        .subcommand(SubCommand::with_name("exchange").about("Sell one booth token for the other")
+            .arg(pubkey_arg("booth", "booth").required(true))
+            .arg(pubkey_arg("from_mint", "from-mint").required(true).help("Mint of the token sold"))
+            .arg(amount_arg())
+            .arg(pubkey_arg("from", "from").help("Token account to debit [default: associated token account]"))
+            .arg(pubkey_arg("to", "to").help("Token account to credit [default: associated token account, created if missing]")))
+        .subcommand(SubCommand::with_name("set-rate").about("Push a rate to an oracle")
+            .arg(keypair_arg("oracle_keypair", "oracle-keypair").required(true).help("Keypair file of the oracle"))
+            .arg(Arg::with_name("rate").long("rate").takes_value(true).required(true).help("A to B rate"))
+            .arg(Arg::with_name("confidence").long("confidence").takes_value(true).default_value("0")
+                .help("Fraction of the rate in [0, 1)")))
+        .subcommand(SubCommand::with_name("close").about("Close a booth with empty vaults, admin only")
+            .arg(pubkey_arg("booth", "booth").required(true)))
+        .subcommand(SubCommand::with_name("show-booth").about("Print a booth, its oracle rate, the rate it gives each way and its reserves")
+            .arg(pubkey_arg("booth", "booth").required(true))
+            .arg(Arg::with_name("json").long("json").help("Print the summary as JSON")))
+        .subcommand(SubCommand::with_name("show-oracle").about("Print a pushed oracle")
+            .arg(pubkey_arg("oracle", "oracle").required(true)))
+        .get_matches();
+
+    if let Err(err) = run(&matches) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn pubkey_arg<'a>(name: &'a str, long: &'a str) -> Arg<'a, 'a> {
+    Arg::with_name(name).long(long).takes_value(true).value_name("PUBKEY")
+}
+
+fn keypair_arg<'a>(name: &'a str, long: &'a str) -> Arg<'a, 'a> {
+    Arg::with_name(name).long(long).takes_value(true).value_name("PATH")
+}
+
+fn amount_arg<'a>() -> Arg<'a, 'a> {
+    Arg::with_name("amount").long("amount").takes_value(true).required(true).help("In token units, not base units")
+}
+
+fn pubkey(matches: &ArgMatches, name: &str) -> CliResult<Option<Pubkey>> {
+    match matches.value_of(name) {
+        Some(value) => Ok(Some(Pubkey::from_str(value).map_err(|_| format!("invalid pubkey for {}: {}", name, value))?)),
+        None => Ok(None),
+    }
+}
+
+fn required_pubkey(matches: &ArgMatches, name: &str) -> CliResult<Pubkey> {
+    pubkey(matches, name)?.ok_or_else(|| format!("missing {}", name).into())
+}
+
+fn keypair(path: &str) -> CliResult<Keypair> {
+    Ok(read_keypair_file(path).map_err(|err| format!("reading keypair {}: {}", path, err))?)
+}
+
+fn run(matches: &ArgMatches) -> CliResult<()> {
+    let (command, sub_matches) = matches.subcommand();
+    let sub_matches = sub_matches.ok_or("missing subcommand")?;
+    let keypair_path = match sub_matches.value_of("keypair") {
+        Some(path) => path.to_string(),
+        None => format!("{}/.config/solana/id.json", std::env::var("HOME")?),
+    };
+    let config = Config {
+        rpc: RpcClient::new_with_commitment(sub_matches.value_of("url").unwrap().to_string(), CommitmentConfig::confirmed()),
+        payer: keypair(&keypair_path)?,
+        program_id: required_pubkey(sub_matches, "program_id")?,
+    };
+
+    match command {
+        "init" => init(&config, sub_matches),
+        "deposit" => deposit(&config, sub_matches),
+        "withdraw" => withdraw(&config, sub_matches),
+        "exchange" => exchange(&config, sub_matches),
+        "set-rate" => set_rate(&config, sub_matches),
+        "close" => close(&config, sub_matches),
+        "show-booth" => show_booth(&config, sub_matches),
+        "show-oracle" => show_oracle(&config, sub_matches),
+        _ => unreachable!(),
+    }
+}
+
+fn send(config: &Config, instructions: &[Instruction], extra_signers: &[&Keypair]) -> CliResult<()> {
+    let mut signers = vec![&config.payer];
+    signers.extend_from_slice(extra_signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&config.payer.pubkey()),
+        &signers,
+        config.rpc.get_latest_blockhash()?,
+    );
+    let signature = config.rpc.send_and_confirm_transaction(&transaction)?;
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+// the booth account and both vault accounts
+fn fetch_booth(config: &Config, address: &Pubkey) -> CliResult<(Account, ExchangeBooth, [Account; 2])> {
+    let booth_account = config.rpc.get_account(address)?;
+    if booth_account.owner != config.program_id {
+        return Err(format!("{} is not owned by {}", address, config.program_id).into())
+    }
+    let booth = ExchangeBooth::try_from_slice(&booth_account.data)?;
+    let vault_a = config.rpc.get_account(&booth.vault_a)?;
+    let vault_b = config.rpc.get_account(&booth.vault_b)?;
+    Ok((booth_account, booth, [vault_a, vault_b]))
+}
+
+// the booth and its addresses, mints come from the vaults
+fn load_booth(config: &Config, address: &Pubkey) -> CliResult<(ExchangeBooth, BoothAccounts)> {
+    let (_, booth, [vault_a, vault_b]) = fetch_booth(config, address)?;
+    let mint_a = token::unpack_account(&vault_a.data)?.mint;
+    let mint_b = token::unpack_account(&vault_b.data)?.mint;
+    let accounts = BoothAccounts::from_booth(address, &booth, &mint_a, &mint_b, &vault_a.owner);
+    Ok((booth, accounts))
+}
+
+fn check_booth_mint(booth: &BoothAccounts, mint: &Pubkey) -> CliResult<()> {
+    if *mint != booth.mint_a && *mint != booth.mint_b {
+        return Err(format!("{} is not a mint of booth {}", mint, booth.exchange_booth).into())
+    }
+    Ok(())
+}
+
+fn init(config: &Config, matches: &ArgMatches) -> CliResult<()> {
+    let mint_a = required_pubkey(matches, "mint_a")?;
+    let mint_b = required_pubkey(matches, "mint_b")?;
+    let index = value_t!(matches, "index", u64)?;
+    let token_program = config.rpc.get_account(&mint_a)?.owner;
+
+    let mut instructions = vec![];
+    let mut extra_signers = vec![];
+    let oracle_keypair = matches.value_of("oracle_keypair").map(keypair).transpose()?;
+    let oracle = match &oracle_keypair {
+        None => required_pubkey(matches, "oracle")?,
+        Some(oracle_keypair) => {
+            instructions.extend(client::create_oracle(
+                &config.program_id,
+                &config.payer.pubkey(),
+                &oracle_keypair.pubkey(),
+                config.rpc.get_minimum_balance_for_rent_exemption(client::ORACLE_WITH_HISTORY_LEN)?,
+                value_t!(matches, "rate", f64)?,
+            ));
+            extra_signers.push(oracle_keypair);
+            oracle_keypair.pubkey()
+        }
+    };
+    let booth = BoothAccounts::new(&config.program_id, &config.payer.pubkey(), &oracle, &mint_a, &mint_b, index, &token_program);
+    instructions.push(client::initialize_exchange_booth(&config.program_id, &booth, index));
+
+    send(config, &instructions, &extra_signers)?;
+    println!("booth: {}", booth.exchange_booth);
+    println!("oracle: {}", booth.oracle);
+    println!("vault a: {}", booth.vault_a);
+    println!("vault b: {}", booth.vault_b);
+    Ok(())
+}
+
+// Deposit has no instruction of its own. While nobody holds lp shares the tokens are transferred
+// straight into the vault, the admin's first AddLiquidity counts them as the admin's. After that a
+// transfer would be split between the share holders, so the deposit goes through AddLiquidity.
+fn deposit(config: &Config, matches: &ArgMatches) -> CliResult<()> {
+    let (_, booth) = load_booth(config, &required_pubkey(matches, "booth")?)?;
+    let mint = required_pubkey(matches, "mint")?;
+    check_booth_mint(&booth, &mint)?;
+    let payer = config.payer.pubkey();
+    let from = pubkey(matches, "from")?
+        .unwrap_or_else(|| token::associated_token_address(&payer, &mint, &booth.token_program));
+    let ui_amount = value_t!(matches, "amount", f64)?;
+
+    if token::unpack_mint(&config.rpc.get_account_data(&booth.lp_mint)?)?.supply > 0 {
+        // AddLiquidity takes both sides, the other one is left at 0 but its account must exist
+        let other_mint = if mint == booth.mint_a { booth.mint_b } else { booth.mint_a };
+        let other = token::associated_token_address(&payer, &other_mint, &booth.token_program);
+        let lp = token::associated_token_address(&payer, &booth.lp_mint, &booth.token_program);
+        let existing = config.rpc.get_multiple_accounts(&[other, lp])?;
+        for ((account, account_mint), existing) in [(other, other_mint), (lp, booth.lp_mint)].into_iter().zip(existing) {
+            if existing.is_none() {
+                return Err(format!("booth has lp shares, deposit needs a token account {} for {}", account, account_mint).into())
+            }
+        }
+        let (token_a, token_b, amount_a, amount_b) = if mint == booth.mint_a {
+            (from, other, ui_amount, 0.0)
+        } else {
+            (other, from, 0.0, ui_amount)
+        };
+        return send(config, &[client::add_liquidity(&config.program_id, &booth, &payer, &token_a, &token_b, &lp, amount_a, amount_b)], &[])
+    }
+
+    let decimals = token::unpack_mint(&config.rpc.get_account_data(&mint)?)?.decimals;
+    // spl_token only builds for its own program id, Token-2022 encodes it the same way
+    let mut transfer = spl_token::instruction::transfer_checked(
+        &spl_token::id(),
+        &from,
+        &mint,
+        &booth.vault(&mint),
+        &payer,
+        &[],
+        pricing::to_base_units(ui_amount, decimals),
+        decimals,
+    )?;
+    transfer.program_id = booth.token_program;
+    send(config, &[transfer], &[])
+}
+
+fn withdraw(config: &Config, matches: &ArgMatches) -> CliResult<()> {
+    let (_, booth) = load_booth(config, &required_pubkey(matches, "booth")?)?;
+    let mint = required_pubkey(matches, "mint")?;
+    check_booth_mint(&booth, &mint)?;
+    let to = pubkey(matches, "to")?
+        .unwrap_or_else(|| token::associated_token_address(&config.payer.pubkey(), &mint, &booth.token_program));
+    let amount = value_t!(matches, "amount", f64)?;
+    send(config, &[client::withdraw(&config.program_id, &booth, &mint, &to, amount)], &[])
+}
+
+fn exchange(config: &Config, matches: &ArgMatches) -> CliResult<()> {
+    let (_, booth) = load_booth(config, &required_pubkey(matches, "booth")?)?;
+    let from_mint = required_pubkey(matches, "from_mint")?;
+    check_booth_mint(&booth, &from_mint)?;
+    let to_mint = if from_mint == booth.mint_a { booth.mint_b } else { booth.mint_a };
+    let customer = config.payer.pubkey();
+    let from = pubkey(matches, "from")?
+        .unwrap_or_else(|| token::associated_token_address(&customer, &from_mint, &booth.token_program));
+    let (to, create_destination) = match pubkey(matches, "to")? {
+        Some(to) => (to, false),
+        None => (token::associated_token_address(&customer, &to_mint, &booth.token_program), true),
+    };
+    let amount = value_t!(matches, "amount", f64)?;
+    send(config, &[client::exchange(&config.program_id, &booth, &customer, &from, &to, amount, create_destination)], &[])
+}
+
+fn set_rate(config: &Config, matches: &ArgMatches) -> CliResult<()> {
+    let oracle_keypair = keypair(matches.value_of("oracle_keypair").unwrap())?;
+    let rate = value_t!(matches, "rate", f64)?;
+    let confidence = value_t!(matches, "confidence", f64)?;
+    send(
+        config,
+        &[client::update_oracle_exchange_rate(&config.program_id, &oracle_keypair.pubkey(), rate, confidence)],
+        &[&oracle_keypair]
+    )
+}
+
+fn close(config: &Config, matches: &ArgMatches) -> CliResult<()> {
+    let (_, booth) = load_booth(config, &required_pubkey(matches, "booth")?)?;
+    send(config, &[client::close_exchange_booth(&config.program_id, &booth)], &[])
+}
+
+fn show_booth(config: &Config, matches: &ArgMatches) -> CliResult<()> {
+    let address = required_pubkey(matches, "booth")?;
+    let (booth_account, booth, [vault_a, vault_b]) = fetch_booth(config, &address)?;
+    // the rest in one request, the vaults already told us which mints they hold
+    let keys = [booth.oracle, token::unpack_account(&vault_a.data)?.mint, token::unpack_account(&vault_b.data)?.mint];
+    let data = config.rpc.get_multiple_accounts(&keys)?
+        .into_iter()
+        .zip(keys)
+        .map(|(account, key)| account.map(|account| account.data).ok_or_else(|| format!("account {} not found", key)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let summary = BoothSummary::decode(&address, &booth_account.data, &data[0], [&vault_a.data, &vault_b.data], [&data[1], &data[2]], now)?;
+    if matches.is_present("json") {
+        println!("{}", summary.to_json());
+    } else {
+        println!("{}", summary);
+    }
+    Ok(())
+}
+
+fn show_oracle(config: &Config, matches: &ArgMatches) -> CliResult<()> {
+    let data = config.rpc.get_account_data(&required_pubkey(matches, "oracle")?)?;
+    let (exchange_rate, history) = oracle::unpack_exchange_rate(&data)?;
+    println!("{:#?}", exchange_rate);
+    if let Some(history) = history {
+        println!("{:#?}", history);
+    }
+    Ok(())
+}
@@ -0,0 +1,713 @@
+// Instruction builders for off-chain callers. Each one derives the PDAs an instruction needs
+// and lays out the accounts in the order of its table in instruction.rs, so clients don't
+// have to repeat them.
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_instruction,
+    system_program,
+    sysvar,
+};
+
+use crate::{
+    instruction::{ExchangeBoothInstruction, Quote, TradeDirection},
+    processor::{registry, token},
+    state::{ExchangeBooth, OracleSource, EXCHANGE_RATE_LEN, RATE_HISTORY_LEN},
+};
+
+
+pub fn exchange_booth_address(program_id: &Pubkey, admin: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"exchange_booth", admin.as_ref(), mint_a.as_ref(), mint_b.as_ref(), &index.to_le_bytes()],
+        program_id,
+    )
+}
+
+// vaults of both single pair and multi asset booths
+pub fn vault_address(program_id: &Pubkey, admin: &Pubkey, booth: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"exchange_booth", admin.as_ref(), booth.as_ref(), mint.as_ref()], program_id)
+}
+
+pub fn lp_mint_address(program_id: &Pubkey, admin: &Pubkey, exchange_booth: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"exchange_booth", admin.as_ref(), exchange_booth.as_ref(), b"lp_mint"], program_id)
+}
+
+pub fn booth_registry_address(program_id: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> (Pubkey, u8) {
+    registry::find_address(program_id, mint_a, mint_b)
+}
+
+pub fn quote_nonce_address(program_id: &Pubkey, exchange_booth: &Pubkey, customer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"quote_nonce", exchange_booth.as_ref(), customer.as_ref()], program_id)
+}
+
+pub fn limit_order_address(program_id: &Pubkey, exchange_booth: &Pubkey, owner: &Pubkey, order_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"limit_order", exchange_booth.as_ref(), owner.as_ref(), &order_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+pub fn limit_order_escrow_address(program_id: &Pubkey, limit_order: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"limit_order_escrow", limit_order.as_ref()], program_id)
+}
+
+pub fn dca_schedule_address(program_id: &Pubkey, exchange_booth: &Pubkey, owner: &Pubkey, schedule_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"dca_schedule", exchange_booth.as_ref(), owner.as_ref(), &schedule_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+pub fn dca_escrow_address(program_id: &Pubkey, dca_schedule: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dca_escrow", dca_schedule.as_ref()], program_id)
+}
+
+pub fn wsol_address(program_id: &Pubkey, exchange_booth: &Pubkey, customer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"wsol", exchange_booth.as_ref(), customer.as_ref()], program_id)
+}
+
+/// Addresses of a single pair booth, everything but the oracle is derived.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoothAccounts {
+    pub admin: Pubkey,
+    pub exchange_booth: Pubkey,
+    pub oracle: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub lp_mint: Pubkey,
+    // spl-token or Token-2022, whichever owns the mints
+    pub token_program: Pubkey
+}
+
+impl BoothAccounts {
+    pub fn new(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        oracle: &Pubkey,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        index: u64,
+        token_program: &Pubkey,
+    ) -> Self {
+        let exchange_booth = exchange_booth_address(program_id, admin, mint_a, mint_b, index).0;
+        BoothAccounts {
+            admin: *admin,
+            exchange_booth,
+            oracle: *oracle,
+            vault_a: vault_address(program_id, admin, &exchange_booth, mint_a).0,
+            vault_b: vault_address(program_id, admin, &exchange_booth, mint_b).0,
+            mint_a: *mint_a,
+            mint_b: *mint_b,
+            lp_mint: lp_mint_address(program_id, admin, &exchange_booth).0,
+            token_program: *token_program,
+        }
+    }
+
+    // for a booth already on chain, `token_program` is the owner of its vaults
+    pub fn from_booth(exchange_booth: &Pubkey, booth: &ExchangeBooth, mint_a: &Pubkey, mint_b: &Pubkey, token_program: &Pubkey) -> Self {
+        BoothAccounts {
+            admin: booth.admin,
+            exchange_booth: *exchange_booth,
+            oracle: booth.oracle,
+            vault_a: booth.vault_a,
+            vault_b: booth.vault_b,
+            mint_a: *mint_a,
+            mint_b: *mint_b,
+            lp_mint: booth.lp_mint,
+            token_program: *token_program,
+        }
+    }
+
+    pub fn vault(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.mint_a {
+            self.vault_a
+        } else {
+            self.vault_b
+        }
+    }
+
+    // the first six accounts of Exchange and of every instruction that trades like it
+    fn trade_accounts(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.exchange_booth, false),
+            AccountMeta::new_readonly(self.oracle, false),
+            AccountMeta::new(self.vault_a, false),
+            AccountMeta::new(self.vault_b, false),
+            AccountMeta::new_readonly(self.mint_a, false),
+            AccountMeta::new_readonly(self.mint_b, false),
+        ]
+    }
+}
+
+/// Addresses of a multi asset booth, vaults are derived per mint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiAssetBoothAccounts {
+    pub admin: Pubkey,
+    pub multi_asset_booth: Pubkey,
+    pub oracle: Pubkey,
+    pub token_program: Pubkey
+}
+
+impl MultiAssetBoothAccounts {
+    pub fn vault(&self, program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+        vault_address(program_id, &self.admin, &self.multi_asset_booth, mint).0
+    }
+}
+
+/// Accounts of one order passed to FillLimitOrders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitOrderAccounts {
+    pub limit_order: Pubkey,
+    pub owner: Pubkey,
+    pub owner_from_token_account: Pubkey,
+    pub owner_to_token_account: Pubkey
+}
+
+pub fn initialize_exchange_booth(program_id: &Pubkey, booth: &BoothAccounts, index: u64) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::InititializeExchangeBooth { index },
+        vec![
+            AccountMeta::new(booth.exchange_booth, false),
+            AccountMeta::new_readonly(booth.oracle, false),
+            AccountMeta::new(booth.vault_a, false),
+            AccountMeta::new(booth.vault_b, false),
+            AccountMeta::new_readonly(booth.mint_a, false),
+            AccountMeta::new_readonly(booth.mint_b, false),
+            AccountMeta::new(booth.admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(booth.token_program, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(booth.lp_mint, false),
+            AccountMeta::new(booth_registry_address(program_id, &booth.mint_a, &booth.mint_b).0, false),
+        ],
+    )
+}
+
+pub fn withdraw(program_id: &Pubkey, booth: &BoothAccounts, mint: &Pubkey, user_token_account: &Pubkey, amount: f64) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::Withdraw { amount },
+        vec![
+            AccountMeta::new_readonly(booth.exchange_booth, false),
+            AccountMeta::new(booth.vault(mint), false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new_readonly(booth.admin, true),
+            AccountMeta::new_readonly(booth.token_program, false),
+            AccountMeta::new_readonly(booth.lp_mint, false),
+        ],
+    )
+}
+
+// With `create_destination` the customer's associated token account for the bought mint is
+// passed as customer_to_token_account and created if missing.
+pub fn exchange(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    customer: &Pubkey,
+    customer_from_token_account: &Pubkey,
+    customer_to_token_account: &Pubkey,
+    amount: f64,
+    create_destination: bool,
+) -> Instruction {
+    let mut accounts = booth.trade_accounts();
+    accounts.extend([
+        // pays for the destination account when it has to be created
+        if create_destination { AccountMeta::new(*customer, true) } else { AccountMeta::new_readonly(*customer, true) },
+        AccountMeta::new(*customer_from_token_account, false),
+        AccountMeta::new(*customer_to_token_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(booth.token_program, false),
+    ]);
+    if create_destination {
+        accounts.push(AccountMeta::new_readonly(token::associated_token::id(), false));
+    }
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::Exchange { amount }, accounts)
+}
+
+pub fn close_exchange_booth(program_id: &Pubkey, booth: &BoothAccounts) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::CloseExchangeBooth {},
+        vec![
+            AccountMeta::new(booth.exchange_booth, false),
+            AccountMeta::new(booth.vault_a, false),
+            AccountMeta::new(booth.vault_b, false),
+            AccountMeta::new(booth.admin, true),
+            AccountMeta::new(booth_registry_address(program_id, &booth.mint_a, &booth.mint_b).0, false),
+            AccountMeta::new_readonly(booth.token_program, false),
+            AccountMeta::new(booth.lp_mint, false),
+        ],
+    )
+}
+
+pub fn update_oracle_exchange_rate(program_id: &Pubkey, oracle: &Pubkey, exchange_rate_a_to_b: f64, confidence: f64) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::UpdateOracleExchangeRate { exchange_rate_a_to_b, confidence },
+        vec![AccountMeta::new(*oracle, true)],
+    )
+}
+
+pub const ORACLE_WITH_HISTORY_LEN: usize = EXCHANGE_RATE_LEN + RATE_HISTORY_LEN;
+
+// Creates a pushed oracle with room for its rate history, so a booth can switch to TWAP pricing
+// later, and writes its first rate. `oracle` signs both, `lamports` is the rent exemption of
+// ORACLE_WITH_HISTORY_LEN bytes.
+pub fn create_oracle(program_id: &Pubkey, payer: &Pubkey, oracle: &Pubkey, lamports: u64, exchange_rate_a_to_b: f64) -> Vec<Instruction> {
+    vec![
+        system_instruction::create_account(payer, oracle, lamports, ORACLE_WITH_HISTORY_LEN as u64, program_id),
+        update_oracle_exchange_rate(program_id, oracle, exchange_rate_a_to_b, 0.0),
+    ]
+}
+
+pub fn set_inventory_skew(program_id: &Pubkey, booth: &BoothAccounts, target_ratio: f64, sensitivity: f64) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::SetInventorySkew { target_ratio, sensitivity },
+        vec![
+            AccountMeta::new(booth.exchange_booth, false),
+            AccountMeta::new_readonly(booth.admin, true),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_liquidity(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    provider: &Pubkey,
+    provider_token_a_account: &Pubkey,
+    provider_token_b_account: &Pubkey,
+    provider_lp_token_account: &Pubkey,
+    amount_a: f64,
+    amount_b: f64,
+) -> Instruction {
+    let mut accounts = booth.trade_accounts();
+    accounts.extend([
+        AccountMeta::new(booth.lp_mint, false),
+        AccountMeta::new_readonly(*provider, true),
+        AccountMeta::new(*provider_token_a_account, false),
+        AccountMeta::new(*provider_token_b_account, false),
+        AccountMeta::new(*provider_lp_token_account, false),
+        AccountMeta::new_readonly(booth.token_program, false),
+    ]);
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::AddLiquidity { amount_a, amount_b }, accounts)
+}
+
+pub fn remove_liquidity(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    provider: &Pubkey,
+    provider_token_a_account: &Pubkey,
+    provider_token_b_account: &Pubkey,
+    provider_lp_token_account: &Pubkey,
+    amount: f64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::RemoveLiquidity { amount },
+        vec![
+            AccountMeta::new_readonly(booth.exchange_booth, false),
+            AccountMeta::new(booth.vault_a, false),
+            AccountMeta::new(booth.vault_b, false),
+            AccountMeta::new_readonly(booth.mint_a, false),
+            AccountMeta::new_readonly(booth.mint_b, false),
+            AccountMeta::new(booth.lp_mint, false),
+            AccountMeta::new_readonly(*provider, true),
+            AccountMeta::new(*provider_token_a_account, false),
+            AccountMeta::new(*provider_token_b_account, false),
+            AccountMeta::new(*provider_lp_token_account, false),
+            AccountMeta::new_readonly(booth.token_program, false),
+        ],
+    )
+}
+
+// `remaining_accounts` are passed through to the borrower program as is
+#[allow(clippy::too_many_arguments)]
+pub fn flash_loan(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    mint: &Pubkey,
+    borrower_token_account: &Pubkey,
+    borrower_program: &Pubkey,
+    remaining_accounts: &[AccountMeta],
+    amount: f64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(booth.exchange_booth, false),
+        AccountMeta::new(booth.vault(mint), false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(*borrower_token_account, false),
+        AccountMeta::new_readonly(*borrower_program, false),
+        AccountMeta::new_readonly(booth.token_program, false),
+    ];
+    accounts.extend_from_slice(remaining_accounts);
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::FlashLoan { amount, mint: *mint }, accounts)
+}
+
+// The caller still has to put the Ed25519 program instruction with the oracle's signature
+// over `quote` right before this one.
+pub fn exchange_with_quote(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    customer_from_token_account: &Pubkey,
+    customer_to_token_account: &Pubkey,
+    amount: f64,
+    quote: Quote,
+) -> Instruction {
+    let mut accounts = booth.trade_accounts();
+    accounts.extend([
+        AccountMeta::new(quote.customer, true),
+        AccountMeta::new(*customer_from_token_account, false),
+        AccountMeta::new(*customer_to_token_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(booth.token_program, false),
+        AccountMeta::new(quote_nonce_address(program_id, &booth.exchange_booth, &quote.customer).0, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+    ]);
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::ExchangeWithQuote { amount, quote }, accounts)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn place_limit_order(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    owner: &Pubkey,
+    from_mint: &Pubkey,
+    owner_from_token_account: &Pubkey,
+    owner_to_token_account: &Pubkey,
+    order_id: u64,
+    amount: f64,
+    min_rate: f64,
+    tip_lamports: u64,
+) -> Instruction {
+    let limit_order = limit_order_address(program_id, &booth.exchange_booth, owner, order_id).0;
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::PlaceLimitOrder { order_id, amount, min_rate, tip_lamports },
+        vec![
+            AccountMeta::new_readonly(booth.exchange_booth, false),
+            AccountMeta::new_readonly(booth.vault_a, false),
+            AccountMeta::new_readonly(booth.vault_b, false),
+            AccountMeta::new(limit_order, false),
+            AccountMeta::new(limit_order_escrow_address(program_id, &limit_order).0, false),
+            AccountMeta::new_readonly(*from_mint, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*owner_from_token_account, false),
+            AccountMeta::new_readonly(*owner_to_token_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(booth.token_program, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    )
+}
+
+pub fn fill_limit_orders(program_id: &Pubkey, booth: &BoothAccounts, cranker: &Pubkey, orders: &[LimitOrderAccounts]) -> Instruction {
+    let mut accounts = booth.trade_accounts();
+    accounts.extend([
+        AccountMeta::new(*cranker, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(booth.token_program, false),
+    ]);
+    for order in orders {
+        accounts.extend([
+            AccountMeta::new(order.limit_order, false),
+            AccountMeta::new(limit_order_escrow_address(program_id, &order.limit_order).0, false),
+            AccountMeta::new(order.owner, false),
+            AccountMeta::new(order.owner_from_token_account, false),
+            AccountMeta::new(order.owner_to_token_account, false),
+        ]);
+    }
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::FillLimitOrders {}, accounts)
+}
+
+pub fn cancel_limit_order(
+    program_id: &Pubkey,
+    limit_order: &Pubkey,
+    owner: &Pubkey,
+    owner_from_token_account: &Pubkey,
+    from_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::CancelLimitOrder {},
+        vec![
+            AccountMeta::new(*limit_order, false),
+            AccountMeta::new(limit_order_escrow_address(program_id, limit_order).0, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*owner_from_token_account, false),
+            AccountMeta::new_readonly(*from_mint, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_dca_schedule(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    owner: &Pubkey,
+    from_mint: &Pubkey,
+    owner_from_token_account: &Pubkey,
+    owner_to_token_account: &Pubkey,
+    schedule_id: u64,
+    amount: f64,
+    interval_seconds: i64,
+    count: u64,
+) -> Instruction {
+    let dca_schedule = dca_schedule_address(program_id, &booth.exchange_booth, owner, schedule_id).0;
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::CreateDcaSchedule { schedule_id, amount, interval_seconds, count },
+        vec![
+            AccountMeta::new_readonly(booth.exchange_booth, false),
+            AccountMeta::new_readonly(booth.vault_a, false),
+            AccountMeta::new_readonly(booth.vault_b, false),
+            AccountMeta::new(dca_schedule, false),
+            AccountMeta::new(dca_escrow_address(program_id, &dca_schedule).0, false),
+            AccountMeta::new_readonly(*from_mint, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*owner_from_token_account, false),
+            AccountMeta::new_readonly(*owner_to_token_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(booth.token_program, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    )
+}
+
+pub fn execute_dca(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    dca_schedule: &Pubkey,
+    owner: &Pubkey,
+    owner_from_token_account: &Pubkey,
+    owner_to_token_account: &Pubkey,
+) -> Instruction {
+    let mut accounts = booth.trade_accounts();
+    accounts.extend([
+        AccountMeta::new(*dca_schedule, false),
+        AccountMeta::new(dca_escrow_address(program_id, dca_schedule).0, false),
+        AccountMeta::new(*owner, false),
+        AccountMeta::new(*owner_from_token_account, false),
+        AccountMeta::new(*owner_to_token_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(booth.token_program, false),
+    ]);
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::ExecuteDca {}, accounts)
+}
+
+pub fn cancel_dca_schedule(
+    program_id: &Pubkey,
+    dca_schedule: &Pubkey,
+    owner: &Pubkey,
+    owner_from_token_account: &Pubkey,
+    from_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::CancelDcaSchedule {},
+        vec![
+            AccountMeta::new(*dca_schedule, false),
+            AccountMeta::new(dca_escrow_address(program_id, dca_schedule).0, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*owner_from_token_account, false),
+            AccountMeta::new_readonly(*from_mint, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+    )
+}
+
+// `mints` in the order the oracle prices them
+pub fn initialize_multi_asset_booth(program_id: &Pubkey, booth: &MultiAssetBoothAccounts, mints: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(booth.multi_asset_booth, false),
+        AccountMeta::new(booth.oracle, false),
+        AccountMeta::new(booth.admin, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(booth.token_program, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    for mint in mints {
+        accounts.extend([
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(booth.vault(program_id, mint), false),
+        ]);
+    }
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::InitializeMultiAssetBooth {}, accounts)
+}
+
+pub fn update_multi_asset_oracle(program_id: &Pubkey, oracle: &Pubkey, authority: &Pubkey, prices: Vec<f64>) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::UpdateMultiAssetOracle { prices },
+        vec![
+            AccountMeta::new(*oracle, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+// `from` and `to` are (index in the booth, mint) of the assets traded
+#[allow(clippy::too_many_arguments)]
+pub fn exchange_multi_asset(
+    program_id: &Pubkey,
+    booth: &MultiAssetBoothAccounts,
+    from: (u8, &Pubkey),
+    to: (u8, &Pubkey),
+    customer: &Pubkey,
+    customer_from_token_account: &Pubkey,
+    customer_to_token_account: &Pubkey,
+    amount: f64,
+) -> Instruction {
+    let (from_index, from_mint) = from;
+    let (to_index, to_mint) = to;
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::ExchangeMultiAsset { amount, from_index, to_index },
+        vec![
+            AccountMeta::new_readonly(booth.multi_asset_booth, false),
+            AccountMeta::new_readonly(booth.oracle, false),
+            AccountMeta::new(booth.vault(program_id, from_mint), false),
+            AccountMeta::new(booth.vault(program_id, to_mint), false),
+            AccountMeta::new_readonly(*from_mint, false),
+            AccountMeta::new_readonly(*to_mint, false),
+            AccountMeta::new_readonly(*customer, true),
+            AccountMeta::new(*customer_from_token_account, false),
+            AccountMeta::new(*customer_to_token_account, false),
+            AccountMeta::new_readonly(booth.token_program, false),
+        ],
+    )
+}
+
+pub fn withdraw_multi_asset(
+    program_id: &Pubkey,
+    booth: &MultiAssetBoothAccounts,
+    asset_index: u8,
+    mint: &Pubkey,
+    user_token_account: &Pubkey,
+    amount: f64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::WithdrawMultiAsset { amount, asset_index },
+        vec![
+            AccountMeta::new_readonly(booth.multi_asset_booth, false),
+            AccountMeta::new(booth.vault(program_id, mint), false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new_readonly(booth.admin, true),
+            AccountMeta::new_readonly(booth.token_program, false),
+        ],
+    )
+}
+
+// trades through `first_booth` then each of `next_booths`, which must all use the token program of the first
+#[allow(clippy::too_many_arguments)]
+pub fn exchange_route(
+    program_id: &Pubkey,
+    first_booth: &BoothAccounts,
+    next_booths: &[BoothAccounts],
+    customer: &Pubkey,
+    customer_from_token_account: &Pubkey,
+    customer_to_token_account: &Pubkey,
+    amount: f64,
+    min_amount_out: f64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*customer, true),
+        AccountMeta::new(*customer_from_token_account, false),
+        AccountMeta::new(*customer_to_token_account, false),
+        AccountMeta::new_readonly(first_booth.token_program, false),
+    ];
+    for booth in std::iter::once(first_booth).chain(next_booths) {
+        accounts.extend(booth.trade_accounts());
+    }
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::ExchangeRoute { amount, min_amount_out }, accounts)
+}
+
+pub fn exchange_sol(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    customer: &Pubkey,
+    customer_token_account: &Pubkey,
+    amount: f64,
+    sell_sol: bool,
+) -> Instruction {
+    let mut accounts = booth.trade_accounts();
+    accounts.extend([
+        AccountMeta::new(*customer, true),
+        AccountMeta::new(*customer_token_account, false),
+        AccountMeta::new(wsol_address(program_id, &booth.exchange_booth, customer).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(booth.token_program, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ]);
+    Instruction::new_with_borsh(*program_id, &ExchangeBoothInstruction::ExchangeSol { amount, sell_sol }, accounts)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn set_oracle(
+    program_id: &Pubkey,
+    booth: &BoothAccounts,
+    new_oracle: &Pubkey,
+    source: OracleSource,
+    max_confidence_bps: u64,
+    max_price_age: i64,
+    twap_window: i64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::SetOracle { source, max_confidence_bps, max_price_age, twap_window },
+        vec![
+            AccountMeta::new(booth.exchange_booth, false),
+            AccountMeta::new_readonly(booth.admin, true),
+            AccountMeta::new_readonly(*new_oracle, false),
+        ],
+    )
+}
+
+pub fn initialize_aggregated_oracle(program_id: &Pubkey, oracle: &Pubkey, admin: &Pubkey, publishers: Vec<Pubkey>, quorum: u8) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::InitializeAggregatedOracle { publishers, quorum },
+        vec![
+            AccountMeta::new(*oracle, false),
+            AccountMeta::new_readonly(*admin, true),
+        ],
+    )
+}
+
+pub fn submit_oracle_price(program_id: &Pubkey, oracle: &Pubkey, publisher: &Pubkey, a_to_b: f64) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::SubmitOraclePrice { a_to_b },
+        vec![
+            AccountMeta::new(*oracle, false),
+            AccountMeta::new_readonly(*publisher, true),
+        ],
+    )
+}
+
+// read only, meant for simulateTransaction; the QuoteResult comes back as return data
+pub fn quote(program_id: &Pubkey, booth: &BoothAccounts, amount: f64, direction: TradeDirection) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &ExchangeBoothInstruction::Quote { amount, direction },
+        vec![
+            AccountMeta::new_readonly(booth.exchange_booth, false),
+            AccountMeta::new_readonly(booth.oracle, false),
+            AccountMeta::new_readonly(booth.vault_a, false),
+            AccountMeta::new_readonly(booth.vault_b, false),
+            AccountMeta::new_readonly(booth.mint_a, false),
+            AccountMeta::new_readonly(booth.mint_b, false),
+            AccountMeta::new_readonly(booth.token_program, false),
+        ],
+    )
+}
@@ -26,6 +26,46 @@ pub enum ExchangeBoothError {
     InvalidInstructionInput,
     #[error("Invalid account data.")]
     InvalidAccountData,
+    #[error("Flash loan not repaid.")]
+    FlashLoanNotRepaid,
+    #[error("Invalid quote signature.")]
+    InvalidQuoteSignature,
+    #[error("Quote expired.")]
+    QuoteExpired,
+    #[error("Quote nonce already used.")]
+    QuoteNonceReused,
+    #[error("Quote size exceeded.")]
+    QuoteSizeExceeded,
+    #[error("Limit order does not match the accounts passed.")]
+    LimitOrderMismatch,
+    #[error("DCA schedule does not match the accounts passed.")]
+    DcaScheduleMismatch,
+    #[error("DCA execution is not due yet.")]
+    DcaNotDue,
+    #[error("Exchange booth vaults must be empty.")]
+    BoothNotEmpty,
+    #[error("Output below the requested minimum.")]
+    SlippageExceeded,
+    #[error("Invalid token program.")]
+    InvalidTokenProgram,
+    #[error("Invalid system program.")]
+    InvalidSystemProgram,
+    #[error("Invalid associated token program.")]
+    InvalidAssociatedTokenProgram,
+    #[error("Invalid rent sysvar.")]
+    InvalidRentSysvar,
+    #[error("Token account or mint not owned by the token program.")]
+    InvalidTokenAccountOwner,
+    #[error("Oracle price is stale.")]
+    StaleOraclePrice,
+    #[error("Oracle price confidence interval too wide.")]
+    OracleConfidenceTooWide,
+    #[error("Not enough fresh oracle submissions.")]
+    OracleQuorumNotMet,
+    #[error("Liquidity providers still hold shares of the booth.")]
+    LiquidityOutstanding,
+    #[error("Trade direction does not match the quote.")]
+    QuoteDirectionMismatch,
 }
 
 impl From<ExchangeBoothError> for ProgramError {
@@ -0,0 +1,281 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    clock::Clock,
+    entrypoint::ProgramResult,
+    log::sol_log_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::state::OracleSource;
+
+// first byte of every event, bump it whenever Event or EventData change layout
+pub const EVENT_VERSION: u8 = 1;
+
+// prefix the runtime puts in front of sol_log_data output in the transaction logs
+const LOG_DATA_PREFIX: &str = "Program data: ";
+
+/// Logged by every state-changing instruction as `[EVENT_VERSION] ++ borsh(Event)`, see `decode_log`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Event {
+    pub slot: u64,
+    pub data: EventData
+}
+
+/// Token amounts are in base units of their mint.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum EventData {
+    Trade {
+        booth: Pubkey,
+        // the account that signed for the from tokens, an escrow for limit orders and DCA
+        customer: Pubkey,
+        from_mint: Pubkey,
+        to_mint: Pubkey,
+        // sent by the customer
+        amount_in: u64,
+        // received by the customer
+        amount_out: u64,
+        rate: f64,
+        // Token-2022 transfer fees withheld on the way into the booth, in from_mint
+        fee_in: u64,
+        // Token-2022 transfer fees withheld on the way to the customer, in to_mint
+        fee_out: u64
+    },
+    BoothInitialized {
+        booth: Pubkey,
+        admin: Pubkey,
+        mints: Vec<Pubkey>
+    },
+    BoothClosed {
+        booth: Pubkey,
+        admin: Pubkey
+    },
+    OracleSet {
+        booth: Pubkey,
+        oracle: Pubkey,
+        source: OracleSource
+    },
+    InventorySkewSet {
+        booth: Pubkey,
+        target_ratio: f64,
+        sensitivity: f64
+    },
+    OracleUpdated {
+        oracle: Pubkey,
+        // the signer of the update, the oracle's own key for pushed oracles. Always Some since
+        // pushed updates need the oracle keypair, kept optional so the layout doesn't change
+        publisher: Option<Pubkey>,
+        a_to_b: f64,
+        confidence: f64
+    },
+    AggregatedOracleInitialized {
+        oracle: Pubkey,
+        admin: Pubkey,
+        publishers: Vec<Pubkey>,
+        quorum: u8
+    },
+    MultiAssetOracleUpdated {
+        oracle: Pubkey,
+        prices: Vec<f64>
+    },
+    LiquidityAdded {
+        booth: Pubkey,
+        provider: Pubkey,
+        amount_a: u64,
+        amount_b: u64,
+        shares: u64
+    },
+    LiquidityRemoved {
+        booth: Pubkey,
+        provider: Pubkey,
+        amount_a: u64,
+        amount_b: u64,
+        shares: u64
+    },
+    Withdrawn {
+        booth: Pubkey,
+        admin: Pubkey,
+        mint: Pubkey,
+        amount: u64
+    },
+    FlashLoan {
+        booth: Pubkey,
+        borrower_program: Pubkey,
+        mint: Pubkey,
+        amount: u64,
+        fee: u64
+    },
+    LimitOrderPlaced {
+        booth: Pubkey,
+        order: Pubkey,
+        owner: Pubkey,
+        from_mint: Pubkey,
+        amount: u64,
+        min_rate: f64
+    },
+    LimitOrderClosed {
+        order: Pubkey,
+        owner: Pubkey,
+        // false when the owner cancelled it
+        filled: bool
+    },
+    DcaScheduleCreated {
+        booth: Pubkey,
+        schedule: Pubkey,
+        owner: Pubkey,
+        from_mint: Pubkey,
+        // per execution
+        amount: u64,
+        count: u64
+    },
+    DcaScheduleCompleted {
+        schedule: Pubkey,
+        owner: Pubkey
+    },
+    DcaScheduleCancelled {
+        schedule: Pubkey,
+        owner: Pubkey,
+        // executions that will no longer run
+        remaining: u64
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventDecodeError {
+    NotEventData,
+    UnsupportedVersion(u8),
+    InvalidData,
+}
+
+pub(crate) fn emit(data: EventData) -> ProgramResult {
+    let event = Event {
+        slot: Clock::get()?.slot,
+        data
+    };
+    sol_log_data(&[&encode(&event)?]);
+    Ok(())
+}
+
+// the single sol_log_data field emit logs, the inverse of decode
+fn encode(event: &Event) -> Result<Vec<u8>, ProgramError> {
+    let mut bytes = vec![EVENT_VERSION];
+    event.serialize(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Decodes the bytes of a single sol_log_data field.
+pub fn decode(bytes: &[u8]) -> Result<Event, EventDecodeError> {
+    match bytes.split_first() {
+        Some((&EVENT_VERSION, event)) => Event::try_from_slice(event).map_err(|_| EventDecodeError::InvalidData),
+        Some((version, _)) => Err(EventDecodeError::UnsupportedVersion(*version)),
+        None => Err(EventDecodeError::InvalidData),
+    }
+}
+
+/// Decodes a `Program data: <base64>` line from the transaction logs. Lines that are not
+/// log data fail with `NotEventData`, so callers can run it over every log line of this program.
+pub fn decode_log(line: &str) -> Result<Event, EventDecodeError> {
+    let encoded = line.strip_prefix(LOG_DATA_PREFIX).ok_or(EventDecodeError::NotEventData)?;
+    let bytes = base64::decode(encoded.trim()).map_err(|_| EventDecodeError::InvalidData)?;
+    decode(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the event as a processor would log it, read back the way an indexer reads the transaction logs
+    fn assert_round_trips(data: EventData) {
+        let event = Event { slot: 42, data };
+        let line = format!("{}{}", LOG_DATA_PREFIX, base64::encode(encode(&event).unwrap()));
+        assert_eq!(decode_log(&line), Ok(event));
+    }
+
+    fn key(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    #[test]
+    fn trade_round_trips() {
+        assert_round_trips(EventData::Trade {
+            booth: key(1),
+            customer: key(2),
+            from_mint: key(3),
+            to_mint: key(4),
+            amount_in: 1_000_000,
+            amount_out: 1_980_000,
+            rate: 1.98,
+            fee_in: 0,
+            fee_out: 20_000,
+        });
+    }
+
+    #[test]
+    fn booth_lifecycle_round_trips() {
+        assert_round_trips(EventData::BoothInitialized { booth: key(1), admin: key(2), mints: vec![key(3), key(4), key(5)] });
+        assert_round_trips(EventData::BoothClosed { booth: key(1), admin: key(2) });
+        assert_round_trips(EventData::InventorySkewSet { booth: key(1), target_ratio: 0.5, sensitivity: 2.0 });
+    }
+
+    #[test]
+    fn oracle_round_trips() {
+        assert_round_trips(EventData::OracleSet { booth: key(1), oracle: key(2), source: OracleSource::Aggregated });
+        assert_round_trips(EventData::OracleUpdated { oracle: key(2), publisher: Some(key(2)), a_to_b: 2.0, confidence: 0.01 });
+        assert_round_trips(EventData::AggregatedOracleInitialized {
+            oracle: key(2),
+            admin: key(3),
+            publishers: vec![key(4), key(5)],
+            quorum: 2,
+        });
+        assert_round_trips(EventData::MultiAssetOracleUpdated { oracle: key(2), prices: vec![1.0, 2.5, 0.1] });
+    }
+
+    #[test]
+    fn liquidity_round_trips() {
+        assert_round_trips(EventData::LiquidityAdded { booth: key(1), provider: key(2), amount_a: 10, amount_b: 20, shares: 30 });
+        assert_round_trips(EventData::LiquidityRemoved { booth: key(1), provider: key(2), amount_a: 5, amount_b: 10, shares: 15 });
+        assert_round_trips(EventData::Withdrawn { booth: key(1), admin: key(2), mint: key(3), amount: u64::MAX });
+    }
+
+    #[test]
+    fn flash_loan_round_trips() {
+        assert_round_trips(EventData::FlashLoan { booth: key(1), borrower_program: key(2), mint: key(3), amount: 1_000, fee: 1 });
+    }
+
+    #[test]
+    fn limit_order_round_trips() {
+        assert_round_trips(EventData::LimitOrderPlaced {
+            booth: key(1),
+            order: key(2),
+            owner: key(3),
+            from_mint: key(4),
+            amount: 500,
+            min_rate: 1.5,
+        });
+        assert_round_trips(EventData::LimitOrderClosed { order: key(2), owner: key(3), filled: true });
+    }
+
+    #[test]
+    fn dca_round_trips() {
+        assert_round_trips(EventData::DcaScheduleCreated {
+            booth: key(1),
+            schedule: key(2),
+            owner: key(3),
+            from_mint: key(4),
+            amount: 100,
+            count: 12,
+        });
+        assert_round_trips(EventData::DcaScheduleCompleted { schedule: key(2), owner: key(3) });
+        assert_round_trips(EventData::DcaScheduleCancelled { schedule: key(2), owner: key(3), remaining: 4 });
+    }
+
+    #[test]
+    fn decode_log_rejects_other_lines_and_versions() {
+        assert_eq!(decode_log("Program log: Exchanged"), Err(EventDecodeError::NotEventData));
+        let mut bytes = encode(&Event { slot: 1, data: EventData::BoothClosed { booth: key(1), admin: key(2) } }).unwrap();
+        assert_eq!(decode(&bytes[..bytes.len() - 1]), Err(EventDecodeError::InvalidData));
+        bytes[0] = EVENT_VERSION + 1;
+        assert_eq!(decode(&bytes), Err(EventDecodeError::UnsupportedVersion(EVENT_VERSION + 1)));
+    }
+}
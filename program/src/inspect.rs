@@ -0,0 +1,280 @@
+// Human readable view of a booth for off-chain tools. It only decodes account data, so callers
+// fetch the accounts with whatever RPC client they use: the booth, ExchangeBooth::oracle,
+// both vaults and the mints the vaults hold.
+use std::fmt;
+
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    pricing,
+    processor::{oracle, token},
+    state::{ExchangeBooth, ExchangeRate},
+};
+
+use borsh::BorshDeserialize;
+
+
+#[derive(Debug, Clone)]
+pub struct BoothSummary {
+    pub address: Pubkey,
+    pub booth: ExchangeBooth,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+    // vault balances in base units
+    pub balance_a: u64,
+    pub balance_b: u64,
+    // the oracle's rate at the time passed to `decode`, before the booth's skew and confidence
+    // spread, or why Exchange would refuse to price with it
+    pub exchange_rate: Result<ExchangeRate, ProgramError>
+}
+
+impl BoothSummary {
+    // `vaults` and `mints` in A, B order, `now` is a unix timestamp
+    pub fn decode(
+        address: &Pubkey,
+        booth_data: &[u8],
+        oracle_data: &[u8],
+        vaults: [&[u8]; 2],
+        mints: [&[u8]; 2],
+        now: i64,
+    ) -> Result<Self, ProgramError> {
+        let booth = ExchangeBooth::try_from_slice(booth_data).map_err(|_| ProgramError::InvalidAccountData)?;
+        let vault_a = token::unpack_account(vaults[0])?;
+        let vault_b = token::unpack_account(vaults[1])?;
+        let exchange_rate = oracle::rate_from_data(&booth, oracle_data, now);
+        Ok(BoothSummary {
+            address: *address,
+            mint_a: vault_a.mint,
+            mint_b: vault_b.mint,
+            decimals_a: token::unpack_mint(mints[0])?.decimals,
+            decimals_b: token::unpack_mint(mints[1])?.decimals,
+            balance_a: vault_a.amount,
+            balance_b: vault_b.amount,
+            exchange_rate,
+            booth,
+        })
+    }
+
+    pub fn ui_balance_a(&self) -> f64 {
+        pricing::to_ui_amount(self.balance_a, self.decimals_a)
+    }
+
+    pub fn ui_balance_b(&self) -> f64 {
+        pricing::to_ui_amount(self.balance_b, self.decimals_b)
+    }
+
+    // the rate Exchange would give for selling A (`exchange_from_a`) or B, with the booth's skew
+    // and the oracle's confidence applied to the current vaults
+    pub fn booth_rate(&self, exchange_from_a: bool) -> Option<f64> {
+        let exchange_rate = self.exchange_rate.as_ref().ok()?;
+        Some(pricing::booth_rate(&self.booth, exchange_rate, self.ui_balance_a(), self.ui_balance_b(), exchange_from_a))
+    }
+
+    // both vaults valued in B at the oracle rate
+    pub fn reserves_value_in_b(&self) -> Option<f64> {
+        let exchange_rate = self.exchange_rate.as_ref().ok()?;
+        Some(self.ui_balance_a() * exchange_rate.a_to_b + self.ui_balance_b())
+    }
+
+    pub fn to_json(&self) -> String {
+        let (a_to_b, confidence, oracle_error) = match &self.exchange_rate {
+            Ok(exchange_rate) => (json_number(exchange_rate.a_to_b), json_number(exchange_rate.confidence), "null".to_string()),
+            Err(err) => ("null".to_string(), "null".to_string(), json_string(&err.to_string())),
+        };
+        format!(
+            concat!(
+                "{{\"address\":\"{}\",\"admin\":\"{}\",\"oracle\":\"{}\",\"oracle_source\":\"{:?}\",",
+                "\"vault_a\":\"{}\",\"vault_b\":\"{}\",\"mint_a\":\"{}\",\"mint_b\":\"{}\",\"lp_mint\":\"{}\",",
+                "\"decimals_a\":{},\"decimals_b\":{},\"balance_a\":{},\"balance_b\":{},",
+                "\"ui_balance_a\":{},\"ui_balance_b\":{},\"a_to_b\":{},\"confidence\":{},\"oracle_error\":{},",
+                "\"booth_rate_a_to_b\":{},\"booth_rate_b_to_a\":{},",
+                "\"reserves_value_in_b\":{},\"skew_target_ratio\":{},\"skew_sensitivity\":{},",
+                "\"max_confidence_bps\":{},\"max_price_age\":{},\"twap_window\":{}}}"
+            ),
+            self.address,
+            self.booth.admin,
+            self.booth.oracle,
+            self.booth.oracle_source,
+            self.booth.vault_a,
+            self.booth.vault_b,
+            self.mint_a,
+            self.mint_b,
+            self.booth.lp_mint,
+            self.decimals_a,
+            self.decimals_b,
+            self.balance_a,
+            self.balance_b,
+            json_number(self.ui_balance_a()),
+            json_number(self.ui_balance_b()),
+            a_to_b,
+            confidence,
+            oracle_error,
+            self.booth_rate(true).map_or_else(|| "null".to_string(), json_number),
+            self.booth_rate(false).map_or_else(|| "null".to_string(), json_number),
+            self.reserves_value_in_b().map_or_else(|| "null".to_string(), json_number),
+            json_number(self.booth.skew_target_ratio),
+            json_number(self.booth.skew_sensitivity),
+            self.booth.max_confidence_bps,
+            self.booth.max_price_age,
+            self.booth.twap_window,
+        )
+    }
+}
+
+impl fmt::Display for BoothSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "booth:     {}", self.address)?;
+        writeln!(f, "admin:     {}", self.booth.admin)?;
+        writeln!(f, "oracle:    {} ({:?})", self.booth.oracle, self.booth.oracle_source)?;
+        writeln!(f, "token A:   {} in vault {}, {} decimals", self.mint_a, self.booth.vault_a, self.decimals_a)?;
+        writeln!(f, "token B:   {} in vault {}, {} decimals", self.mint_b, self.booth.vault_b, self.decimals_b)?;
+        writeln!(f, "balances:  {} A, {} B", self.ui_balance_a(), self.ui_balance_b())?;
+        match &self.exchange_rate {
+            Ok(exchange_rate) => {
+                writeln!(f, "rate:      oracle 1 A = {} B, confidence {} bps", exchange_rate.a_to_b, exchange_rate.confidence * 10_000.)?;
+            }
+            Err(err) => writeln!(f, "rate:      none, the oracle can't price a trade ({})", err)?,
+        }
+        if let (Some(a_to_b), Some(b_to_a)) = (self.booth_rate(true), self.booth_rate(false)) {
+            writeln!(f, "exchange:  1 A sells for {} B, 1 B sells for {} A", a_to_b, b_to_a)?;
+        }
+        if let Some(value) = self.reserves_value_in_b() {
+            writeln!(f, "reserves:  {} B", value)?;
+        }
+        writeln!(f, "skew:      target ratio {}, sensitivity {}", self.booth.skew_target_ratio, self.booth.skew_sensitivity)?;
+        write!(
+            f,
+            "limits:    max confidence {} bps, max price age {}s, TWAP window {}s",
+            self.booth.max_confidence_bps,
+            self.booth.max_price_age,
+            self.booth.twap_window
+        )
+    }
+}
+
+// JSON has no NaN or infinity
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{OracleSource, EXCHANGE_BOOTH_LEN, EXCHANGE_RATE_LEN};
+    use borsh::BorshSerialize;
+    use solana_program::program_pack::Pack;
+    use spl_token::state::{Account, AccountState, Mint};
+
+    struct Accounts {
+        booth: Vec<u8>,
+        oracle: Vec<u8>,
+        vaults: [Vec<u8>; 2],
+        mints: [Vec<u8>; 2],
+    }
+
+    // 1000 A with 6 decimals and 1000 B with 9 decimals, priced by a pushed oracle at 2 B per A
+    fn accounts(skew_sensitivity: f64, confidence: f64) -> Accounts {
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut booth = vec![0; EXCHANGE_BOOTH_LEN];
+        ExchangeBooth {
+            admin: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            vault_a: Pubkey::new_unique(),
+            vault_b: Pubkey::new_unique(),
+            lp_mint: Pubkey::new_unique(),
+            skew_target_ratio: 1.0,
+            skew_sensitivity,
+            oracle_source: OracleSource::Pushed,
+            max_confidence_bps: 0,
+            max_price_age: 0,
+            twap_window: 0,
+        }.serialize(&mut &mut booth[..]).unwrap();
+        let mut oracle = vec![0; EXCHANGE_RATE_LEN];
+        ExchangeRate { a_to_b: 2.0, b_to_a: 0.5, confidence }.serialize(&mut &mut oracle[..]).unwrap();
+        Accounts {
+            booth,
+            oracle,
+            vaults: [vault(&mint_a, 1_000_000_000), vault(&mint_b, 1_000_000_000_000)],
+            mints: [mint(6), mint(9)],
+        }
+    }
+
+    fn vault(mint: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0; Account::LEN];
+        Account { mint: *mint, amount, state: AccountState::Initialized, ..Account::default() }.pack_into_slice(&mut data);
+        data
+    }
+
+    fn mint(decimals: u8) -> Vec<u8> {
+        let mut data = vec![0; Mint::LEN];
+        Mint { decimals, is_initialized: true, ..Mint::default() }.pack_into_slice(&mut data);
+        data
+    }
+
+    fn decode(accounts: &Accounts) -> BoothSummary {
+        let vaults = [&accounts.vaults[0][..], &accounts.vaults[1][..]];
+        let mints = [&accounts.mints[0][..], &accounts.mints[1][..]];
+        BoothSummary::decode(&Pubkey::new_unique(), &accounts.booth, &accounts.oracle, vaults, mints, 0).unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-12, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn balances_in_base_and_token_units() {
+        let summary = decode(&accounts(0.0, 0.0));
+        assert_eq!((summary.decimals_a, summary.decimals_b), (6, 9));
+        assert_eq!((summary.balance_a, summary.balance_b), (1_000_000_000, 1_000_000_000_000));
+        assert_eq!((summary.ui_balance_a(), summary.ui_balance_b()), (1_000.0, 1_000.0));
+        // 1000 A at 2 plus 1000 B
+        assert_eq!(summary.reserves_value_in_b(), Some(3_000.0));
+    }
+
+    #[test]
+    fn booth_rate_is_what_exchange_gives_each_way() {
+        // the oracle's own rate stays unspread
+        let summary = decode(&accounts(0.5, 0.01));
+        assert_eq!(summary.exchange_rate.as_ref().unwrap().a_to_b, 2.0);
+        // skew 1/6 against whoever sells A, as 2000 B of A sits against a target of 1000 B,
+        // then 1% off either way for the confidence
+        assert_close(summary.booth_rate(true).unwrap(), 2.0 * 5.0 / 6.0 * 0.99);
+        assert_close(summary.booth_rate(false).unwrap(), 0.5 * 7.0 / 6.0 * 0.99);
+    }
+
+    #[test]
+    fn json_and_display_show_the_booth_rates() {
+        let summary = decode(&accounts(0.0, 0.01));
+        let json = summary.to_json();
+        assert!(json.contains("\"a_to_b\":2,\"confidence\":0.01,\"oracle_error\":null,"), "{}", json);
+        assert!(json.contains("\"booth_rate_a_to_b\":1.98,\"booth_rate_b_to_a\":0.495,"), "{}", json);
+        let text = summary.to_string();
+        assert!(text.contains("rate:      oracle 1 A = 2 B, confidence 100 bps\n"), "{}", text);
+        assert!(text.contains("exchange:  1 A sells for 1.98 B, 1 B sells for 0.495 A\n"), "{}", text);
+    }
+
+    #[test]
+    fn oracle_that_cant_price_leaves_no_rates() {
+        let mut accounts = accounts(0.0, 0.0);
+        accounts.oracle.truncate(3);
+        let summary = decode(&accounts);
+        assert!(summary.exchange_rate.is_err());
+        assert_eq!(summary.booth_rate(true), None);
+        assert_eq!(summary.reserves_value_in_b(), None);
+        let json = summary.to_json();
+        assert!(json.contains("\"a_to_b\":null,\"confidence\":null,\"oracle_error\":\""), "{}", json);
+        assert!(json.contains("\"booth_rate_a_to_b\":null,\"booth_rate_b_to_a\":null,\"reserves_value_in_b\":null,"), "{}", json);
+        assert!(!summary.to_string().contains("exchange:"));
+    }
+}
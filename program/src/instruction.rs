@@ -1,22 +1,34 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::state::OracleSource;
+
+pub use crate::pricing::QuoteResult;
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum ExchangeBoothInstruction {
     /// Accounts:
     /// | index | writable | signer | description                                                                                         |
     /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
-    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 0     | ✅       | ❌     | exchange_booth: PDA of ["exchange_booth", admin, mint_A, mint_B, index], created here
     /// | 1     | ❌       | ❌     | oracle: contains the ExchangeRate struct in the data
     /// | 2     | ✅       | ❌     | vault_A: account that the ExchangeBooth::vault_a address points to (PDA, this is to debit/credit)
     /// | 3     | ✅       | ❌     | vault_B: account that the ExchangeBooth::vault_b address points to (PDA, this is to debit/credit)
     /// | 4     | ❌       | ❌     | mint_A: mint address of token A (required to get decimal places)
     /// | 5     | ❌       | ❌     | mint_B: mint address of token B (required to get decimal places)
-    /// | 6     | ❌       | ✅     | admin account
+    /// | 6     | ✅       | ✅     | admin account, pays for every account created here
     /// | 7     | ❌       | ❌     | system_program
-    /// | 8     | ❌       | ❌     | token_program
+    /// | 8     | ❌       | ❌     | token_program: spl-token or Token-2022, must own both mints
+    /// | 9     | ❌       | ❌     | rent sysvar
+    /// | 10    | ✅       | ❌     | lp_mint: PDA mint for liquidity provider shares, created here
+    /// | 11    | ✅       | ❌     | booth_registry: PDA of ["booth_registry", mints sorted], created or grown to list this booth
     InititializeExchangeBooth {
-        // no instruction data on purpose
-     },
+        // lets one admin run several booths on the same pair (little endian in the seeds)
+        index: u64
+    },
+    /// The admin funds a booth with a direct token transfer to a vault while it has no lp
+    /// shares (the first AddLiquidity, which only the admin can send, then mints shares for
+    /// them). Once shares exist such a transfer is a gift to their holders, deposit with AddLiquidity.
     Deposit {
         // TODO
     },
@@ -29,6 +41,7 @@ pub enum ExchangeBoothInstruction {
     /// | 3     | ✅       | ❌     | user_token_account: writable deposit address
     /// | 4     | ❌       | ✅     | admin account
     /// | 5     | ❌       | ❌     | token_program
+    /// | 6     | ❌       | ❌     | lp_mint: the booth's lp mint, its supply must be 0
     Withdraw {
         amount: f64
     },
@@ -36,7 +49,7 @@ pub enum ExchangeBoothInstruction {
     /// | index | writable | signer | description                                                                                         |
     /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
     /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
-    /// | 1     | ❌       | ❌     | oracle: contains the ExchangeRate struct in the data
+    /// | 1     | ❌       | ❌     | oracle: must be the booth oracle, an ExchangeRate or an external price account (see SetOracle)
     /// | 2     | ✅       | ❌     | vault_A: account that the ExchangeBooth::vault_a address points to (PDA, this is to debit/credit)
     /// | 3     | ✅       | ❌     | vault_B: account that the ExchangeBooth::vault_b address points to (PDA, this is to debit/credit)
     /// | 4     | ❌       | ❌     | mint_A: mint address of token A (required to get decimal places)
@@ -46,17 +59,403 @@ pub enum ExchangeBoothInstruction {
     /// | 8     | ✅       | ❌     | customer_to_token_account: the token account that the exchange program will CREDIT
     /// | 9     | ❌       | ❌     | system_program
     /// | 10    | ❌       | ❌     | token_program
+    /// | 11    | ❌       | ❌     | (optional) associated_token_program: customer_to_token_account must then be the customer's associated token account for the bought mint, and is created if missing with the customer (writable) paying
     Exchange {
         amount: f64,
     },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data, closed here
+    /// | 1     | ✅       | ❌     | vault_A: must be empty, closed here
+    /// | 2     | ✅       | ❌     | vault_B: must be empty, closed here
+    /// | 3     | ✅       | ✅     | admin account, receives the rent of every closed account
+    /// | 4     | ✅       | ❌     | booth_registry: registry of the booth's mint pair, the booth is removed from it
+    /// | 5     | ❌       | ❌     | token_program
+    /// | 6     | ✅       | ❌     | lp_mint: the booth's lp mint, its supply must be 0. Closed if it was created closeable (Token-2022),
+    ///                                  otherwise its mint authority is revoked
     CloseExchangeBooth {
-        // TODO
+        // no instruction data on purpose
     },
     /// Accounts:
     /// | index | writable | signer | description                                                                                         |
     /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
-    /// | 0     | ✅       | ❌     | oracle: contains the ExchangeRate struct in the data
+    /// | 0     | ✅       | ✅     | oracle: contains the ExchangeRate struct in the data, followed by a RateHistory
+    ///                                  if it was allocated with state::EXCHANGE_RATE_LEN + state::RATE_HISTORY_LEN.
+    ///                                  Oracles in the older state::LEGACY_EXCHANGE_RATE_LEN layout (no confidence)
+    ///                                  keep it and only accept a confidence of 0.
+    ///                                  Signed by its own keypair, the same key that signs quotes for ExchangeWithQuote
     UpdateOracleExchangeRate {
-        exchange_rate_a_to_b: f64
+        exchange_rate_a_to_b: f64,
+        // fraction of the rate in [0, 1), customers get that much less in either direction
+        confidence: f64
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ✅     | admin account
+    SetInventorySkew {
+        // value of vault A (priced in B) over vault B that the booth tries to hold
+        target_ratio: f64,
+        // 0 turns skew off, must be in [0, 1)
+        sensitivity: f64
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ❌     | oracle: must be the booth oracle, prices the deposit and the vaults
+    /// | 2     | ✅       | ❌     | vault_A: account that the ExchangeBooth::vault_a address points to
+    /// | 3     | ✅       | ❌     | vault_B: account that the ExchangeBooth::vault_b address points to
+    /// | 4     | ❌       | ❌     | mint_A: mint address of token A (required to get decimal places)
+    /// | 5     | ❌       | ❌     | mint_B: mint address of token B (required to get decimal places)
+    /// | 6     | ✅       | ❌     | lp_mint: account that the ExchangeBooth::lp_mint address points to
+    /// | 7     | ❌       | ✅     | provider: owner of the token accounts below, must be the admin while the booth has no lp shares
+    /// | 8     | ✅       | ❌     | provider_token_a_account: the token A account that is DEBITED
+    /// | 9     | ✅       | ❌     | provider_token_b_account: the token B account that is DEBITED
+    /// | 10    | ✅       | ❌     | provider_lp_token_account: the lp token account that is CREDITED
+    /// | 11    | ❌       | ❌     | token_program
+    AddLiquidity {
+        amount_a: f64,
+        amount_b: f64
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ✅       | ❌     | vault_A: account that the ExchangeBooth::vault_a address points to
+    /// | 2     | ✅       | ❌     | vault_B: account that the ExchangeBooth::vault_b address points to
+    /// | 3     | ❌       | ❌     | mint_A: mint address of token A
+    /// | 4     | ❌       | ❌     | mint_B: mint address of token B
+    /// | 5     | ✅       | ❌     | lp_mint: account that the ExchangeBooth::lp_mint address points to
+    /// | 6     | ❌       | ✅     | provider: owner of the token accounts below
+    /// | 7     | ✅       | ❌     | provider_token_a_account: the token A account that is CREDITED
+    /// | 8     | ✅       | ❌     | provider_token_b_account: the token B account that is CREDITED
+    /// | 9     | ✅       | ❌     | provider_lp_token_account: the lp token account that is burned from
+    /// | 10    | ❌       | ❌     | token_program
+    RemoveLiquidity {
+        // in lp token units
+        amount: f64
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ✅       | ❌     | vault: the booth vault holding `mint`, lent from and repaid to
+    /// | 2     | ❌       | ❌     | mint: mint address of the borrowed token
+    /// | 3     | ✅       | ❌     | borrower_token_account: receives the loan
+    /// | 4     | ❌       | ❌     | borrower_program: invoked with FlashLoanReceiverInstruction, must repay the vault
+    /// | 5     | ❌       | ❌     | token_program
+    /// | 6..   | ?        | ?      | remaining accounts, passed through to borrower_program as is
+    FlashLoan {
+        amount: f64,
+        mint: Pubkey
+    },
+    /// Same accounts as Exchange, followed by:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 11    | ✅       | ❌     | quote_nonce: PDA of ["quote_nonce", exchange_booth, customer], created on first use
+    /// | 12    | ❌       | ❌     | instructions sysvar
+    ///
+    /// The instruction right before this one must be an Ed25519 program instruction verifying
+    /// the oracle key's signature over the borsh encoded `quote`.
+    ExchangeWithQuote {
+        amount: f64,
+        quote: Quote
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ❌     | vault_A: account that the ExchangeBooth::vault_a address points to
+    /// | 2     | ❌       | ❌     | vault_B: account that the ExchangeBooth::vault_b address points to
+    /// | 3     | ✅       | ❌     | limit_order: PDA of ["limit_order", exchange_booth, owner, order_id], created here
+    /// | 4     | ✅       | ❌     | escrow: PDA token account of ["limit_order_escrow", limit_order], created here
+    /// | 5     | ❌       | ❌     | from_mint: mint of the token being sold
+    /// | 6     | ✅       | ✅     | owner: pays rent and the crank tip
+    /// | 7     | ✅       | ❌     | owner_from_token_account: DEBITED into the escrow, receives refunds
+    /// | 8     | ❌       | ❌     | owner_to_token_account: CREDITED when the order fills
+    /// | 9     | ❌       | ❌     | system_program
+    /// | 10    | ❌       | ❌     | token_program
+    /// | 11    | ❌       | ❌     | rent sysvar
+    PlaceLimitOrder {
+        order_id: u64,
+        amount: f64,
+        min_rate: f64,
+        tip_lamports: u64
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ❌     | oracle: contains the ExchangeRate struct in the data
+    /// | 2     | ✅       | ❌     | vault_A: account that the ExchangeBooth::vault_a address points to
+    /// | 3     | ✅       | ❌     | vault_B: account that the ExchangeBooth::vault_b address points to
+    /// | 4     | ❌       | ❌     | mint_A: mint address of token A (required to get decimal places)
+    /// | 5     | ❌       | ❌     | mint_B: mint address of token B (required to get decimal places)
+    /// | 6     | ✅       | ✅     | cranker: receives the tip of every filled order
+    /// | 7     | ❌       | ❌     | system_program
+    /// | 8     | ❌       | ❌     | token_program
+    /// then for each order, orders that can't fill yet are skipped:
+    /// | +0    | ✅       | ❌     | limit_order
+    /// | +1    | ✅       | ❌     | escrow
+    /// | +2    | ✅       | ❌     | owner: receives the rent back
+    /// | +3    | ✅       | ❌     | owner_from_token_account
+    /// | +4    | ✅       | ❌     | owner_to_token_account
+    FillLimitOrders {
+        // no instruction data on purpose
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | limit_order
+    /// | 1     | ✅       | ❌     | escrow
+    /// | 2     | ✅       | ✅     | owner: gets the escrowed tokens, rent and tip back
+    /// | 3     | ✅       | ❌     | owner_from_token_account
+    /// | 4     | ❌       | ❌     | from_mint: mint of the escrowed token
+    /// | 5     | ❌       | ❌     | token_program
+    CancelLimitOrder {
+        // no instruction data on purpose
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ❌     | vault_A: account that the ExchangeBooth::vault_a address points to
+    /// | 2     | ❌       | ❌     | vault_B: account that the ExchangeBooth::vault_b address points to
+    /// | 3     | ✅       | ❌     | dca_schedule: PDA of ["dca_schedule", exchange_booth, owner, schedule_id], created here
+    /// | 4     | ✅       | ❌     | escrow: PDA token account of ["dca_escrow", dca_schedule], created here
+    /// | 5     | ❌       | ❌     | from_mint: mint of the token being sold
+    /// | 6     | ✅       | ✅     | owner: pays rent
+    /// | 7     | ✅       | ❌     | owner_from_token_account: DEBITED amount * count into the escrow
+    /// | 8     | ❌       | ❌     | owner_to_token_account: CREDITED on every execution
+    /// | 9     | ❌       | ❌     | system_program
+    /// | 10    | ❌       | ❌     | token_program
+    /// | 11    | ❌       | ❌     | rent sysvar
+    CreateDcaSchedule {
+        schedule_id: u64,
+        // sold on every execution
+        amount: f64,
+        interval_seconds: i64,
+        count: u64
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ❌     | oracle: contains the ExchangeRate struct in the data
+    /// | 2     | ✅       | ❌     | vault_A: account that the ExchangeBooth::vault_a address points to
+    /// | 3     | ✅       | ❌     | vault_B: account that the ExchangeBooth::vault_b address points to
+    /// | 4     | ❌       | ❌     | mint_A: mint address of token A (required to get decimal places)
+    /// | 5     | ❌       | ❌     | mint_B: mint address of token B (required to get decimal places)
+    /// | 6     | ✅       | ❌     | dca_schedule
+    /// | 7     | ✅       | ❌     | escrow
+    /// | 8     | ✅       | ❌     | owner: gets the rent back after the last execution
+    /// | 9     | ✅       | ❌     | owner_from_token_account
+    /// | 10    | ✅       | ❌     | owner_to_token_account
+    /// | 11    | ❌       | ❌     | system_program
+    /// | 12    | ❌       | ❌     | token_program
+    ExecuteDca {
+        // no instruction data on purpose
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | multi_asset_booth: allocated with state::multi_asset_booth_len(number of assets)
+    /// | 1     | ✅       | ❌     | oracle: allocated with state::multi_asset_rate_len(number of assets), the admin becomes its authority if it is still zeroed
+    /// | 2     | ✅       | ✅     | admin account, pays for the vaults
+    /// | 3     | ❌       | ❌     | system_program
+    /// | 4     | ❌       | ❌     | token_program
+    /// | 5     | ❌       | ❌     | rent sysvar
+    /// then for each asset, in the order the oracle prices them:
+    /// | +0    | ❌       | ❌     | mint
+    /// | +1    | ✅       | ❌     | vault: PDA of ["exchange_booth", admin, multi_asset_booth, mint], created here
+    InitializeMultiAssetBooth {
+        // no instruction data on purpose
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | oracle: allocated with state::multi_asset_rate_len(number of assets)
+    /// | 1     | ❌       | ✅     | authority: MultiAssetRate::authority
+    UpdateMultiAssetOracle {
+        // price of each booth asset in units of the quote asset
+        prices: Vec<f64>
+    },
+    /// Exchange between any two assets of a multi asset booth. This is its own instruction rather
+    /// than mint indices on Exchange: Exchange's data and accounts are what every existing client
+    /// of the pair booths sends, and a pair booth prices with an ExchangeRate, skew and confidence
+    /// that a MultiAssetRate doesn't have.
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | multi_asset_booth: contains the MultiAssetBooth struct in the data
+    /// | 1     | ❌       | ❌     | oracle: account that MultiAssetBooth::oracle points to
+    /// | 2     | ✅       | ❌     | from_vault: vault of assets[from_index]
+    /// | 3     | ✅       | ❌     | to_vault: vault of assets[to_index]
+    /// | 4     | ❌       | ❌     | from_mint: mint of assets[from_index]
+    /// | 5     | ❌       | ❌     | to_mint: mint of assets[to_index]
+    /// | 6     | ❌       | ✅     | customer
+    /// | 7     | ✅       | ❌     | customer_from_token_account: the token account that the exchange program will DEBIT
+    /// | 8     | ✅       | ❌     | customer_to_token_account: the token account that the exchange program will CREDIT
+    /// | 9     | ❌       | ❌     | token_program
+    ExchangeMultiAsset {
+        amount: f64,
+        from_index: u8,
+        to_index: u8
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | multi_asset_booth: contains the MultiAssetBooth struct in the data
+    /// | 1     | ✅       | ❌     | target_vault: vault of assets[asset_index]
+    /// | 2     | ❌       | ❌     | mint: mint of assets[asset_index]
+    /// | 3     | ✅       | ❌     | user_token_account: writable deposit address
+    /// | 4     | ❌       | ✅     | admin account
+    /// | 5     | ❌       | ❌     | token_program
+    WithdrawMultiAsset {
+        amount: f64,
+        asset_index: u8
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ✅     | customer
+    /// | 1     | ✅       | ❌     | customer_from_token_account: pays the first booth
+    /// | 2     | ✅       | ❌     | customer_to_token_account: receives the output of the last booth
+    /// | 3     | ❌       | ❌     | token_program
+    /// then for each booth in the route, in order (a booth can appear only once):
+    /// | +0    | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | +1    | ❌       | ❌     | oracle: must be the booth oracle
+    /// | +2    | ✅       | ❌     | vault_A
+    /// | +3    | ✅       | ❌     | vault_B
+    /// | +4    | ❌       | ❌     | mint_A
+    /// | +5    | ❌       | ❌     | mint_B
+    ExchangeRoute {
+        // in units of the token the customer sells
+        amount: f64,
+        // in units of the token the customer ends up with, the whole route fails below this
+        min_amount_out: f64
+    },
+    /// Exchange with SOL on one side. The program wraps into / unwraps from a temporary
+    /// wSOL account of the customer, so one booth mint must be the native mint.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ❌     | oracle: contains the ExchangeRate struct in the data
+    /// | 2     | ✅       | ❌     | vault_A
+    /// | 3     | ✅       | ❌     | vault_B
+    /// | 4     | ❌       | ❌     | mint_A
+    /// | 5     | ❌       | ❌     | mint_B
+    /// | 6     | ✅       | ✅     | customer: pays or receives the SOL
+    /// | 7     | ✅       | ❌     | customer_token_account: the token side of the trade
+    /// | 8     | ✅       | ❌     | wsol_account: PDA of ["wsol", exchange_booth, customer], created and closed here
+    /// | 9     | ❌       | ❌     | system_program
+    /// | 10    | ❌       | ❌     | token_program
+    /// | 11    | ❌       | ❌     | rent sysvar
+    ExchangeSol {
+        // in units of what the customer sells
+        amount: f64,
+        // true to sell SOL for the token, false to buy SOL with it
+        sell_sol: bool
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ✅     | admin account
+    /// | 2     | ❌       | ❌     | new_oracle: already holds a valid rate for `source`, the booth prices with it from now on
+    SetOracle {
+        source: OracleSource,
+        // in basis points of the rate, 0 accepts any confidence
+        max_confidence_bps: u64,
+        // only checked for external and aggregated oracles, in seconds
+        max_price_age: i64,
+        // pushed oracles with history only, in seconds, 0 prices at the latest rate
+        twap_window: i64
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | aggregated_oracle: allocated with state::aggregated_oracle_len(number of publishers)
+    /// | 1     | ❌       | ✅     | admin account
+    InitializeAggregatedOracle {
+        // at most state::MAX_ORACLE_PUBLISHERS, each may only update its own submission
+        publishers: Vec<Pubkey>,
+        // fresh submissions needed to price a trade
+        quorum: u8
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | aggregated_oracle: contains the AggregatedOracle struct in the data
+    /// | 1     | ❌       | ✅     | publisher: one of the publishers the oracle was initialized with
+    SubmitOraclePrice {
+        a_to_b: f64
+    },
+    /// Prices an Exchange without moving any tokens and returns a QuoteResult through
+    /// return data, meant to be run with simulateTransaction.
+    /// Token-2022 transfer fees are not included.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | exchange_booth: contains the ExchangeBooth struct in the data
+    /// | 1     | ❌       | ❌     | oracle: must be the booth oracle
+    /// | 2     | ❌       | ❌     | vault_A
+    /// | 3     | ❌       | ❌     | vault_B
+    /// | 4     | ❌       | ❌     | mint_A
+    /// | 5     | ❌       | ❌     | mint_B
+    /// | 6     | ❌       | ❌     | token_program
+    Quote {
+        // in units of what the customer would sell
+        amount: f64,
+        direction: TradeDirection
+    },
+    /// Accounts:
+    /// | index | writable | signer | description                                                                                         |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | dca_schedule
+    /// | 1     | ✅       | ❌     | escrow
+    /// | 2     | ✅       | ✅     | owner: gets the unsold escrow and the rent back
+    /// | 3     | ✅       | ❌     | owner_from_token_account
+    /// | 4     | ❌       | ❌     | from_mint: mint of the escrowed token
+    /// | 5     | ❌       | ❌     | token_program
+    CancelDcaSchedule {
+        // no instruction data on purpose
     }
 }
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TradeDirection {
+    AToB,
+    BToA,
+}
+
+/// Off-chain price for a single customer, signed by the key of the booth's oracle account.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub exchange_booth: Pubkey,
+    pub customer: Pubkey,
+    pub a_to_b: f64,
+    // the only way round the quote can be filled, a_to_b is inverted for BToA
+    pub direction: TradeDirection,
+    // in units of the token the customer sells
+    pub max_amount: f64,
+    // last slot the quote can be filled in
+    pub expiry_slot: u64,
+    // must be greater than the last nonce the customer filled on this booth
+    pub nonce: u64
+}
+
+/// Instruction data the booth sends to the borrower program during a FlashLoan.
+/// By the time the borrower program returns, the vault must hold `amount + fee` more
+/// than it did after the loan left, or the whole FlashLoan fails.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct FlashLoanReceiverInstruction {
+    pub amount: u64,
+    pub fee: u64
+}
@@ -1,5 +1,11 @@
+#[cfg(feature = "client")]
+pub mod client;
 pub mod entrypoint;
 pub mod error;
+pub mod event;
+#[cfg(feature = "client")]
+pub mod inspect;
 pub mod instruction;
+pub mod pricing;
 pub mod processor;
 pub mod state;
@@ -0,0 +1,228 @@
+// Exchange math, shared by the processors and by off-chain callers so a quote computed from
+// fetched accounts comes out the same as the trade. Functions over plain numbers and state
+// structs, no accounts and no logging, the processors log what they priced with.
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::state::{ExchangeBooth, ExchangeRate, FLASH_LOAN_FEE_BPS};
+
+/// Return data of the Quote instruction, in units of the token the customer would buy.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct QuoteResult {
+    pub amount_out: f64,
+    // what the booth's spread (inventory skew, oracle confidence) costs against the oracle rate
+    pub fee: f64,
+    // amount_out / amount
+    pub effective_rate: f64
+}
+
+
+// 10^decimals, base units per token unit
+pub fn pow10(decimals: u8) -> f64 {
+    f64::powf(10., decimals.into())
+}
+
+// token units to base units, rounding down
+pub fn to_base_units(amount: f64, decimals: u8) -> u64 {
+    (amount * pow10(decimals)) as u64
+}
+
+pub fn to_ui_amount(amount_small: u64, decimals: u8) -> f64 {
+    amount_small as f64 / pow10(decimals)
+}
+
+// Rate the booth offers in the given direction: the oracle rate, moved by inventory skew if enabled
+// and widened by the oracle's confidence. Vault balances are in token units.
+pub fn booth_rate(
+    exchange_booth: &ExchangeBooth,
+    exchange_rate: &ExchangeRate,
+    vault_a_balance: f64,
+    vault_b_balance: f64,
+    exchange_from_a: bool,
+) -> f64 {
+    let mut rate = if exchange_from_a {exchange_rate.a_to_b} else {exchange_rate.b_to_a};
+    if exchange_booth.skew_sensitivity > 0.0 {
+        rate = skewed_rate(exchange_booth, exchange_rate, vault_a_balance, vault_b_balance, exchange_from_a, rate);
+    }
+    // the customer pays for the oracle's uncertainty whichever way they trade
+    if exchange_rate.confidence > 0.0 {
+        rate *= 1.0 - exchange_rate.confidence;
+    }
+    rate
+}
+
+// Adjusts the oracle rate by how far the vault inventory is from the booth's target ratio.
+// imbalance is in (-1, 1): positive when the booth holds more A (by value) than it wants,
+// so selling A to the booth gets a worse rate and buying A gets a better one.
+fn skewed_rate(
+    exchange_booth: &ExchangeBooth,
+    exchange_rate: &ExchangeRate,
+    vault_a_balance: f64,
+    vault_b_balance: f64,
+    exchange_from_a: bool,
+    rate: f64,
+) -> f64 {
+    let value_a = vault_a_balance * exchange_rate.a_to_b;
+    let target_value_a = vault_b_balance * exchange_booth.skew_target_ratio;
+    if value_a + target_value_a == 0.0 {
+        return rate;
+    }
+    let imbalance = (value_a - target_value_a) / (value_a + target_value_a);
+    let skew = exchange_booth.skew_sensitivity * imbalance;
+
+    if exchange_from_a {rate * (1.0 - skew)} else {rate * (1.0 + skew)}
+}
+
+// What the booth pays out, in base units of the bought mint, for `received_small` base units
+// of the sold mint reaching its vault. Every Exchange, ExchangeRoute hop and quote rounds here.
+pub fn amount_out(received_small: u64, from_decimals: u8, to_decimals: u8, rate: f64) -> u64 {
+    let received = to_ui_amount(received_small, from_decimals);
+    let result = received * rate;
+    to_base_units(result, to_decimals)
+}
+
+// Prices an Exchange of `amount` token units at the booth rate `rate`, as if the vault received
+// all of it. The fee is the spread against `oracle_rate`, the unadjusted rate in that direction.
+pub fn quote(amount: f64, from_decimals: u8, to_decimals: u8, oracle_rate: f64, rate: f64) -> QuoteResult {
+    let amount_small = to_base_units(amount, from_decimals);
+    let amount_out = to_ui_amount(amount_out(amount_small, from_decimals, to_decimals, rate), to_decimals);
+    let received = to_ui_amount(amount_small, from_decimals);
+    QuoteResult {
+        amount_out,
+        fee: (received * oracle_rate - amount_out).max(0.0),
+        effective_rate: amount_out / amount,
+    }
+}
+
+// FLASH_LOAN_FEE_BPS of the loan, rounded up so small loans still pay
+pub fn flash_loan_fee(amount_small: u64) -> u64 {
+    ((amount_small as u128 * FLASH_LOAN_FEE_BPS as u128 + 9_999) / 10_000) as u64
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::OracleSource;
+    use solana_program::pubkey::Pubkey;
+
+    fn booth(skew_target_ratio: f64, skew_sensitivity: f64) -> ExchangeBooth {
+        ExchangeBooth {
+            admin: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            vault_a: Pubkey::new_unique(),
+            vault_b: Pubkey::new_unique(),
+            lp_mint: Pubkey::new_unique(),
+            skew_target_ratio,
+            skew_sensitivity,
+            oracle_source: OracleSource::Pushed,
+            max_confidence_bps: 0,
+            max_price_age: 0,
+            twap_window: 0,
+        }
+    }
+
+    fn rate(a_to_b: f64, confidence: f64) -> ExchangeRate {
+        ExchangeRate {a_to_b, b_to_a: 1.0 / a_to_b, confidence}
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-12, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn booth_rate_is_the_oracle_rate_without_skew_or_confidence() {
+        let exchange_booth = booth(1.0, 0.0);
+        assert_eq!(booth_rate(&exchange_booth, &rate(2.0, 0.0), 1_000.0, 1_000.0, true), 2.0);
+        assert_eq!(booth_rate(&exchange_booth, &rate(2.0, 0.0), 1_000.0, 1_000.0, false), 0.5);
+    }
+
+    #[test]
+    fn skew_moves_the_rate_by_the_inventory_imbalance() {
+        // 1000 A at 2 B per A is 2000 B of A against a target of 1000 * 1.0 = 1000 B:
+        // imbalance (2000 - 1000) / (2000 + 1000) = 1/3, skew 0.5 * 1/3 = 1/6
+        let exchange_booth = booth(1.0, 0.5);
+        assert_close(booth_rate(&exchange_booth, &rate(2.0, 0.0), 1_000.0, 1_000.0, true), 2.0 * 5.0 / 6.0);
+        assert_close(booth_rate(&exchange_booth, &rate(2.0, 0.0), 1_000.0, 1_000.0, false), 0.5 * 7.0 / 6.0);
+        // short of A: 250 A is 500 B against 1000 B, imbalance -1/3, selling A gets the better rate
+        assert_close(booth_rate(&exchange_booth, &rate(2.0, 0.0), 250.0, 1_000.0, true), 2.0 * 7.0 / 6.0);
+    }
+
+    #[test]
+    fn skew_leaves_balanced_and_empty_vaults_at_the_oracle_rate() {
+        // 2000 B of A against 1000 B at a target ratio of 2
+        assert_eq!(booth_rate(&booth(2.0, 0.5), &rate(2.0, 0.0), 1_000.0, 1_000.0, true), 2.0);
+        assert_eq!(booth_rate(&booth(1.0, 0.5), &rate(2.0, 0.0), 0.0, 0.0, true), 2.0);
+    }
+
+    #[test]
+    fn confidence_widens_the_rate_against_the_customer_both_ways() {
+        let exchange_booth = booth(1.0, 0.0);
+        assert_close(booth_rate(&exchange_booth, &rate(2.0, 0.01), 1_000.0, 1_000.0, true), 1.98);
+        assert_close(booth_rate(&exchange_booth, &rate(2.0, 0.01), 1_000.0, 1_000.0, false), 0.495);
+        // on top of the skew: 2 * 5/6 * 0.99
+        assert_close(booth_rate(&booth(1.0, 0.5), &rate(2.0, 0.01), 1_000.0, 1_000.0, true), 1.65);
+    }
+
+    #[test]
+    fn scaling_between_units() {
+        assert_eq!(pow10(0), 1.0);
+        assert_eq!(pow10(9), 1_000_000_000.0);
+        assert_eq!(to_base_units(1.5, 0), 1);
+        assert_eq!(to_base_units(1.5, 6), 1_500_000);
+        assert_eq!(to_base_units(1.0, 18), 1_000_000_000_000_000_000);
+        assert_eq!(to_ui_amount(1_500_000_000, 9), 1.5);
+        assert_eq!(to_ui_amount(1, 18), 1e-18);
+    }
+
+    #[test]
+    fn amount_out_rounds_down_at_0_decimals() {
+        // 7 * 0.5 = 3.5
+        assert_eq!(amount_out(7, 0, 0, 0.5), 3);
+        assert_eq!(amount_out(1, 0, 0, 0.999), 0);
+    }
+
+    #[test]
+    fn amount_out_rounds_down_at_6_decimals() {
+        // 1 A at 1/3 is 333_333.33.. base units
+        assert_eq!(amount_out(1_000_000, 6, 6, 1.0 / 3.0), 333_333);
+        assert_eq!(amount_out(1_000_000, 6, 6, 2.0 * 5.0 / 6.0), 1_666_666);
+        // 0.000001 A at 0.5 is half a base unit
+        assert_eq!(amount_out(1, 6, 6, 0.5), 0);
+    }
+
+    #[test]
+    fn amount_out_rounds_down_at_9_decimals() {
+        assert_eq!(amount_out(1_000_000, 6, 9, 2.5), 2_500_000_000);
+        // the last 999 base units are less than one of the 6 decimal mint
+        assert_eq!(amount_out(1_999_999_999, 9, 6, 1.0), 1_999_999);
+    }
+
+    #[test]
+    fn amount_out_rounds_down_at_18_decimals() {
+        assert_eq!(amount_out(1_000_000, 6, 18, 1.0), 1_000_000_000_000_000_000);
+        assert_eq!(amount_out(1_500_000_000_000_000_000, 18, 6, 2.0), 3_000_000);
+        assert_eq!(amount_out(999_999_999_999, 18, 6, 1.0), 0);
+    }
+
+    #[test]
+    fn quote_charges_the_spread_against_the_oracle_rate() {
+        // 2 A at 1.98 instead of 2
+        let quote_result = quote(2.0, 6, 6, 2.0, 1.98);
+        assert_close(quote_result.amount_out, 3.96);
+        assert_close(quote_result.fee, 0.04);
+        assert_close(quote_result.effective_rate, 1.98);
+        // no spread, no fee
+        assert_eq!(quote(2.0, 6, 6, 2.0, 2.0).fee, 0.0);
+    }
+
+    #[test]
+    fn flash_loan_fee_rounds_up() {
+        // FLASH_LOAN_FEE_BPS is 9
+        assert_eq!(flash_loan_fee(0), 0);
+        assert_eq!(flash_loan_fee(1), 1);
+        assert_eq!(flash_loan_fee(10_000), 9);
+        assert_eq!(flash_loan_fee(10_001), 10);
+        assert_eq!(flash_loan_fee(1_111_112), 1_001);
+        assert_eq!(flash_loan_fee(u64::MAX), 16_602_069_666_338_597);
+    }
+}
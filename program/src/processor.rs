@@ -6,12 +6,39 @@ use solana_program::{
 
 use crate::instruction::ExchangeBoothInstruction;
 
+pub mod add_liquidity;
+pub mod cancel_dca_schedule;
+pub mod cancel_limit_order;
 pub mod close_exchange_booth;
+pub mod create_dca_schedule;
+pub mod escrow;
 pub mod deposit;
 pub mod exchange;
+pub mod exchange_multi_asset;
+pub mod exchange_route;
+pub mod exchange_sol;
+pub mod exchange_with_quote;
+pub mod execute_dca;
+pub mod fill_limit_orders;
+pub mod flash_loan;
+pub mod initialize_aggregated_oracle;
 pub mod initialize_exchange_booth;
+pub mod initialize_multi_asset_booth;
+pub mod oracle;
+pub mod pda;
+pub mod place_limit_order;
+pub mod quote;
+pub mod registry;
+pub mod remove_liquidity;
+pub mod set_inventory_skew;
+pub mod set_oracle;
+pub mod submit_oracle_price;
+pub mod token;
 pub mod withdraw;
+pub mod withdraw_multi_asset;
+pub mod update_multi_asset_oracle;
 pub mod update_oracle_exchange_rate;
+pub mod validate;
 
 pub struct Processor {}
 
@@ -25,12 +52,12 @@ impl Processor {
             .map_err(|_| ProgramError::InvalidInstructionData)?;
 
         match instruction {
-            ExchangeBoothInstruction::InititializeExchangeBooth { } => {
+            ExchangeBoothInstruction::InititializeExchangeBooth { index } => {
                 msg!("Instruction: InitializeExchangeBooth");
-                initialize_exchange_booth::process(program_id, accounts)?;
+                initialize_exchange_booth::process(program_id, accounts, index)?;
             }
             ExchangeBoothInstruction::Deposit { } => {
-                msg!("Instruction: Deposit"); //not actually necessary since we can just use a direct token transfer
+                msg!("Instruction: Deposit"); //a direct token transfer works until lp shares exist, AddLiquidity after that
                 deposit::process(program_id, accounts)?;
             }
             ExchangeBoothInstruction::Withdraw { amount } => {
@@ -45,9 +72,93 @@ impl Processor {
                 msg!("Instruction: CloseExchangeBooth");
                 close_exchange_booth::process(program_id, accounts)?;
             }
-            ExchangeBoothInstruction::UpdateOracleExchangeRate {exchange_rate_a_to_b } => {
+            ExchangeBoothInstruction::UpdateOracleExchangeRate {exchange_rate_a_to_b, confidence } => {
                 msg!("Instruction: UpdateOracleExchangeRate");
-                update_oracle_exchange_rate::process(accounts, exchange_rate_a_to_b)?;
+                update_oracle_exchange_rate::process(program_id, accounts, exchange_rate_a_to_b, confidence)?;
+            }
+            ExchangeBoothInstruction::SetInventorySkew { target_ratio, sensitivity } => {
+                msg!("Instruction: SetInventorySkew");
+                set_inventory_skew::process(program_id, accounts, target_ratio, sensitivity)?;
+            }
+            ExchangeBoothInstruction::AddLiquidity { amount_a, amount_b } => {
+                msg!("Instruction: AddLiquidity");
+                add_liquidity::process(program_id, accounts, amount_a, amount_b)?;
+            }
+            ExchangeBoothInstruction::RemoveLiquidity { amount } => {
+                msg!("Instruction: RemoveLiquidity");
+                remove_liquidity::process(program_id, accounts, amount)?;
+            }
+            ExchangeBoothInstruction::FlashLoan { amount, mint } => {
+                msg!("Instruction: FlashLoan");
+                flash_loan::process(program_id, accounts, amount, mint)?;
+            }
+            ExchangeBoothInstruction::ExchangeWithQuote { amount, quote } => {
+                msg!("Instruction: ExchangeWithQuote");
+                exchange_with_quote::process(program_id, accounts, amount, quote)?;
+            }
+            ExchangeBoothInstruction::PlaceLimitOrder { order_id, amount, min_rate, tip_lamports } => {
+                msg!("Instruction: PlaceLimitOrder");
+                place_limit_order::process(program_id, accounts, order_id, amount, min_rate, tip_lamports)?;
+            }
+            ExchangeBoothInstruction::FillLimitOrders { } => {
+                msg!("Instruction: FillLimitOrders");
+                fill_limit_orders::process(program_id, accounts)?;
+            }
+            ExchangeBoothInstruction::CancelLimitOrder { } => {
+                msg!("Instruction: CancelLimitOrder");
+                cancel_limit_order::process(program_id, accounts)?;
+            }
+            ExchangeBoothInstruction::CreateDcaSchedule { schedule_id, amount, interval_seconds, count } => {
+                msg!("Instruction: CreateDcaSchedule");
+                create_dca_schedule::process(program_id, accounts, schedule_id, amount, interval_seconds, count)?;
+            }
+            ExchangeBoothInstruction::ExecuteDca { } => {
+                msg!("Instruction: ExecuteDca");
+                execute_dca::process(program_id, accounts)?;
+            }
+            ExchangeBoothInstruction::InitializeMultiAssetBooth { } => {
+                msg!("Instruction: InitializeMultiAssetBooth");
+                initialize_multi_asset_booth::process(program_id, accounts)?;
+            }
+            ExchangeBoothInstruction::UpdateMultiAssetOracle { prices } => {
+                msg!("Instruction: UpdateMultiAssetOracle");
+                update_multi_asset_oracle::process(program_id, accounts, prices)?;
+            }
+            ExchangeBoothInstruction::ExchangeMultiAsset { amount, from_index, to_index } => {
+                msg!("Instruction: ExchangeMultiAsset");
+                exchange_multi_asset::process(program_id, accounts, amount, from_index, to_index)?;
+            }
+            ExchangeBoothInstruction::WithdrawMultiAsset { amount, asset_index } => {
+                msg!("Instruction: WithdrawMultiAsset");
+                withdraw_multi_asset::process(program_id, accounts, amount, asset_index)?;
+            }
+            ExchangeBoothInstruction::ExchangeRoute { amount, min_amount_out } => {
+                msg!("Instruction: ExchangeRoute");
+                exchange_route::process(program_id, accounts, amount, min_amount_out)?;
+            }
+            ExchangeBoothInstruction::ExchangeSol { amount, sell_sol } => {
+                msg!("Instruction: ExchangeSol");
+                exchange_sol::process(program_id, accounts, amount, sell_sol)?;
+            }
+            ExchangeBoothInstruction::SetOracle { source, max_confidence_bps, max_price_age, twap_window } => {
+                msg!("Instruction: SetOracle");
+                set_oracle::process(program_id, accounts, source, max_confidence_bps, max_price_age, twap_window)?;
+            }
+            ExchangeBoothInstruction::InitializeAggregatedOracle { publishers, quorum } => {
+                msg!("Instruction: InitializeAggregatedOracle");
+                initialize_aggregated_oracle::process(program_id, accounts, publishers, quorum)?;
+            }
+            ExchangeBoothInstruction::SubmitOraclePrice { a_to_b } => {
+                msg!("Instruction: SubmitOraclePrice");
+                submit_oracle_price::process(program_id, accounts, a_to_b)?;
+            }
+            ExchangeBoothInstruction::Quote { amount, direction } => {
+                msg!("Instruction: Quote");
+                quote::process(program_id, accounts, amount, direction)?;
+            }
+            ExchangeBoothInstruction::CancelDcaSchedule { } => {
+                msg!("Instruction: CancelDcaSchedule");
+                cancel_dca_schedule::process(program_id, accounts)?;
             }
         }
 
@@ -0,0 +1,157 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    pricing,
+    processor::{oracle, token, validate},
+    state::ExchangeBooth,
+};
+
+use borsh::BorshDeserialize;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_a: f64,
+    amount_b: f64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let oracle = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let mint_a_acc = next_account_info(account_info_iter)?;
+    let mint_b_acc = next_account_info(account_info_iter)?;
+    let lp_mint_acc = next_account_info(account_info_iter)?;
+    let provider = next_account_info(account_info_iter)?;
+    let provider_token_a_acc = next_account_info(account_info_iter)?;
+    let provider_token_b_acc = next_account_info(account_info_iter)?;
+    let provider_lp_token_acc = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !provider.is_signer {
+        msg!("error: provider must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [vault_a, vault_b, lp_mint_acc, provider_token_a_acc, provider_token_b_acc, provider_lp_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    if !(amount_a >= 0.0 && amount_b >= 0.0 && amount_a + amount_b > 0.0) {
+        msg!("error: deposit amounts must be non-negative and not both zero");
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if exchange_booth.vault_a != *vault_a.key || exchange_booth.vault_b != *vault_b.key {
+        msg!("error: vaults do not belong to the exchange booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if exchange_booth.lp_mint != *lp_mint_acc.key {
+        msg!("error: lp mint does not belong to the exchange booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    // the oracle prices every share, load_rate makes sure it is the booth's
+    let exchange_rate = oracle::load_rate(program_id, &exchange_booth, oracle)?;
+    token::check_program(token_program)?;
+    token::check_owner(
+        token_program,
+        &[vault_a, vault_b, mint_a_acc, mint_b_acc, lp_mint_acc, provider_token_a_acc, provider_token_b_acc, provider_lp_token_acc]
+    )?;
+    let vault_a_token_account = token::unpack_account(&vault_a.try_borrow_data()?)?;
+    let vault_b_token_account = token::unpack_account(&vault_b.try_borrow_data()?)?;
+    let mint_a = token::unpack_mint(&mint_a_acc.try_borrow_data()?)?;
+    let mint_b = token::unpack_mint(&mint_b_acc.try_borrow_data()?)?;
+    let lp_mint = token::unpack_mint(&lp_mint_acc.try_borrow_data()?)?;
+    if vault_a_token_account.mint != *mint_a_acc.key || vault_b_token_account.mint != *mint_b_acc.key {
+        msg!("error: mints do not match the booth vaults");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    // everything is valued in token B at the oracle rate
+    let pool_value = pricing::to_ui_amount(vault_a_token_account.amount, mint_a.decimals) * exchange_rate.a_to_b
+        + pricing::to_ui_amount(vault_b_token_account.amount, mint_b.decimals);
+    // whoever seeds the pool gets shares for what is already in the vaults, which is only fair
+    // if that is the admin who funded them. Empty vaults too: the admin's direct deposits that
+    // follow would back the seeder's shares, and any share blocks Withdraw and CloseExchangeBooth
+    if lp_mint.supply == 0 && *provider.key != exchange_booth.admin {
+        msg!("error: only the admin can seed a booth without lp shares");
+        return Err(ExchangeBoothError::IncorrectAdmin.into())
+    }
+    if lp_mint.supply > 0 && pool_value <= 0.0 {
+        msg!("error: lp shares outstanding against empty vaults");
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+
+    // shares are priced on what the vaults received, after any transfer fee
+    let mut received = [0u64; 2];
+    for (received_small, (amount, decimals, from, mint, to)) in received.iter_mut().zip([
+        (amount_a, mint_a.decimals, provider_token_a_acc, mint_a_acc, vault_a),
+        (amount_b, mint_b.decimals, provider_token_b_acc, mint_b_acc, vault_b),
+    ]) {
+        let amount_small = pricing::to_base_units(amount, decimals);
+        if amount_small == 0 {
+            continue;
+        }
+        *received_small = token::transfer_checked(token_program, from, mint, to, provider, amount_small, &[])?;
+    }
+    let deposit_value = pricing::to_ui_amount(received[0], mint_a.decimals) * exchange_rate.a_to_b
+        + pricing::to_ui_amount(received[1], mint_b.decimals);
+
+    let shares: u64 = if lp_mint.supply == 0 {
+        pricing::to_base_units(pool_value + deposit_value, lp_mint.decimals)
+    } else {
+        (lp_mint.supply as f64 * deposit_value / pool_value) as u64
+    };
+    if shares == 0 {
+        msg!("error: deposit too small to mint any lp shares");
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    msg!("Provider deposits {} A and {} B ({} and {} received) for {} lp shares",
+        amount_a,
+        amount_b,
+        received[0],
+        received[1],
+        shares
+    );
+
+    let (_, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"exchange_booth",
+            exchange_booth.admin.as_ref(),
+            exchange_booth_acc.key.as_ref(),
+            b"lp_mint"
+        ],
+        program_id,
+    );
+    token::mint_to(
+        token_program,
+        lp_mint_acc,
+        provider_lp_token_acc,
+        lp_mint_acc,
+        shares,
+        &[&[b"exchange_booth", exchange_booth.admin.as_ref(), exchange_booth_acc.key.as_ref(), b"lp_mint", &[bump_seed]]]
+    )?;
+
+    event::emit(EventData::LiquidityAdded {
+        booth: *exchange_booth_acc.key,
+        provider: *provider.key,
+        amount_a: received[0],
+        amount_b: received[1],
+        shares,
+    })?;
+
+    Ok(())
+}
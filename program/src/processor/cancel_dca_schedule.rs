@@ -0,0 +1,62 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::escrow,
+    state::DcaSchedule,
+};
+
+use borsh::BorshDeserialize;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let dca_schedule_acc = next_account_info(account_info_iter)?;
+    let escrow = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let owner_from_token_acc = next_account_info(account_info_iter)?;
+    let from_mint_acc = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        msg!("error: owner must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [dca_schedule_acc, escrow, owner, owner_from_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    if dca_schedule_acc.owner != program_id {
+        msg!("error: DCA schedule not owned by this program");
+        return Err(ExchangeBoothError::InvalidAccountOwner.into())
+    }
+    let dca_schedule = DcaSchedule::try_from_slice(&dca_schedule_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if dca_schedule.escrow != *escrow.key
+        || dca_schedule.owner != *owner.key
+        || dca_schedule.owner_from_token_account != *owner_from_token_acc.key
+        || dca_schedule.from_mint != *from_mint_acc.key
+    {
+        msg!("error: accounts do not match DCA schedule {}", dca_schedule_acc.key);
+        return Err(ExchangeBoothError::DcaScheduleMismatch.into())
+    }
+
+    msg!("Cancelling DCA schedule {} with {} executions left", dca_schedule_acc.key, dca_schedule.remaining);
+    escrow::close(program_id, b"dca_escrow", dca_schedule_acc, escrow, owner, owner_from_token_acc, from_mint_acc, token_program)?;
+    event::emit(EventData::DcaScheduleCancelled {
+        schedule: *dca_schedule_acc.key,
+        owner: *owner.key,
+        remaining: dca_schedule.remaining,
+    })
+}
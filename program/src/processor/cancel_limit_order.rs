@@ -0,0 +1,77 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::escrow,
+    state::LimitOrder,
+};
+
+use borsh::BorshDeserialize;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let limit_order_acc = next_account_info(account_info_iter)?;
+    let escrow = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let owner_from_token_acc = next_account_info(account_info_iter)?;
+    let from_mint_acc = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        msg!("error: owner must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    let limit_order = load_limit_order(program_id, limit_order_acc, escrow, owner, owner_from_token_acc)?;
+    if limit_order.from_mint != *from_mint_acc.key {
+        msg!("error: limit order escrows {}, not {}", limit_order.from_mint, from_mint_acc.key);
+        return Err(ExchangeBoothError::LimitOrderMismatch.into())
+    }
+
+    msg!("Cancelling limit order {}", limit_order_acc.key);
+    escrow::close(program_id, b"limit_order_escrow", limit_order_acc, escrow, owner, owner_from_token_acc, from_mint_acc, token_program)?;
+    event::emit(EventData::LimitOrderClosed {
+        order: *limit_order_acc.key,
+        owner: *owner.key,
+        filled: false,
+    })
+}
+
+// Checks the order is ours and matches the accounts that are supposed to get its funds back.
+pub(crate) fn load_limit_order<'a>(
+    program_id: &Pubkey,
+    limit_order_acc: &AccountInfo<'a>,
+    escrow: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+    owner_from_token_acc: &AccountInfo<'a>,
+) -> Result<LimitOrder, ExchangeBoothError> {
+    for account in [limit_order_acc, escrow, owner, owner_from_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable)
+        }
+    }
+    if limit_order_acc.owner != program_id {
+        msg!("error: limit order not owned by this program");
+        return Err(ExchangeBoothError::InvalidAccountOwner)
+    }
+    let limit_order = LimitOrder::try_from_slice(&limit_order_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if limit_order.escrow != *escrow.key
+        || limit_order.owner != *owner.key
+        || limit_order.owner_from_token_account != *owner_from_token_acc.key
+    {
+        msg!("error: accounts do not match limit order {}", limit_order_acc.key);
+        return Err(ExchangeBoothError::LimitOrderMismatch)
+    }
+    Ok(limit_order)
+}
@@ -1,21 +1,119 @@
 use solana_program::{
-    account_info::{AccountInfo},
+    account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    msg,
     pubkey::Pubkey
 };
 
 use crate::{
     error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::{registry, token, validate},
     state::ExchangeBooth,
 };
 
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshDeserialize;
 
 
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    // ???
 ) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let booth_registry = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let lp_mint_acc = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        msg!("error: Admin must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [exchange_booth_acc, vault_a, vault_b, admin, booth_registry, lp_mint_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if exchange_booth.admin != *admin.key {
+        msg!("error: signer is not the booth admin");
+        return Err(ExchangeBoothError::IncorrectAdmin.into())
+    }
+    if exchange_booth.vault_a != *vault_a.key || exchange_booth.vault_b != *vault_b.key {
+        msg!("error: vaults do not belong to the booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[vault_a, vault_b, lp_mint_acc])?;
+
+    if exchange_booth.lp_mint != *lp_mint_acc.key {
+        msg!("error: lp mint does not belong to the booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    let lp_mint = token::unpack_mint(&lp_mint_acc.try_borrow_data()?)?;
+    if lp_mint.supply > 0 {
+        msg!("error: {} lp shares outstanding", lp_mint.supply);
+        return Err(ExchangeBoothError::LiquidityOutstanding.into())
+    }
+
+    // the vaults know their mints, which are part of both the vault and the registry seeds
+    let mut mints = Vec::with_capacity(2);
+    for vault in [vault_a, vault_b] {
+        let vault_account = token::unpack_account(&vault.try_borrow_data()?)?;
+        if vault_account.amount > 0 {
+            msg!("error: vault {} still holds {}", vault.key, vault_account.amount);
+            return Err(ExchangeBoothError::BoothNotEmpty.into())
+        }
+        let (_, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"exchange_booth",
+                admin.key.as_ref(),
+                exchange_booth_acc.key.as_ref(),
+                vault_account.mint.as_ref()
+            ],
+            program_id,
+        );
+        token::close_account(
+            token_program,
+            vault,
+            admin,
+            vault,
+            &[&[b"exchange_booth", admin.key.as_ref(), exchange_booth_acc.key.as_ref(), vault_account.mint.as_ref(), &[bump_seed]]]
+        )?;
+        mints.push(vault_account.mint);
+    }
+
+    let (_, bump_seed_lp) = Pubkey::find_program_address(
+        &[b"exchange_booth", admin.key.as_ref(), exchange_booth_acc.key.as_ref(), b"lp_mint"],
+        program_id,
+    );
+    token::retire_mint(
+        token_program,
+        lp_mint_acc,
+        admin,
+        lp_mint_acc,
+        &[&[b"exchange_booth", admin.key.as_ref(), exchange_booth_acc.key.as_ref(), b"lp_mint", &[bump_seed_lp]]]
+    )?;
+
+    registry::remove(program_id, booth_registry, &mints[0], &mints[1], exchange_booth_acc.key, admin)?;
+
+    msg!("Closing exchange booth {}", exchange_booth_acc.key);
+    **admin.lamports.borrow_mut() = admin.lamports()
+        .checked_add(exchange_booth_acc.lamports())
+        .ok_or(ExchangeBoothError::InvalidAccountData)?;
+    **exchange_booth_acc.lamports.borrow_mut() = 0;
+    exchange_booth_acc.data.borrow_mut().fill(0);
+    event::emit(EventData::BoothClosed {
+        booth: *exchange_booth_acc.key,
+        admin: *admin.key,
+    })?;
+
     Ok(())
-}
\ No newline at end of file
+}
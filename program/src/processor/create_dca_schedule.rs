@@ -0,0 +1,174 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    pricing,
+    processor::{escrow, pda, token, validate},
+    state::{DcaSchedule, ExchangeBooth, DCA_SCHEDULE_LEN},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    schedule_id: u64,
+    amount: f64,
+    interval_seconds: i64,
+    count: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let dca_schedule_acc = next_account_info(account_info_iter)?;
+    let escrow = next_account_info(account_info_iter)?;
+    let from_mint_acc = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let owner_from_token_acc = next_account_info(account_info_iter)?;
+    let owner_to_token_acc = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        msg!("error: owner must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [dca_schedule_acc, escrow, owner_from_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    if !(amount > 0.0 && interval_seconds > 0 && count > 0) {
+        msg!("error: amount, interval and count must be positive");
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    validate::check_system_program(system_program)?;
+    validate::check_rent_sysvar(rent_account)?;
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[vault_a, vault_b, from_mint_acc, owner_from_token_acc, owner_to_token_acc])?;
+
+    let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if exchange_booth.vault_a != *vault_a.key || exchange_booth.vault_b != *vault_b.key {
+        msg!("error: vaults do not belong to the exchange booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    // the schedule must sell one booth token for the other, into accounts the owner holds
+    let mint_a = token::unpack_account(&vault_a.try_borrow_data()?)?.mint;
+    let mint_b = token::unpack_account(&vault_b.try_borrow_data()?)?.mint;
+    let to_mint = if *from_mint_acc.key == mint_a {
+        mint_b
+    } else if *from_mint_acc.key == mint_b {
+        mint_a
+    } else {
+        msg!("error: {} is not traded by this booth", from_mint_acc.key);
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    };
+    let owner_from_token_account = token::unpack_account(&owner_from_token_acc.try_borrow_data()?)?;
+    let owner_to_token_account = token::unpack_account(&owner_to_token_acc.try_borrow_data()?)?;
+    if owner_from_token_account.mint != *from_mint_acc.key || owner_to_token_account.mint != to_mint {
+        msg!("error: owner token accounts do not match the traded mints");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if owner_from_token_account.owner != *owner.key || owner_to_token_account.owner != *owner.key {
+        msg!("error: owner token accounts are not held by the owner");
+        return Err(ExchangeBoothError::InvalidAccountOwner.into())
+    }
+
+    let (dca_schedule_key, schedule_bump_seed) = Pubkey::find_program_address(
+        &[
+            b"dca_schedule",
+            exchange_booth_acc.key.as_ref(),
+            owner.key.as_ref(),
+            &schedule_id.to_le_bytes(),
+        ],
+        program_id,
+    );
+    if dca_schedule_key != *dca_schedule_acc.key {
+        msg!("error: DCA schedule PDA mismatch");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    pda::create_account(
+        program_id,
+        dca_schedule_acc,
+        DCA_SCHEDULE_LEN,
+        &[b"dca_schedule", exchange_booth_acc.key.as_ref(), owner.key.as_ref(), &schedule_id.to_le_bytes(), &[schedule_bump_seed]],
+        owner,
+        system_program,
+    )?;
+
+    escrow::create(
+        program_id,
+        b"dca_escrow",
+        dca_schedule_acc,
+        escrow,
+        from_mint_acc,
+        owner,
+        system_program,
+        token_program,
+        rent_account,
+    )?;
+
+    // escrow what the executions will sell, computed the same way Exchange does. With a
+    // transfer fee the escrow gets less, so each execution sells an even share of that
+    let from_mint = token::unpack_mint(&from_mint_acc.try_borrow_data()?)?;
+    let amount_small = pricing::to_base_units(amount, from_mint.decimals);
+    let total_small = amount_small
+        .checked_mul(count)
+        .ok_or(ExchangeBoothError::InvalidInstructionInput)?;
+    let received_small = token::transfer_checked(token_program, owner_from_token_acc, from_mint_acc, escrow, owner, total_small, &[])?;
+    let amount_small = received_small / count;
+    if amount_small == 0 {
+        msg!("error: {} received in escrow is not enough for {} executions", received_small, count);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    let amount = pricing::to_ui_amount(amount_small, from_mint.decimals);
+
+    let dca_schedule = DcaSchedule {
+        exchange_booth: *exchange_booth_acc.key,
+        owner: *owner.key,
+        escrow: *escrow.key,
+        from_mint: *from_mint_acc.key,
+        owner_from_token_account: *owner_from_token_acc.key,
+        owner_to_token_account: *owner_to_token_acc.key,
+        amount,
+        interval_seconds,
+        next_execution_ts: Clock::get()?.unix_timestamp,
+        remaining: count
+    };
+    dca_schedule.serialize(&mut &mut dca_schedule_acc.data.borrow_mut()[..])?;
+
+    msg!("DCA schedule {} sells {} ({}) of {} every {}s, {} times",
+        dca_schedule_acc.key,
+        amount,
+        amount_small,
+        from_mint_acc.key,
+        interval_seconds,
+        count
+    );
+    event::emit(EventData::DcaScheduleCreated {
+        booth: *exchange_booth_acc.key,
+        schedule: *dca_schedule_acc.key,
+        owner: *owner.key,
+        from_mint: *from_mint_acc.key,
+        amount: amount_small,
+        count,
+    })?;
+    Ok(())
+}
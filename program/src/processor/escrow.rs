@@ -0,0 +1,82 @@
+// Token escrows owned by program state accounts (limit orders, DCA schedules).
+// An escrow is a PDA token account of [seed_prefix, state account] and, like the
+// booth vaults, is its own authority.
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{error::ExchangeBoothError, processor::{pda, token}};
+
+
+pub(crate) fn bump_seed(program_id: &Pubkey, seed_prefix: &[u8], state_acc: &AccountInfo) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seed_prefix, state_acc.key.as_ref()], program_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create<'a>(
+    program_id: &Pubkey,
+    seed_prefix: &[u8],
+    state_acc: &AccountInfo<'a>,
+    escrow: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    rent_account: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (escrow_key, escrow_bump_seed) = bump_seed(program_id, seed_prefix, state_acc);
+    if escrow_key != *escrow.key {
+        msg!("error: escrow PDA mismatch");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    token::check_program(token_program)?;
+    let escrow_len = token::account_len(token_program, mint)?;
+    pda::create_account(
+        token_program.key,
+        escrow,
+        escrow_len,
+        &[seed_prefix, state_acc.key.as_ref(), &[escrow_bump_seed]],
+        payer,
+        system_program,
+    )?;
+    token::initialize_account(token_program, escrow, mint, escrow.key, rent_account)?;
+
+    Ok(())
+}
+
+// Refunds whatever is left in the escrow, closes it and hands the state account's lamports to the owner.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn close<'a>(
+    program_id: &Pubkey,
+    seed_prefix: &[u8],
+    state_acc: &AccountInfo<'a>,
+    escrow: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+    owner_from_token_acc: &AccountInfo<'a>,
+    from_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[escrow, owner_from_token_acc, from_mint])?;
+    let (_, escrow_bump_seed) = bump_seed(program_id, seed_prefix, state_acc);
+    let escrow_seeds: &[&[u8]] = &[seed_prefix, state_acc.key.as_ref(), &[escrow_bump_seed]];
+
+    let leftover = token::balance(escrow)?;
+    if leftover > 0 {
+        token::transfer_checked(token_program, escrow, from_mint, owner_from_token_acc, escrow, leftover, &[escrow_seeds])?;
+    }
+    token::close_account(token_program, escrow, owner, escrow, &[escrow_seeds])?;
+
+    msg!("Returning {} of escrow {} to {}", leftover, escrow.key, owner.key);
+    **owner.lamports.borrow_mut() = owner.lamports()
+        .checked_add(state_acc.lamports())
+        .ok_or(ExchangeBoothError::InvalidAccountData)?;
+    **state_acc.lamports.borrow_mut() = 0;
+    state_acc.data.borrow_mut().fill(0);
+
+    Ok(())
+}
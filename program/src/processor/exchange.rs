@@ -4,19 +4,22 @@ use solana_program::{
     msg, 
     program_error::ProgramError,
     pubkey::Pubkey,
-    program_pack::Pack,
-    program::{invoke_signed, invoke},
 };
 
 use crate::{
     error::ExchangeBoothError,
+    event::{self, EventData},
+    instruction::TradeDirection,
+    pricing,
+    processor::{oracle, token, validate},
     state::ExchangeBooth,
-    state::ExchangeRate
 };
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use spl_token::state::Account as TokenAccount;
-use spl_token::state::Mint as Mint;
+
+
+// number of accounts Exchange settles with, instructions built on it take theirs after these
+pub(crate) const EXCHANGE_ACCOUNTS_LEN: usize = 11;
 
 
 pub fn process(
@@ -24,6 +27,72 @@ pub fn process(
     accounts: &[AccountInfo],
     amount: f64,
 ) -> ProgramResult {
+    // an associated token program after the exchange accounts opts into creating the destination
+    if let Some(associated_token_program) = accounts.get(EXCHANGE_ACCOUNTS_LEN) {
+        create_destination_ata(accounts, associated_token_program)?;
+    }
+    execute(program_id, accounts, amount, &SettlementOptions::default())?;
+    Ok(())
+}
+
+// Checks customer_to_token_account is the customer's associated token account for the mint
+// they buy, and creates it (paid by the customer) if it doesn't exist yet.
+fn create_destination_ata<'a>(accounts: &[AccountInfo<'a>], associated_token_program: &AccountInfo<'a>) -> ProgramResult {
+    let mint_a_acc = &accounts[4];
+    let mint_b_acc = &accounts[5];
+    let customer = &accounts[6];
+    let customer_from_token_acc = &accounts[7];
+    let customer_to_token_acc = &accounts[8];
+    let system_program = &accounts[9];
+    let token_program = &accounts[10];
+
+    token::check_associated_token_program(associated_token_program)?;
+    validate::check_system_program(system_program)?;
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[mint_a_acc, mint_b_acc, customer_from_token_acc])?;
+    let from_mint = token::unpack_account(&customer_from_token_acc.try_borrow_data()?)?.mint;
+    let to_mint_acc = if from_mint == *mint_a_acc.key {mint_b_acc} else {mint_a_acc};
+    let ata = token::associated_token_address(customer.key, to_mint_acc.key, token_program.key);
+    if ata != *customer_to_token_acc.key {
+        msg!("error: customer to token account is not the associated token account {}", ata);
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if customer_to_token_acc.data_is_empty() {
+        msg!("Creating associated token account {}", ata);
+        token::create_associated_token_account(
+            associated_token_program,
+            customer,
+            customer_to_token_acc,
+            customer,
+            to_mint_acc,
+            system_program,
+            token_program,
+        )?;
+    }
+    Ok(())
+}
+
+// How a settlement differs from a plain Exchange signed by the customer.
+#[derive(Default)]
+pub(crate) struct SettlementOptions<'a> {
+    // replaces the oracle rate (and inventory skew) when the trade was priced elsewhere
+    pub quoted_a_to_b: Option<f64>,
+    // fail unless the customer sells the side the quote was issued for
+    pub quoted_direction: Option<TradeDirection>,
+    // skip the trade, without failing, when the customer would get a worse rate than this
+    pub min_rate: Option<f64>,
+    // signer seeds when the customer is a PDA of this program (e.g. an escrow) rather than a signer
+    pub customer_seeds: &'a [&'a [u8]],
+}
+
+// Shared by every instruction that settles like Exchange, over the same account list.
+// Returns false if the trade was skipped because of SettlementOptions::min_rate.
+pub(crate) fn execute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+    options: &SettlementOptions,
+) -> Result<bool, ProgramError> {
     let account_info_iter = &mut accounts.iter();
 
     let exchange_booth_acc = next_account_info(account_info_iter)?;
@@ -38,15 +107,22 @@ pub fn process(
     let system_program = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
 
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    validate::check_system_program(system_program)?;
+    token::check_program(token_program)?;
+    token::check_owner(
+        token_program,
+        &[vault_a, vault_b, mint_a_acc, mint_b_acc, customer_from_token_acc, customer_to_token_acc]
+    )?;
+
     //pulling out data
     let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())?;
-    let exchange_rate = ExchangeRate::try_from_slice(&oracle.data.borrow())?;
-    let vault_a_token_account = TokenAccount::unpack_from_slice(&vault_a.try_borrow_data()?)?;
-    let vault_b_token_account = TokenAccount::unpack_from_slice(&vault_b.try_borrow_data()?)?;
-    let mint_a = Mint::unpack_from_slice(&mint_a_acc.try_borrow_data()?)?;
-    let mint_b = Mint::unpack_from_slice(&mint_b_acc.try_borrow_data()?)?;
-    let customer_from_token_account = TokenAccount::unpack_from_slice(&customer_from_token_acc.try_borrow_data()?)?;
-    let customer_to_token_account = TokenAccount::unpack_from_slice(&customer_to_token_acc.try_borrow_data()?)?;
+    let vault_a_token_account = token::unpack_account(&vault_a.try_borrow_data()?)?;
+    let vault_b_token_account = token::unpack_account(&vault_b.try_borrow_data()?)?;
+    let mint_a = token::unpack_mint(&mint_a_acc.try_borrow_data()?)?;
+    let mint_b = token::unpack_mint(&mint_b_acc.try_borrow_data()?)?;
+    let customer_from_token_account = token::unpack_account(&customer_from_token_acc.try_borrow_data()?)?;
+    let customer_to_token_account = token::unpack_account(&customer_to_token_acc.try_borrow_data()?)?;
 
     //checking writable/signable
     if !vault_a.is_writable {
@@ -57,7 +133,7 @@ pub fn process(
         msg!("Vault_B is not set to is_writable");
         return Err(ProgramError::MissingRequiredSignature);
     }
-    if !customer.is_signer {
+    if !customer.is_signer && options.customer_seeds.is_empty() {
         msg!("Customer is not set to is_signable");
         return Err(ProgramError::MissingRequiredSignature);
     }
@@ -108,7 +184,6 @@ pub fn process(
 
     //figure out the direction
     let mut exchange_from_a: bool = false;
-    let mut rate = exchange_rate.b_to_a;
     let mut from_decimal = mint_b.decimals;
     let mut from_token = "B";
     let mut to_decimal = mint_a.decimals;
@@ -116,47 +191,70 @@ pub fn process(
 
     if customer_from_token_account.mint == *mint_a_acc.key {
         exchange_from_a = true;
-        rate = exchange_rate.a_to_b;
         from_decimal = mint_a.decimals;
         from_token = "A";
         to_decimal = mint_b.decimals;
         to_token = "B";
     }
+    if let Some(direction) = options.quoted_direction {
+        if exchange_from_a != (direction == TradeDirection::AToB) {
+            msg!("error: quote is for {:?}, customer sells {}", direction, from_token);
+            return Err(ExchangeBoothError::QuoteDirectionMismatch.into())
+        }
+    }
 
-    let result = amount * rate;
-    let amount_small: u64 = (amount * f64::powf(10., from_decimal.into())) as u64;
-    let result_small: u64 = (result * f64::powf(10., to_decimal.into())) as u64;
-    msg!("Customer is exchanging {} ({}) token {} for {} ({}) token {} with exchange rate {}",
+    let rate = if let Some(a_to_b) = options.quoted_a_to_b {
+        let rate = if exchange_from_a {a_to_b} else {1.0 / a_to_b};
+        msg!("Using quoted rate {} instead of the oracle", rate);
+        rate
+    } else {
+        let exchange_rate = oracle::load_rate(program_id, &exchange_booth, oracle)?;
+        let rate = pricing::booth_rate(
+            &exchange_booth,
+            &exchange_rate,
+            pricing::to_ui_amount(vault_a_token_account.amount, mint_a.decimals),
+            pricing::to_ui_amount(vault_b_token_account.amount, mint_b.decimals),
+            exchange_from_a,
+        );
+        let oracle_rate = if exchange_from_a {exchange_rate.a_to_b} else {exchange_rate.b_to_a};
+        msg!("Oracle rate {}, booth rate {} after skew and confidence", oracle_rate, rate);
+        rate
+    };
+
+    if let Some(min_rate) = options.min_rate {
+        if rate < min_rate {
+            msg!("Rate {} is below the minimum {}, skipping", rate, min_rate);
+            return Ok(false);
+        }
+    }
+
+    let amount_small = pricing::to_base_units(amount, from_decimal);
+
+    //debit customers FROM TOKEN account, credit the corresponding vault
+    msg!("Transfering token {}", from_token);
+    let received_small = token::transfer_checked(
+        token_program,
+        customer_from_token_acc,
+        if exchange_from_a {mint_a_acc} else {mint_b_acc},
+        if exchange_from_a {vault_a} else {vault_b},
+        customer,
+        amount_small,
+        &[options.customer_seeds]
+    )?;
+
+    // price what the vault received, a transfer fee on the way in is the customer's
+    let result_small = pricing::amount_out(received_small, from_decimal, to_decimal, rate);
+    msg!("Customer is exchanging {} ({}, {} received) token {} for {} ({}) token {} with exchange rate {}",
         amount,
         amount_small,
+        received_small,
         from_token,
-        result,
+        pricing::to_ui_amount(result_small, to_decimal),
         result_small,
         to_token,
         rate
     );
 
-    //debit customers FROM TOKEN account, credit the corresponding vault
-    let transfer_from_customer_to_vault_ix = spl_token::instruction::transfer(
-        token_program.key,
-        customer_from_token_acc.key,
-        if exchange_from_a {&exchange_booth.vault_a} else {&exchange_booth.vault_b},
-        &customer.key,
-        &[&customer.key],
-        amount_small
-    )?;
-    msg!("Transfering token {}", from_token);
-    invoke(
-        &transfer_from_customer_to_vault_ix,
-        &[
-            customer.clone(),
-            customer_from_token_acc.clone(),
-            if exchange_from_a {vault_a.clone()} else{vault_b.clone()},
-            token_program.clone(),
-        ]
-    )?;
-   
-    
     //generate PDA for the bump seed
     let (_, bump_seed) = Pubkey::find_program_address(
         &[
@@ -168,23 +266,14 @@ pub fn process(
         program_id,
     );
     //debit other vault, and credit the customer's TO TOKEN account
-    let transfer_vault_to_to_customer_ix = spl_token::instruction::transfer(
-        token_program.key,
-        if exchange_from_a {&exchange_booth.vault_b} else {&exchange_booth.vault_a},
-        customer_to_token_acc.key,
-        if exchange_from_a {&exchange_booth.vault_b} else {&exchange_booth.vault_a},
-        &[if exchange_from_a {&exchange_booth.vault_b} else {&exchange_booth.vault_a}],
-        result_small
-    )?;
     msg!("Transfering token {}", to_token);
-    invoke_signed(
-        &transfer_vault_to_to_customer_ix,
-        &[
-            customer.clone(),
-            customer_to_token_acc.clone(),
-            if exchange_from_a {vault_b.clone()} else{vault_a.clone()},
-            token_program.clone(),
-        ],
+    let paid_small = token::transfer_checked(
+        token_program,
+        if exchange_from_a {vault_b} else {vault_a},
+        if exchange_from_a {mint_b_acc} else {mint_a_acc},
+        customer_to_token_acc,
+        if exchange_from_a {vault_b} else {vault_a},
+        result_small,
         &[&[
             b"exchange_booth",
             exchange_booth.admin.as_ref(),
@@ -194,7 +283,20 @@ pub fn process(
         ]],
     )?;
 
+    event::emit(EventData::Trade {
+        booth: *exchange_booth_acc.key,
+        customer: *customer.key,
+        from_mint: if exchange_from_a {*mint_a_acc.key} else {*mint_b_acc.key},
+        to_mint: if exchange_from_a {*mint_b_acc.key} else {*mint_a_acc.key},
+        amount_in: amount_small,
+        amount_out: paid_small,
+        rate,
+        fee_in: amount_small.saturating_sub(received_small),
+        fee_out: result_small.saturating_sub(paid_small),
+    })?;
+
 
     //spl_token::instruction::initialize_account(token_program_id: &Pubkey, account_pubkey: &Pubkey, mint_pubkey: &Pubkey, owner_pubkey: &Pubkey)
-    Ok(())
-}
\ No newline at end of file
+    Ok(true)
+}
+
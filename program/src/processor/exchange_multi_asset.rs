@@ -0,0 +1,154 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    pricing,
+    processor::{token, validate},
+    state::{MultiAssetBooth, MultiAssetRate},
+};
+
+use borsh::BorshDeserialize;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+    from_index: u8,
+    to_index: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let booth_acc = next_account_info(account_info_iter)?;
+    let oracle = next_account_info(account_info_iter)?;
+    let from_vault = next_account_info(account_info_iter)?;
+    let to_vault = next_account_info(account_info_iter)?;
+    let from_mint_acc = next_account_info(account_info_iter)?;
+    let to_mint_acc = next_account_info(account_info_iter)?;
+    let customer = next_account_info(account_info_iter)?;
+    let customer_from_token_acc = next_account_info(account_info_iter)?;
+    let customer_to_token_acc = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !customer.is_signer {
+        msg!("error: customer must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [from_vault, to_vault, customer_from_token_acc, customer_to_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    validate::check_program_owned(program_id, &[booth_acc, oracle])?;
+
+    let booth = MultiAssetBooth::try_from_slice(&booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if booth.oracle != *oracle.key {
+        msg!("error: oracle does not belong to the booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    let rate = MultiAssetRate::try_from_slice(&oracle.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+
+    let (from_index, to_index) = (from_index as usize, to_index as usize);
+    if from_index == to_index || from_index >= booth.assets.len() || to_index >= booth.assets.len() {
+        msg!("error: invalid asset pair {} -> {} for {} assets", from_index, to_index, booth.assets.len());
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    if rate.prices.len() != booth.assets.len() {
+        msg!("error: oracle prices {} assets, booth has {}", rate.prices.len(), booth.assets.len());
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+    let from_asset = &booth.assets[from_index];
+    let to_asset = &booth.assets[to_index];
+    if from_asset.vault != *from_vault.key || to_asset.vault != *to_vault.key {
+        msg!("error: vaults do not match the booth assets");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if from_asset.mint != *from_mint_acc.key || to_asset.mint != *to_mint_acc.key {
+        msg!("error: mints do not match the booth assets");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    token::check_program(token_program)?;
+    token::check_owner(
+        token_program,
+        &[from_vault, to_vault, from_mint_acc, to_mint_acc, customer_from_token_acc, customer_to_token_acc]
+    )?;
+    let customer_from_token_account = token::unpack_account(&customer_from_token_acc.try_borrow_data()?)?;
+    let customer_to_token_account = token::unpack_account(&customer_to_token_acc.try_borrow_data()?)?;
+    if customer_from_token_account.mint != from_asset.mint || customer_to_token_account.mint != to_asset.mint {
+        msg!("error: customer token accounts do not match the traded mints");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    if !(rate.prices[from_index] > 0.0 && rate.prices[to_index] > 0.0) {
+        msg!("error: oracle has not priced assets {} and {} yet", from_index, to_index);
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+    // both prices are in the quote asset, so their ratio is the cross rate
+    let cross_rate = rate.prices[from_index] / rate.prices[to_index];
+    let from_mint = token::unpack_mint(&from_mint_acc.try_borrow_data()?)?;
+    let to_mint = token::unpack_mint(&to_mint_acc.try_borrow_data()?)?;
+    let amount_small = pricing::to_base_units(amount, from_mint.decimals);
+    let received_small = token::transfer_checked(
+        token_program,
+        customer_from_token_acc,
+        from_mint_acc,
+        from_vault,
+        customer,
+        amount_small,
+        &[]
+    )?;
+
+    // price what the vault received, a transfer fee on the way in is the customer's
+    let result_small = pricing::amount_out(received_small, from_mint.decimals, to_mint.decimals, cross_rate);
+    msg!("Customer is exchanging {} ({}, {} received) of asset {} for {} ({}) of asset {} with exchange rate {}",
+        amount,
+        amount_small,
+        received_small,
+        from_index,
+        pricing::to_ui_amount(result_small, to_mint.decimals),
+        result_small,
+        to_index,
+        cross_rate
+    );
+
+    let (_, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"exchange_booth",
+            booth.admin.as_ref(),
+            booth_acc.key.as_ref(),
+            to_asset.mint.as_ref(),
+        ],
+        program_id,
+    );
+    let paid_small = token::transfer_checked(
+        token_program,
+        to_vault,
+        to_mint_acc,
+        customer_to_token_acc,
+        to_vault,
+        result_small,
+        &[&[b"exchange_booth", booth.admin.as_ref(), booth_acc.key.as_ref(), to_asset.mint.as_ref(), &[bump_seed]]]
+    )?;
+
+    event::emit(EventData::Trade {
+        booth: *booth_acc.key,
+        customer: *customer.key,
+        from_mint: *from_mint_acc.key,
+        to_mint: *to_mint_acc.key,
+        amount_in: amount_small,
+        amount_out: paid_small,
+        rate: cross_rate,
+        fee_in: amount_small.saturating_sub(received_small),
+        fee_out: result_small.saturating_sub(paid_small),
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    pricing,
+    processor::{oracle, token, validate},
+    state::ExchangeBooth,
+};
+
+use borsh::BorshDeserialize;
+
+// customer accounts shared by the whole route, then this many accounts per booth
+const CUSTOMER_ACCOUNTS_LEN: usize = 4;
+const HOP_ACCOUNTS_LEN: usize = 6;
+
+// One priced leg of the route: tokens come into `in_vault` and leave `out_vault` at `rate`.
+struct Hop<'a, 'b> {
+    exchange_booth_acc: &'b AccountInfo<'a>,
+    admin: Pubkey,
+    in_vault: &'b AccountInfo<'a>,
+    out_vault: &'b AccountInfo<'a>,
+    in_mint: &'b AccountInfo<'a>,
+    out_mint: &'b AccountInfo<'a>,
+    rate: f64,
+    in_decimals: u8,
+    out_decimals: u8,
+}
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+    min_amount_out: f64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let customer = next_account_info(account_info_iter)?;
+    let customer_from_token_acc = next_account_info(account_info_iter)?;
+    let customer_to_token_acc = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !customer.is_signer {
+        msg!("error: customer must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [customer_from_token_acc, customer_to_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    let hop_accounts = &accounts[CUSTOMER_ACCOUNTS_LEN..];
+    if hop_accounts.is_empty() || hop_accounts.len() % HOP_ACCOUNTS_LEN != 0 {
+        msg!("error: expected {} accounts per booth in the route", HOP_ACCOUNTS_LEN);
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[customer_from_token_acc, customer_to_token_acc])?;
+    let customer_from_token_account = token::unpack_account(&customer_from_token_acc.try_borrow_data()?)?;
+    let customer_to_token_account = token::unpack_account(&customer_to_token_acc.try_borrow_data()?)?;
+
+    // price every hop first, each one selling what the previous one bought
+    let mut from_mint = customer_from_token_account.mint;
+    let mut hops: Vec<Hop> = Vec::with_capacity(hop_accounts.len() / HOP_ACCOUNTS_LEN);
+    for (index, hop) in hop_accounts.chunks(HOP_ACCOUNTS_LEN).enumerate() {
+        let exchange_booth_acc = &hop[0];
+        let oracle = &hop[1];
+        let vault_a = &hop[2];
+        let vault_b = &hop[3];
+        let mint_a_acc = &hop[4];
+        let mint_b_acc = &hop[5];
+
+        // a booth priced twice would quote its second hop from balances the first one already moved
+        if hops.iter().any(|previous| previous.exchange_booth_acc.key == exchange_booth_acc.key) {
+            msg!("error: hop {} repeats booth {}", index, exchange_booth_acc.key);
+            return Err(ExchangeBoothError::InvalidInstructionInput.into())
+        }
+        validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+        token::check_owner(token_program, &[vault_a, vault_b, mint_a_acc, mint_b_acc])?;
+        let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+            .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+        if exchange_booth.vault_a != *vault_a.key || exchange_booth.vault_b != *vault_b.key {
+            msg!("error: hop {} vaults do not belong to the booth", index);
+            return Err(ExchangeBoothError::InvalidAccountAddress.into())
+        }
+        for vault in [vault_a, vault_b] {
+            if !vault.is_writable {
+                msg!("error: {} not writable", vault.key);
+                return Err(ExchangeBoothError::AccountMustBeWritable.into())
+            }
+        }
+        let exchange_rate = oracle::load_rate(program_id, &exchange_booth, oracle)?;
+        let vault_a_token_account = token::unpack_account(&vault_a.try_borrow_data()?)?;
+        let vault_b_token_account = token::unpack_account(&vault_b.try_borrow_data()?)?;
+        if vault_a_token_account.mint != *mint_a_acc.key || vault_b_token_account.mint != *mint_b_acc.key {
+            msg!("error: hop {} mints do not match the booth vaults", index);
+            return Err(ExchangeBoothError::InvalidAccountAddress.into())
+        }
+        let mint_a = token::unpack_mint(&mint_a_acc.try_borrow_data()?)?;
+        let mint_b = token::unpack_mint(&mint_b_acc.try_borrow_data()?)?;
+
+        let exchange_from_a = if from_mint == *mint_a_acc.key {
+            true
+        } else if from_mint == *mint_b_acc.key {
+            false
+        } else {
+            msg!("error: hop {} booth does not trade {}", index, from_mint);
+            return Err(ExchangeBoothError::InvalidAccountData.into())
+        };
+        let rate = pricing::booth_rate(
+            &exchange_booth,
+            &exchange_rate,
+            pricing::to_ui_amount(vault_a_token_account.amount, mint_a.decimals),
+            pricing::to_ui_amount(vault_b_token_account.amount, mint_b.decimals),
+            exchange_from_a,
+        );
+        let oracle_rate = if exchange_from_a {exchange_rate.a_to_b} else {exchange_rate.b_to_a};
+        msg!("Hop {}: booth {} at rate {} (oracle rate {})", index, exchange_booth_acc.key, rate, oracle_rate);
+
+        from_mint = if exchange_from_a {*mint_b_acc.key} else {*mint_a_acc.key};
+        hops.push(Hop {
+            exchange_booth_acc,
+            admin: exchange_booth.admin,
+            in_vault: if exchange_from_a {vault_a} else {vault_b},
+            out_vault: if exchange_from_a {vault_b} else {vault_a},
+            in_mint: if exchange_from_a {mint_a_acc} else {mint_b_acc},
+            out_mint: if exchange_from_a {mint_b_acc} else {mint_a_acc},
+            rate,
+            in_decimals: if exchange_from_a {mint_a.decimals} else {mint_b.decimals},
+            out_decimals: if exchange_from_a {mint_b.decimals} else {mint_a.decimals},
+        });
+    }
+
+    if customer_to_token_account.mint != from_mint {
+        msg!("error: route ends in {}, customer to token account holds {}", from_mint, customer_to_token_account.mint);
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+
+    // the customer pays the first booth, then each booth pays the next one directly. Every hop
+    // prices what its vault actually received, so transfer fees along the way are accounted for
+    let first_mint = token::unpack_mint(&hops[0].in_mint.try_borrow_data()?)?;
+    let amount_small = pricing::to_base_units(amount, first_mint.decimals);
+    let mut received_small = token::transfer_checked(
+        token_program,
+        customer_from_token_acc,
+        hops[0].in_mint,
+        hops[0].in_vault,
+        customer,
+        amount_small,
+        &[]
+    )?;
+    let mut sent_small = amount_small;
+    for (index, hop) in hops.iter().enumerate() {
+        let destination = match hops.get(index + 1) {
+            Some(next_hop) => next_hop.in_vault,
+            None => customer_to_token_acc,
+        };
+        let result_small = pricing::amount_out(received_small, hop.in_decimals, hop.out_decimals, hop.rate);
+        msg!("Hop {}: {} in, {} out", index, received_small, result_small);
+        let (_, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"exchange_booth",
+                hop.admin.as_ref(),
+                hop.exchange_booth_acc.key.as_ref(),
+                hop.out_mint.key.as_ref(),
+            ],
+            program_id,
+        );
+        let paid_small = token::transfer_checked(
+            token_program,
+            hop.out_vault,
+            hop.out_mint,
+            destination,
+            hop.out_vault,
+            result_small,
+            &[&[b"exchange_booth", hop.admin.as_ref(), hop.exchange_booth_acc.key.as_ref(), hop.out_mint.key.as_ref(), &[bump_seed]]]
+        )?;
+        // one trade per booth, what a hop pays out is what the next one takes in
+        event::emit(EventData::Trade {
+            booth: *hop.exchange_booth_acc.key,
+            customer: *customer.key,
+            from_mint: *hop.in_mint.key,
+            to_mint: *hop.out_mint.key,
+            amount_in: sent_small,
+            amount_out: paid_small,
+            rate: hop.rate,
+            fee_in: sent_small.saturating_sub(received_small),
+            fee_out: result_small.saturating_sub(paid_small),
+        })?;
+        sent_small = result_small;
+        received_small = paid_small;
+    }
+
+    // what reached the customer, after any fee on the last transfer
+    let amount_out = pricing::to_ui_amount(received_small, hops[hops.len() - 1].out_decimals);
+    if amount_out < min_amount_out {
+        msg!("error: route returns {}, minimum is {}", amount_out, min_amount_out);
+        return Err(ExchangeBoothError::SlippageExceeded.into())
+    }
+
+    Ok(())
+}
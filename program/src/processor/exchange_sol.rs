@@ -0,0 +1,124 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    pricing,
+    processor::{
+        exchange::{self, SettlementOptions},
+        pda,
+        token,
+        validate,
+    },
+};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+    sell_sol: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let oracle = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let mint_a_acc = next_account_info(account_info_iter)?;
+    let mint_b_acc = next_account_info(account_info_iter)?;
+    let customer = next_account_info(account_info_iter)?;
+    let customer_token_acc = next_account_info(account_info_iter)?;
+    let wsol_acc = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+
+    if !customer.is_signer {
+        msg!("error: customer must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [customer, customer_token_acc, wsol_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    validate::check_system_program(system_program)?;
+    validate::check_rent_sysvar(rent_account)?;
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[mint_a_acc, mint_b_acc])?;
+    let native_mint_acc = if token::is_native_mint(mint_a_acc.key) {
+        mint_a_acc
+    } else if token::is_native_mint(mint_b_acc.key) {
+        mint_b_acc
+    } else {
+        msg!("error: neither booth mint is wrapped SOL");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    };
+
+    // the wrapped account only lives for this instruction, so one address per customer is enough
+    let (wsol_key, wsol_bump_seed) = Pubkey::find_program_address(
+        &[b"wsol", exchange_booth_acc.key.as_ref(), customer.key.as_ref()],
+        program_id,
+    );
+    if wsol_key != *wsol_acc.key {
+        msg!("error: wrapped SOL PDA mismatch");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    let wsol_len = token::account_len(token_program, native_mint_acc)?;
+    pda::create_account(
+        token_program.key,
+        wsol_acc,
+        wsol_len,
+        &[b"wsol", exchange_booth_acc.key.as_ref(), customer.key.as_ref(), &[wsol_bump_seed]],
+        customer,
+        system_program
+    )?;
+    token::initialize_account(token_program, wsol_acc, native_mint_acc, customer.key, rent_account)?;
+
+    // the customer owns the wrapped account, so Exchange settles with it like any other token account
+    let (customer_from_token_acc, customer_to_token_acc) = if sell_sol {
+        let decimals = token::unpack_mint(&native_mint_acc.try_borrow_data()?)?.decimals;
+        let lamports = pricing::to_base_units(amount, decimals);
+        msg!("Wrapping {} lamports", lamports);
+        invoke(
+            &system_instruction::transfer(customer.key, wsol_acc.key, lamports),
+            &[customer.clone(), wsol_acc.clone(), system_program.clone()]
+        )?;
+        token::sync_native(token_program, wsol_acc)?;
+        (wsol_acc, customer_token_acc)
+    } else {
+        (customer_token_acc, wsol_acc)
+    };
+    exchange::execute(
+        program_id,
+        &[
+            exchange_booth_acc.clone(),
+            oracle.clone(),
+            vault_a.clone(),
+            vault_b.clone(),
+            mint_a_acc.clone(),
+            mint_b_acc.clone(),
+            customer.clone(),
+            customer_from_token_acc.clone(),
+            customer_to_token_acc.clone(),
+            system_program.clone(),
+            token_program.clone(),
+        ],
+        amount,
+        &SettlementOptions::default(),
+    )?;
+
+    // closing a native account pays out its rent and whatever SOL it holds
+    msg!("Unwrapping {} lamports to {}", wsol_acc.lamports(), customer.key);
+    token::close_account(token_program, wsol_acc, customer, customer, &[])?;
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use solana_program::{
+    account_info::AccountInfo,
+    ed25519_program,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{
+        clock::Clock,
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+        Sysvar,
+    },
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    instruction::Quote,
+    processor::{
+        exchange::{self, SettlementOptions, EXCHANGE_ACCOUNTS_LEN},
+        pda, validate,
+    },
+    state::{ExchangeBooth, QuoteNonce, QUOTE_NONCE_LEN},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+// Ed25519SignatureOffsets, see the ed25519 program in the solana sdk
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+    quote: Quote,
+) -> ProgramResult {
+    if accounts.len() < EXCHANGE_ACCOUNTS_LEN + 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let exchange_booth_acc = &accounts[0];
+    let customer = &accounts[6];
+    let system_program = &accounts[9];
+    let quote_nonce_acc = &accounts[EXCHANGE_ACCOUNTS_LEN];
+    let instructions_sysvar = &accounts[EXCHANGE_ACCOUNTS_LEN + 1];
+
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    validate::check_system_program(system_program)?;
+    let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+
+    if quote.exchange_booth != *exchange_booth_acc.key || quote.customer != *customer.key {
+        msg!("error: quote was issued for another booth or customer");
+        return Err(ExchangeBoothError::InvalidQuoteSignature.into())
+    }
+    verify_quote_signature(instructions_sysvar, &exchange_booth.oracle, &quote.try_to_vec()?)?;
+
+    let slot = Clock::get()?.slot;
+    if slot > quote.expiry_slot {
+        msg!("error: quote expired at slot {}, now {}", quote.expiry_slot, slot);
+        return Err(ExchangeBoothError::QuoteExpired.into())
+    }
+    if !(amount > 0.0 && amount <= quote.max_amount) {
+        msg!("error: amount {} outside of quoted size {}", amount, quote.max_amount);
+        return Err(ExchangeBoothError::QuoteSizeExceeded.into())
+    }
+    if !(quote.a_to_b.is_finite() && quote.a_to_b > 0.0) {
+        msg!("error: quoted rate must be positive");
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    // the nonce account is created the first time a customer fills a quote on this booth
+    let (quote_nonce_key, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"quote_nonce",
+            exchange_booth_acc.key.as_ref(),
+            customer.key.as_ref(),
+        ],
+        program_id,
+    );
+    if quote_nonce_key != *quote_nonce_acc.key {
+        msg!("error: quote nonce PDA mismatch");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if !quote_nonce_acc.is_writable {
+        msg!("error: quote nonce not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    if quote_nonce_acc.data_is_empty() {
+        pda::create_account(
+            program_id,
+            quote_nonce_acc,
+            QUOTE_NONCE_LEN,
+            &[b"quote_nonce", exchange_booth_acc.key.as_ref(), customer.key.as_ref(), &[bump_seed]],
+            customer,
+            system_program,
+        )?;
+    }
+    validate::check_program_owned(program_id, &[quote_nonce_acc])?;
+    let mut quote_nonce = QuoteNonce::try_from_slice(&quote_nonce_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if quote.nonce <= quote_nonce.last_nonce {
+        msg!("error: nonce {} not above last used nonce {}", quote.nonce, quote_nonce.last_nonce);
+        return Err(ExchangeBoothError::QuoteNonceReused.into())
+    }
+    quote_nonce.last_nonce = quote.nonce;
+    quote_nonce.serialize(&mut &mut quote_nonce_acc.data.borrow_mut()[..])?;
+
+    exchange::execute(
+        program_id,
+        &accounts[..EXCHANGE_ACCOUNTS_LEN],
+        amount,
+        &SettlementOptions {
+            quoted_a_to_b: Some(quote.a_to_b),
+            quoted_direction: Some(quote.direction),
+            ..SettlementOptions::default()
+        },
+    )?;
+    Ok(())
+}
+
+// The runtime already checked the signature when it ran the Ed25519 instruction,
+// we only need to make sure that instruction signed our message with the right key.
+fn verify_quote_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> ProgramResult {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    if current_index == 0 {
+        msg!("error: no Ed25519 instruction before the quote");
+        return Err(ExchangeBoothError::InvalidQuoteSignature.into())
+    }
+    let ed25519_index = current_index - 1;
+    let ed25519_ix = load_instruction_at_checked(ed25519_index as usize, instructions_sysvar)?;
+    if ed25519_ix.program_id != ed25519_program::id() {
+        msg!("error: instruction before the quote is not an Ed25519 instruction");
+        return Err(ExchangeBoothError::InvalidQuoteSignature.into())
+    }
+
+    let data = &ed25519_ix.data;
+    if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN || data[0] != 1 {
+        msg!("error: Ed25519 instruction must verify exactly one signature");
+        return Err(ExchangeBoothError::InvalidQuoteSignature.into())
+    }
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN];
+    let read_u16 = |i: usize| u16::from_le_bytes([offsets[i * 2], offsets[i * 2 + 1]]);
+    let signature_instruction_index = read_u16(1);
+    let public_key_offset = read_u16(2) as usize;
+    let public_key_instruction_index = read_u16(3);
+    let message_data_offset = read_u16(4) as usize;
+    let message_data_size = read_u16(5) as usize;
+    let message_instruction_index = read_u16(6);
+
+    // all three must live in the Ed25519 instruction itself, not some other instruction we don't check
+    for instruction_index in [signature_instruction_index, public_key_instruction_index, message_instruction_index] {
+        if instruction_index != u16::MAX && instruction_index != ed25519_index {
+            msg!("error: Ed25519 instruction references data in another instruction");
+            return Err(ExchangeBoothError::InvalidQuoteSignature.into())
+        }
+    }
+
+    let signed_pubkey = data.get(public_key_offset..public_key_offset + PUBKEY_LEN);
+    let signed_message = data.get(message_data_offset..message_data_offset + message_data_size);
+    if signed_pubkey != Some(signer.as_ref()) {
+        msg!("error: quote not signed by the booth oracle {}", signer);
+        return Err(ExchangeBoothError::InvalidQuoteSignature.into())
+    }
+    if signed_message != Some(message) {
+        msg!("error: signed message does not match the quote");
+        return Err(ExchangeBoothError::InvalidQuoteSignature.into())
+    }
+
+    Ok(())
+}
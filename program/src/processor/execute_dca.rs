@@ -0,0 +1,112 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::{
+        escrow,
+        exchange::{self, SettlementOptions},
+    },
+    state::DcaSchedule,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let oracle = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let mint_a_acc = next_account_info(account_info_iter)?;
+    let mint_b_acc = next_account_info(account_info_iter)?;
+    let dca_schedule_acc = next_account_info(account_info_iter)?;
+    let escrow = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let owner_from_token_acc = next_account_info(account_info_iter)?;
+    let owner_to_token_acc = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    for account in [dca_schedule_acc, escrow, owner, owner_from_token_acc, owner_to_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    if dca_schedule_acc.owner != program_id {
+        msg!("error: DCA schedule not owned by this program");
+        return Err(ExchangeBoothError::InvalidAccountOwner.into())
+    }
+    let mut dca_schedule = DcaSchedule::try_from_slice(&dca_schedule_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if dca_schedule.exchange_booth != *exchange_booth_acc.key
+        || dca_schedule.escrow != *escrow.key
+        || dca_schedule.owner != *owner.key
+        || dca_schedule.owner_from_token_account != *owner_from_token_acc.key
+        || dca_schedule.owner_to_token_account != *owner_to_token_acc.key
+    {
+        msg!("error: accounts do not match DCA schedule {}", dca_schedule_acc.key);
+        return Err(ExchangeBoothError::DcaScheduleMismatch.into())
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if now < dca_schedule.next_execution_ts {
+        msg!("error: next execution at {}, now {}", dca_schedule.next_execution_ts, now);
+        return Err(ExchangeBoothError::DcaNotDue.into())
+    }
+
+    // the escrow acts as the customer, selling from itself
+    let (_, escrow_bump_seed) = escrow::bump_seed(program_id, b"dca_escrow", dca_schedule_acc);
+    exchange::execute(
+        program_id,
+        &[
+            exchange_booth_acc.clone(),
+            oracle.clone(),
+            vault_a.clone(),
+            vault_b.clone(),
+            mint_a_acc.clone(),
+            mint_b_acc.clone(),
+            escrow.clone(),
+            escrow.clone(),
+            owner_to_token_acc.clone(),
+            system_program.clone(),
+            token_program.clone(),
+        ],
+        dca_schedule.amount,
+        &SettlementOptions {
+            customer_seeds: &[b"dca_escrow", dca_schedule_acc.key.as_ref(), &[escrow_bump_seed]],
+            ..SettlementOptions::default()
+        },
+    )?;
+
+    dca_schedule.remaining -= 1;
+    msg!("DCA schedule {} executed, {} remaining", dca_schedule_acc.key, dca_schedule.remaining);
+    if dca_schedule.remaining == 0 {
+        let from_mint_acc = if dca_schedule.from_mint == *mint_a_acc.key {mint_a_acc} else {mint_b_acc};
+        escrow::close(program_id, b"dca_escrow", dca_schedule_acc, escrow, owner, owner_from_token_acc, from_mint_acc, token_program)?;
+        return event::emit(EventData::DcaScheduleCompleted {
+            schedule: *dca_schedule_acc.key,
+            owner: *owner.key,
+        })
+    }
+
+    // counted from now rather than the last due time, so a late crank can't trigger a burst of executions
+    dca_schedule.next_execution_ts = now
+        .checked_add(dca_schedule.interval_seconds)
+        .ok_or(ExchangeBoothError::InvalidAccountData)?;
+    dca_schedule.serialize(&mut &mut dca_schedule_acc.data.borrow_mut()[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::{
+        cancel_limit_order,
+        escrow,
+        exchange::{self, SettlementOptions},
+    },
+};
+
+// booth accounts shared by every order, then this many accounts per order
+const BOOTH_ACCOUNTS_LEN: usize = 9;
+const ORDER_ACCOUNTS_LEN: usize = 5;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let oracle = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let mint_a_acc = next_account_info(account_info_iter)?;
+    let mint_b_acc = next_account_info(account_info_iter)?;
+    let cranker = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !cranker.is_signer {
+        msg!("error: cranker must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    if !cranker.is_writable {
+        msg!("error: cranker not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    let order_accounts = &accounts[BOOTH_ACCOUNTS_LEN..];
+    if order_accounts.is_empty() || order_accounts.len() % ORDER_ACCOUNTS_LEN != 0 {
+        msg!("error: expected {} accounts per limit order", ORDER_ACCOUNTS_LEN);
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let mut filled = 0;
+    for order in order_accounts.chunks(ORDER_ACCOUNTS_LEN) {
+        let limit_order_acc = &order[0];
+        let escrow = &order[1];
+        let owner = &order[2];
+        let owner_from_token_acc = &order[3];
+        let owner_to_token_acc = &order[4];
+        let limit_order = cancel_limit_order::load_limit_order(program_id, limit_order_acc, escrow, owner, owner_from_token_acc)?;
+        if limit_order.exchange_booth != *exchange_booth_acc.key
+            || limit_order.owner_to_token_account != *owner_to_token_acc.key
+        {
+            msg!("error: accounts do not match limit order {}", limit_order_acc.key);
+            return Err(ExchangeBoothError::LimitOrderMismatch.into())
+        }
+
+        // the escrow acts as the customer, selling from itself
+        let (_, escrow_bump_seed) = escrow::bump_seed(program_id, b"limit_order_escrow", limit_order_acc);
+        let settled = exchange::execute(
+            program_id,
+            &[
+                exchange_booth_acc.clone(),
+                oracle.clone(),
+                vault_a.clone(),
+                vault_b.clone(),
+                mint_a_acc.clone(),
+                mint_b_acc.clone(),
+                escrow.clone(),
+                escrow.clone(),
+                owner_to_token_acc.clone(),
+                system_program.clone(),
+                token_program.clone(),
+            ],
+            limit_order.amount,
+            &SettlementOptions {
+                min_rate: Some(limit_order.min_rate),
+                customer_seeds: &[b"limit_order_escrow", limit_order_acc.key.as_ref(), &[escrow_bump_seed]],
+                ..SettlementOptions::default()
+            },
+        )?;
+        if !settled {
+            continue;
+        }
+
+        **cranker.lamports.borrow_mut() = cranker.lamports()
+            .checked_add(limit_order.tip_lamports)
+            .ok_or(ExchangeBoothError::InvalidAccountData)?;
+        **limit_order_acc.lamports.borrow_mut() = limit_order_acc.lamports()
+            .checked_sub(limit_order.tip_lamports)
+            .ok_or(ExchangeBoothError::InvalidAccountData)?;
+        let from_mint_acc = if limit_order.from_mint == *mint_a_acc.key {mint_a_acc} else {mint_b_acc};
+        escrow::close(program_id, b"limit_order_escrow", limit_order_acc, escrow, owner, owner_from_token_acc, from_mint_acc, token_program)?;
+        event::emit(EventData::LimitOrderClosed {
+            order: *limit_order_acc.key,
+            owner: *owner.key,
+            filled: true,
+        })?;
+        filled += 1;
+    }
+
+    msg!("Filled {} of {} limit orders", filled, order_accounts.len() / ORDER_ACCOUNTS_LEN);
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    instruction::FlashLoanReceiverInstruction,
+    pricing,
+    processor::{token, validate},
+    state::ExchangeBooth,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+    mint: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let mint_acc = next_account_info(account_info_iter)?;
+    let borrower_token_acc = next_account_info(account_info_iter)?;
+    let borrower_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let borrower_accounts = account_info_iter.as_slice();
+
+    if !vault.is_writable {
+        msg!("error: vault not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    if !borrower_token_acc.is_writable {
+        msg!("error: borrower token account not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    if *mint_acc.key != mint {
+        msg!("error: mint account does not match the requested mint");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if borrower_program.key == program_id {
+        msg!("error: the booth cannot borrow from itself");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if *vault.key != exchange_booth.vault_a && *vault.key != exchange_booth.vault_b {
+        msg!("error: vault is not in exchange booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[vault, mint_acc, borrower_token_acc])?;
+    let vault_token_account = token::unpack_account(&vault.try_borrow_data()?)?;
+    if vault_token_account.mint != mint {
+        msg!("error: vault does not hold the requested mint");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    let mint_data = token::unpack_mint(&mint_acc.try_borrow_data()?)?;
+    let amount_small = pricing::to_base_units(amount, mint_data.decimals);
+    if amount_small == 0 || amount_small > vault_token_account.amount {
+        msg!("error: cannot lend {} out of a vault holding {}", amount_small, vault_token_account.amount);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    let fee = pricing::flash_loan_fee(amount_small);
+    let required_balance = vault_token_account.amount
+        .checked_add(fee)
+        .ok_or(ExchangeBoothError::InvalidInstructionInput)?;
+    msg!("Lending {} ({}) of {} for a fee of {}", amount, amount_small, mint, fee);
+
+    let (_, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"exchange_booth",
+            exchange_booth.admin.as_ref(),
+            exchange_booth_acc.key.as_ref(),
+            mint.as_ref(),
+        ],
+        program_id,
+    );
+    token::transfer_checked(
+        token_program,
+        vault,
+        mint_acc,
+        borrower_token_acc,
+        vault,
+        amount_small,
+        &[&[b"exchange_booth", exchange_booth.admin.as_ref(), exchange_booth_acc.key.as_ref(), mint.as_ref(), &[bump_seed]]]
+    )?;
+
+    // the vault's signature is not forwarded, the borrower has to repay out of its own accounts
+    let receiver_instruction = Instruction {
+        program_id: *borrower_program.key,
+        accounts: borrower_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: FlashLoanReceiverInstruction { amount: amount_small, fee }.try_to_vec()?,
+    };
+    msg!("Invoking borrower program {}", borrower_program.key);
+    let mut receiver_account_infos = vec![borrower_program.clone()];
+    receiver_account_infos.extend_from_slice(borrower_accounts);
+    invoke(&receiver_instruction, &receiver_account_infos)?;
+
+    let repaid_balance = token::balance(vault)?;
+    if repaid_balance < required_balance {
+        msg!("error: vault holds {} after the loan, expected at least {}", repaid_balance, required_balance);
+        return Err(ExchangeBoothError::FlashLoanNotRepaid.into())
+    }
+    event::emit(EventData::FlashLoan {
+        booth: *exchange_booth_acc.key,
+        borrower_program: *borrower_program.key,
+        mint,
+        amount: amount_small,
+        fee,
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::validate,
+    state::{aggregated_oracle_len, AggregatedOracle, OracleSubmission, MAX_ORACLE_PUBLISHERS},
+};
+
+use borsh::BorshSerialize;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    publishers: Vec<Pubkey>,
+    quorum: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let oracle_acc = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        msg!("error: Admin must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    if !oracle_acc.is_writable {
+        msg!("error: oracle not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    validate::check_program_owned(program_id, &[oracle_acc])?;
+    if publishers.is_empty() || publishers.len() > MAX_ORACLE_PUBLISHERS {
+        msg!("error: expected 1 to {} publishers, got {}", MAX_ORACLE_PUBLISHERS, publishers.len());
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    if quorum == 0 || quorum as usize > publishers.len() {
+        msg!("error: quorum {} impossible with {} publishers", quorum, publishers.len());
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    if oracle_acc.data_len() != aggregated_oracle_len(publishers.len()) {
+        msg!("error: oracle has {} bytes, {} publishers need {}", oracle_acc.data_len(), publishers.len(), aggregated_oracle_len(publishers.len()));
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+    if oracle_acc.data.borrow().iter().any(|byte| *byte != 0) {
+        msg!("error: oracle already initialized");
+        return Err(ExchangeBoothError::AccountAlreadyInitialized.into())
+    }
+
+    let mut submissions: Vec<OracleSubmission> = Vec::with_capacity(publishers.len());
+    for publisher in publishers {
+        if submissions.iter().any(|submission| submission.publisher == publisher) {
+            msg!("error: publisher {} listed twice", publisher);
+            return Err(ExchangeBoothError::InvalidInstructionInput.into())
+        }
+        submissions.push(OracleSubmission {
+            publisher,
+            a_to_b: 0.0,
+            timestamp: 0
+        });
+    }
+
+    let aggregated_oracle = AggregatedOracle {
+        admin: *admin.key,
+        quorum,
+        submissions
+    };
+    aggregated_oracle.serialize(&mut &mut oracle_acc.data.borrow_mut()[..])?;
+    event::emit(EventData::AggregatedOracleInitialized {
+        oracle: *oracle_acc.key,
+        admin: *admin.key,
+        publishers: aggregated_oracle.submissions.iter().map(|submission| submission.publisher).collect(),
+        quorum,
+    })?;
+
+    msg!("Aggregated oracle {}: {} publishers, quorum {}", oracle_acc.key, aggregated_oracle.submissions.len(), quorum);
+    Ok(())
+}
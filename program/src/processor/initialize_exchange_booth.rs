@@ -2,18 +2,15 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo}, 
     entrypoint::ProgramResult,
     msg,
-    program::{invoke, invoke_signed},
-    program_pack::Pack,
     pubkey::Pubkey,
-    system_instruction,
-    sysvar::{rent::Rent, Sysvar},
 };
 
-use spl_token::state::{Account};
 
 use crate::{
     error::ExchangeBoothError,
-    state::ExchangeBooth,
+    event::{self, EventData},
+    processor::{pda, registry, token, validate},
+    state::{ExchangeBooth, OracleSource, EXCHANGE_BOOTH_LEN},
 };
 
 use borsh::{BorshSerialize};
@@ -21,7 +18,8 @@ use borsh::{BorshSerialize};
 
 pub fn process(
     program_id: &Pubkey,
-    accounts: &[AccountInfo]
+    accounts: &[AccountInfo],
+    index: u64,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let exchange_booth = next_account_info(account_info_iter)?;
@@ -34,6 +32,8 @@ pub fn process(
     let system_program = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
     let rent_account = next_account_info(account_info_iter)?;
+    let lp_mint = next_account_info(account_info_iter)?;
+    let booth_registry = next_account_info(account_info_iter)?;
 
     if !admin.is_signer {
         msg!("error: Admin must be signer");
@@ -51,6 +51,41 @@ pub fn process(
         msg!("error: vault-b not writable");
         return Err(ExchangeBoothError::AccountMustBeWritable.into())
     }
+    if !lp_mint.is_writable {
+        msg!("error: lp mint not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    validate::check_system_program(system_program)?;
+    validate::check_rent_sysvar(rent_account)?;
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[mint_a, mint_b])?;
+    let (generated_exchange_booth_key, bump_seed_booth) = Pubkey::find_program_address(
+        &[
+            b"exchange_booth",
+            admin.key.as_ref(),
+            mint_a.key.as_ref(),
+            mint_b.key.as_ref(),
+            &index.to_le_bytes()
+        ],
+        program_id,
+    );
+    if generated_exchange_booth_key != *exchange_booth.key {
+        msg!("error: exchange booth PDA mismatch");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if exchange_booth.owner == program_id {
+        msg!("error: exchange booth {} already initialized", exchange_booth.key);
+        return Err(ExchangeBoothError::AccountAlreadyInitialized.into())
+    }
+    pda::create_account(
+        program_id,
+        exchange_booth,
+        EXCHANGE_BOOTH_LEN,
+        &[b"exchange_booth", admin.key.as_ref(), mint_a.key.as_ref(), mint_b.key.as_ref(), &index.to_le_bytes(), &[bump_seed_booth]],
+        admin,
+        system_program
+    )?;
+
     msg!("admin acc: {}", admin.key);
     msg!("exchange acc: {}", exchange_booth.key);
     msg!("mint acc: {}", mint_a.key);
@@ -84,64 +119,95 @@ pub fn process(
     }
 
     // Now we allocate a PDA initialized with the length of the token program struct
-    // and assign the owner to the token program
-    invoke_signed(
-        &system_instruction::create_account(
-            admin.key,
-            vault_a.key,
-            Rent::get()?.minimum_balance(Account::LEN),
-            Account::LEN as u64, 
-            token_program.key // token program needs to be the owner of the vaults
-        ),
-        &[admin.clone(), vault_a.clone(), system_program.clone()],
-        &[&[b"exchange_booth", admin.key.as_ref(), exchange_booth.key.as_ref(), mint_a.key.as_ref(),  &[bump_seed_a]]]
+    // and assign the owner to the token program. Token-2022 mints may need extensions on the account
+    let vault_a_len = token::account_len(token_program, mint_a)?;
+    pda::create_account(
+        token_program.key, // token program needs to be the owner of the vaults
+        vault_a,
+        vault_a_len,
+        &[b"exchange_booth", admin.key.as_ref(), exchange_booth.key.as_ref(), mint_a.key.as_ref(), &[bump_seed_a]],
+        admin,
+        system_program
     )?;
 
     msg!("Passed invoked_signed");
-    invoke(
-        &spl_token::instruction::initialize_account(
-            &token_program.key,
-            &vault_a.key,
-            &mint_a.key,
-            &vault_a.key, // trick -- avoid more pda's by doing this
-        )?,
-        &[token_program.clone(), vault_a.clone(), mint_a.clone(), rent_account.clone()]
+    token::initialize_account(
+        token_program,
+        vault_a,
+        mint_a,
+        vault_a.key, // trick -- avoid more pda's by doing this
+        rent_account
     )?;
     msg!("Passed CPI call A");
 
 
     // repeat for vault b
-    invoke_signed(
-        &system_instruction::create_account(
-            admin.key,
-            vault_b.key,
-            Rent::get()?.minimum_balance(Account::LEN),
-            Account::LEN as u64,
-            token_program.key
-        ),
-        &[admin.clone(), vault_b.clone(), system_program.clone()],
-        &[&[b"exchange_booth", admin.key.as_ref(), exchange_booth.key.as_ref(), mint_b.key.as_ref(),  &[bump_seed_b]]]
+    let vault_b_len = token::account_len(token_program, mint_b)?;
+    pda::create_account(
+        token_program.key,
+        vault_b,
+        vault_b_len,
+        &[b"exchange_booth", admin.key.as_ref(), exchange_booth.key.as_ref(), mint_b.key.as_ref(), &[bump_seed_b]],
+        admin,
+        system_program
     )?;
 
-    invoke(
-        &spl_token::instruction::initialize_account(
-            &token_program.key,
-            &vault_b.key,
-            &mint_b.key,
-            &vault_b.key,
-        )?,
-        &[token_program.clone(), vault_b.clone(), mint_b.clone(), vault_b.clone(), rent_account.clone()]
+    token::initialize_account(token_program, vault_b, mint_b, vault_b.key, rent_account)?;
+
+    // the lp mint is a PDA that is its own mint authority, same trick as the vaults
+    let (generated_lp_mint_pda_key, bump_seed_lp) = Pubkey::find_program_address(
+        &[
+            b"exchange_booth",
+            admin.key.as_ref(),
+            exchange_booth.key.as_ref(),
+            b"lp_mint"
+        ],
+        program_id,
+    );
+
+    if generated_lp_mint_pda_key != *lp_mint.key {
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    let lp_mint_len = token::closeable_mint_len(token_program);
+    pda::create_account(
+        token_program.key,
+        lp_mint,
+        lp_mint_len,
+        &[b"exchange_booth", admin.key.as_ref(), exchange_booth.key.as_ref(), b"lp_mint", &[bump_seed_lp]],
+        admin,
+        system_program
     )?;
+    // so CloseExchangeBooth can get the rent back, where the token program allows it
+    token::initialize_mint_close_authority(token_program, lp_mint, lp_mint.key)?;
+
+    // lp shares are denominated in token B, so they share its decimals
+    let mint_b_data = token::unpack_mint(&mint_b.try_borrow_data()?)?;
+    token::initialize_mint(token_program, lp_mint, lp_mint.key, mint_b_data.decimals, rent_account)?;
 
     // encode the exchange booth into a struct and pass that in as the data to the exchange booth account
     let exchange_booth_struct = ExchangeBooth {
         admin: *admin.key,
         oracle: *oracle.key,
         vault_a: *vault_a.key,
-        vault_b: *vault_b.key
+        vault_b: *vault_b.key,
+        lp_mint: *lp_mint.key,
+        skew_target_ratio: 1.0,
+        skew_sensitivity: 0.0,
+        oracle_source: OracleSource::Pushed,
+        max_confidence_bps: 0,
+        max_price_age: 0,
+        twap_window: 0
     };
 
-    exchange_booth_struct.serialize(&mut *exchange_booth.data.borrow_mut())?;
+    exchange_booth_struct.serialize(&mut &mut exchange_booth.data.borrow_mut()[..])?;
+
+    registry::add(program_id, booth_registry, mint_a.key, mint_b.key, exchange_booth.key, admin, system_program)?;
+    event::emit(EventData::BoothInitialized {
+        booth: *exchange_booth.key,
+        admin: *admin.key,
+        mints: vec![*mint_a.key, *mint_b.key],
+    })?;
 
     //allocate vaults on the fly
 
@@ -0,0 +1,134 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::{pda, token, validate},
+    state::{multi_asset_booth_len, multi_asset_rate_len, BoothAsset, MultiAssetBooth, MultiAssetRate},
+};
+
+use borsh::BorshSerialize;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let booth_acc = next_account_info(account_info_iter)?;
+    let oracle = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let asset_accounts = account_info_iter.as_slice();
+
+    if !admin.is_signer {
+        msg!("error: Admin must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    if !booth_acc.is_writable {
+        msg!("error: exchange booth not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    validate::check_program_owned(program_id, &[booth_acc])?;
+    if asset_accounts.len() < 4 || asset_accounts.len() % 2 != 0 {
+        msg!("error: expected a mint and a vault for each of at least two assets");
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    let num_assets = asset_accounts.len() / 2;
+    if booth_acc.data_len() != multi_asset_booth_len(num_assets) {
+        msg!("error: booth has {} bytes, {} assets need {}", booth_acc.data_len(), num_assets, multi_asset_booth_len(num_assets));
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+    if booth_acc.data.borrow().iter().any(|byte| *byte != 0) {
+        msg!("error: exchange booth already initialized");
+        return Err(ExchangeBoothError::AccountAlreadyInitialized.into())
+    }
+
+    // a fresh oracle is handed to the admin, one that already has an authority is shared as is
+    if !oracle.is_writable {
+        msg!("error: oracle not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    validate::check_program_owned(program_id, &[oracle])?;
+    if oracle.data_len() != multi_asset_rate_len(num_assets) {
+        msg!("error: oracle has {} bytes, {} assets need {}", oracle.data_len(), num_assets, multi_asset_rate_len(num_assets));
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+    if oracle.data.borrow().iter().all(|byte| *byte == 0) {
+        let rate = MultiAssetRate {
+            authority: *admin.key,
+            prices: vec![0.0; num_assets]
+        };
+        rate.serialize(&mut &mut oracle.data.borrow_mut()[..])?;
+    }
+
+    validate::check_system_program(system_program)?;
+    validate::check_rent_sysvar(rent_account)?;
+    token::check_program(token_program)?;
+
+    let mut assets: Vec<BoothAsset> = Vec::with_capacity(num_assets);
+    for pair in asset_accounts.chunks(2) {
+        let mint = &pair[0];
+        let vault = &pair[1];
+        if assets.iter().any(|asset| asset.mint == *mint.key) {
+            msg!("error: mint {} listed twice", mint.key);
+            return Err(ExchangeBoothError::InvalidInstructionInput.into())
+        }
+        if !vault.is_writable {
+            msg!("error: vault {} not writable", vault.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+        token::check_owner(token_program, &[mint])?;
+
+        let (vault_key, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"exchange_booth",
+                admin.key.as_ref(),
+                booth_acc.key.as_ref(),
+                mint.key.as_ref()
+            ],
+            program_id,
+        );
+        if vault_key != *vault.key {
+            return Err(ExchangeBoothError::InvalidAccountAddress.into())
+        }
+
+        let vault_len = token::account_len(token_program, mint)?;
+        pda::create_account(
+            token_program.key,
+            vault,
+            vault_len,
+            &[b"exchange_booth", admin.key.as_ref(), booth_acc.key.as_ref(), mint.key.as_ref(), &[bump_seed]],
+            admin,
+            system_program,
+        )?;
+        token::initialize_account(token_program, vault, mint, vault.key, rent_account)?;
+        msg!("asset {}: mint {}, vault {}", assets.len(), mint.key, vault.key);
+
+        assets.push(BoothAsset {
+            mint: *mint.key,
+            vault: *vault.key
+        });
+    }
+
+    let booth = MultiAssetBooth {
+        admin: *admin.key,
+        oracle: *oracle.key,
+        assets
+    };
+    booth.serialize(&mut &mut booth_acc.data.borrow_mut()[..])?;
+    event::emit(EventData::BoothInitialized {
+        booth: *booth_acc.key,
+        admin: *admin.key,
+        mints: booth.assets.iter().map(|asset| asset.mint).collect(),
+    })?;
+
+    Ok(())
+}
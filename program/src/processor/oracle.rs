@@ -0,0 +1,399 @@
+// Reading a booth's rate. The oracle account is whatever ExchangeBooth::oracle points to, and
+// ExchangeBooth::oracle_source says how to read it: an ExchangeRate this program owns, an
+// external price account in the Pyth v2 layout that somebody else publishes, or an
+// AggregatedOracle several publishers submit to.
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use std::cmp::Ordering;
+
+use crate::{
+    error::ExchangeBoothError,
+    processor::validate,
+    state::{
+        AggregatedOracle,
+        ExchangeBooth,
+        ExchangeRate,
+        OracleSource,
+        RateHistory,
+        RateObservation,
+        EXCHANGE_RATE_LEN,
+        LEGACY_EXCHANGE_RATE_LEN,
+        RATE_HISTORY_LEN,
+        RATE_HISTORY_SIZE,
+    },
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+// Pyth v2 price account, only the fields the booth reads
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const PYTH_VERSION: u32 = 2;
+const PYTH_ACCOUNT_TYPE_PRICE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+const PYTH_MAGIC_OFFSET: usize = 0;
+const PYTH_VERSION_OFFSET: usize = 4;
+const PYTH_ACCOUNT_TYPE_OFFSET: usize = 8;
+const PYTH_EXPONENT_OFFSET: usize = 20;
+const PYTH_TIMESTAMP_OFFSET: usize = 96;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_CONF_OFFSET: usize = 216;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+const PYTH_PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+
+// Aggregate price of a Pyth price account, `price` and `conf` are scaled by 10^exponent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PythPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub status: u32,
+}
+
+pub fn parse_pyth_price(data: &[u8]) -> Result<PythPrice, ProgramError> {
+    if data.len() < PYTH_PRICE_ACCOUNT_MIN_LEN
+        || read_u32(data, PYTH_MAGIC_OFFSET) != PYTH_MAGIC
+        || read_u32(data, PYTH_VERSION_OFFSET) != PYTH_VERSION
+        || read_u32(data, PYTH_ACCOUNT_TYPE_OFFSET) != PYTH_ACCOUNT_TYPE_PRICE
+    {
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+    Ok(PythPrice {
+        price: read_u64(data, PYTH_AGG_PRICE_OFFSET) as i64,
+        conf: read_u64(data, PYTH_AGG_CONF_OFFSET),
+        exponent: read_u32(data, PYTH_EXPONENT_OFFSET) as i32,
+        publish_time: read_u64(data, PYTH_TIMESTAMP_OFFSET) as i64,
+        status: read_u32(data, PYTH_AGG_STATUS_OFFSET),
+    })
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+// Length of the rate at the start of a pushed oracle of `data_len` bytes, telling the legacy
+// layout without confidence from the current one.
+fn rate_len(data_len: usize) -> Result<usize, ProgramError> {
+    [EXCHANGE_RATE_LEN, LEGACY_EXCHANGE_RATE_LEN]
+        .iter()
+        .copied()
+        .find(|rate_len| data_len == *rate_len || data_len == rate_len + RATE_HISTORY_LEN)
+        .ok_or_else(|| ExchangeBoothError::InvalidAccountData.into())
+}
+
+// Splits a pushed oracle into its rate and, if it was allocated with room for one, its history.
+pub fn unpack_exchange_rate(data: &[u8]) -> Result<(ExchangeRate, Option<RateHistory>), ProgramError> {
+    let rate_len = rate_len(data.len())?;
+    let history = if data.len() == rate_len {
+        None
+    } else {
+        // not try_from_slice: a zeroed tail reads as an empty vec and leaves the rest unread
+        let history = RateHistory::deserialize(&mut &data[rate_len..])
+            .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+        // a zeroed tail has never been written to
+        if history.observations.len() == RATE_HISTORY_SIZE {
+            Some(history)
+        } else {
+            Some(RateHistory {
+                next_index: 0,
+                observations: vec![RateObservation::default(); RATE_HISTORY_SIZE]
+            })
+        }
+    };
+    let exchange_rate = if rate_len == LEGACY_EXCHANGE_RATE_LEN {
+        let (a_to_b, b_to_a) = <(f64, f64)>::try_from_slice(&data[..rate_len])
+            .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+        ExchangeRate { a_to_b, b_to_a, confidence: 0.0 }
+    } else {
+        ExchangeRate::try_from_slice(&data[..rate_len])
+            .map_err(|_| ExchangeBoothError::InvalidAccountData)?
+    };
+    Ok((exchange_rate, history))
+}
+
+// Writes back in the layout `data` already has, see unpack_exchange_rate.
+pub(crate) fn pack_exchange_rate(exchange_rate: &ExchangeRate, history: Option<&RateHistory>, data: &mut [u8]) -> ProgramResult {
+    let rate_len = rate_len(data.len())?;
+    if rate_len == LEGACY_EXCHANGE_RATE_LEN {
+        if exchange_rate.confidence != 0.0 {
+            msg!("error: oracle predates confidence, it can only take a confidence of 0");
+            return Err(ExchangeBoothError::InvalidInstructionInput.into())
+        }
+        (exchange_rate.a_to_b, exchange_rate.b_to_a).serialize(&mut &mut data[..rate_len])?;
+    } else {
+        exchange_rate.serialize(&mut &mut data[..rate_len])?;
+    }
+    if let Some(history) = history {
+        history.serialize(&mut &mut data[rate_len..])?;
+    }
+    Ok(())
+}
+
+pub(crate) fn record_observation(history: &mut RateHistory, observation: RateObservation) {
+    let index = history.next_index as usize % history.observations.len();
+    history.observations[index] = observation;
+    history.next_index = ((index + 1) % history.observations.len()) as u32;
+}
+
+// Average a_to_b over the last `window` seconds, each observation counting until the next one
+// replaced it. Falls back to the latest observation when the window holds no elapsed time.
+pub fn time_weighted_rate(history: &RateHistory, now: i64, window: i64) -> Option<f64> {
+    let size = history.observations.len();
+    let chronological: Vec<&RateObservation> = (0..size)
+        .map(|offset| &history.observations[(history.next_index as usize + offset) % size])
+        .filter(|observation| observation.timestamp > 0)
+        .collect();
+    let latest = chronological.last()?;
+
+    let window_start = now.saturating_sub(window);
+    let mut weighted = 0.0;
+    let mut elapsed: i64 = 0;
+    for (index, observation) in chronological.iter().enumerate() {
+        let until = chronological.get(index + 1).map_or(now, |next| next.timestamp);
+        let from = observation.timestamp.max(window_start);
+        if until > from {
+            weighted += observation.a_to_b * (until - from) as f64;
+            elapsed += until - from;
+        }
+    }
+    if elapsed == 0 {
+        return Some(latest.a_to_b)
+    }
+    Some(weighted / elapsed as f64)
+}
+
+// The rate `exchange_booth` trades at right now, read from `oracle`.
+pub(crate) fn load_rate(
+    program_id: &Pubkey,
+    exchange_booth: &ExchangeBooth,
+    oracle: &AccountInfo,
+) -> Result<ExchangeRate, ProgramError> {
+    // the rate decides what customers get, so it can't come from an account they picked
+    if exchange_booth.oracle != *oracle.key {
+        msg!("error: oracle {} does not belong to the exchange booth", oracle.key);
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if exchange_booth.oracle_source != OracleSource::Pyth {
+        validate::check_program_owned(program_id, &[oracle])?;
+    }
+    rate_from_data(exchange_booth, &oracle.data.borrow(), Clock::get()?.unix_timestamp)
+}
+
+// The rate `exchange_booth` trades at at time `now`, given the data of its oracle account.
+// Doesn't check who owns the oracle, so off-chain callers can run it on fetched data.
+pub fn rate_from_data(exchange_booth: &ExchangeBooth, oracle_data: &[u8], now: i64) -> Result<ExchangeRate, ProgramError> {
+    let exchange_rate = match exchange_booth.oracle_source {
+        OracleSource::Pushed => {
+            let (exchange_rate, history) = unpack_exchange_rate(oracle_data)?;
+            if exchange_booth.twap_window == 0 {
+                exchange_rate
+            } else {
+                let history = history.ok_or_else(|| {
+                    msg!("error: oracle keeps no rate history");
+                    ExchangeBoothError::InvalidAccountData
+                })?;
+                let a_to_b = time_weighted_rate(&history, now, exchange_booth.twap_window)
+                    .ok_or_else(|| {
+                        msg!("error: oracle has no observations yet");
+                        ExchangeBoothError::InvalidAccountData
+                    })?;
+                msg!("TWAP over {}s: {} (spot {})", exchange_booth.twap_window, a_to_b, exchange_rate.a_to_b);
+                ExchangeRate {
+                    a_to_b,
+                    b_to_a: 1.0 / a_to_b,
+                    confidence: exchange_rate.confidence
+                }
+            }
+        }
+        OracleSource::Pyth => {
+            let price = parse_pyth_price(oracle_data)?;
+            pyth_rate(exchange_booth, &price, now)?
+        }
+        OracleSource::Aggregated => {
+            let aggregated_oracle = AggregatedOracle::try_from_slice(oracle_data)
+                .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+            median_rate(exchange_booth, &aggregated_oracle, now)?
+        }
+    };
+
+    // an uncertain rate widens the spread, one past the booth's limit stops trading altogether
+    let confidence_bps = exchange_rate.confidence * 10_000.;
+    if exchange_booth.max_confidence_bps > 0 && confidence_bps > exchange_booth.max_confidence_bps as f64 {
+        msg!("error: oracle confidence is {} bps, limit is {} bps", confidence_bps, exchange_booth.max_confidence_bps);
+        return Err(ExchangeBoothError::OracleConfidenceTooWide.into())
+    }
+    Ok(exchange_rate)
+}
+
+// Converts a Pyth price of A in B into a rate, if it is recent enough for the booth.
+pub fn pyth_rate(exchange_booth: &ExchangeBooth, price: &PythPrice, now: i64) -> Result<ExchangeRate, ProgramError> {
+    if price.status != PYTH_STATUS_TRADING || price.price <= 0 {
+        msg!("error: oracle has no trading price (status {}, price {})", price.status, price.price);
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+    let age = now.saturating_sub(price.publish_time);
+    if age > exchange_booth.max_price_age {
+        msg!("error: oracle price is {}s old, limit is {}s", age, exchange_booth.max_price_age);
+        return Err(ExchangeBoothError::StaleOraclePrice.into())
+    }
+
+    let a_to_b = price.price as f64 * f64::powi(10., price.exponent);
+    Ok(ExchangeRate {
+        a_to_b,
+        b_to_a: 1.0 / a_to_b,
+        confidence: price.conf as f64 / price.price as f64
+    })
+}
+
+// Median of the submissions published within the booth's max price age, as long as there
+// are at least `quorum` of them. One publisher going stale or rogue can't move it alone.
+pub fn median_rate(exchange_booth: &ExchangeBooth, aggregated_oracle: &AggregatedOracle, now: i64) -> Result<ExchangeRate, ProgramError> {
+    let mut fresh: Vec<f64> = aggregated_oracle.submissions
+        .iter()
+        .filter(|submission| submission.timestamp > 0 && now.saturating_sub(submission.timestamp) <= exchange_booth.max_price_age)
+        .map(|submission| submission.a_to_b)
+        .collect();
+    if fresh.is_empty() || fresh.len() < aggregated_oracle.quorum as usize {
+        msg!("error: {} fresh submissions, quorum is {}", fresh.len(), aggregated_oracle.quorum);
+        return Err(ExchangeBoothError::OracleQuorumNotMet.into())
+    }
+    fresh.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let middle = fresh.len() / 2;
+    let a_to_b = if fresh.len() % 2 == 0 {
+        (fresh[middle - 1] + fresh[middle]) / 2.0
+    } else {
+        fresh[middle]
+    };
+    msg!("Median of {} submissions: {}", fresh.len(), a_to_b);
+    Ok(ExchangeRate {
+        a_to_b,
+        b_to_a: 1.0 / a_to_b,
+        confidence: 0.0
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(index: usize) -> RateObservation {
+        RateObservation {
+            slot: index as u64 + 1,
+            timestamp: 1_000 + index as i64,
+            a_to_b: 2.0 + index as f64,
+        }
+    }
+
+    #[test]
+    fn unpack_fresh_oracle_with_history() {
+        let data = vec![0u8; EXCHANGE_RATE_LEN + RATE_HISTORY_LEN];
+        let (exchange_rate, history) = unpack_exchange_rate(&data).unwrap();
+        assert_eq!(exchange_rate.a_to_b, 0.0);
+        let history = history.unwrap();
+        assert_eq!(history.next_index, 0);
+        assert_eq!(history.observations, vec![RateObservation::default(); RATE_HISTORY_SIZE]);
+    }
+
+    #[test]
+    fn unpack_oracle_without_history() {
+        let data = vec![0u8; EXCHANGE_RATE_LEN];
+        let (_, history) = unpack_exchange_rate(&data).unwrap();
+        assert!(history.is_none());
+    }
+
+    #[test]
+    fn first_update_of_fresh_oracle_round_trips() {
+        let mut data = vec![0u8; EXCHANGE_RATE_LEN + RATE_HISTORY_LEN];
+        let (mut exchange_rate, history) = unpack_exchange_rate(&data).unwrap();
+        let mut history = history.unwrap();
+        exchange_rate.a_to_b = 2.0;
+        exchange_rate.b_to_a = 0.5;
+        record_observation(&mut history, observation(0));
+        pack_exchange_rate(&exchange_rate, Some(&history), &mut data).unwrap();
+
+        let (exchange_rate, history) = unpack_exchange_rate(&data).unwrap();
+        let history = history.unwrap();
+        assert_eq!(exchange_rate.a_to_b, 2.0);
+        assert_eq!(history.next_index, 1);
+        assert_eq!(history.observations[0], observation(0));
+    }
+
+    #[test]
+    fn full_ring_wraps_over_the_oldest_observation() {
+        let mut data = vec![0u8; EXCHANGE_RATE_LEN + RATE_HISTORY_LEN];
+        for index in 0..RATE_HISTORY_SIZE + 1 {
+            let (exchange_rate, history) = unpack_exchange_rate(&data).unwrap();
+            let mut history = history.unwrap();
+            record_observation(&mut history, observation(index));
+            pack_exchange_rate(&exchange_rate, Some(&history), &mut data).unwrap();
+        }
+
+        let (_, history) = unpack_exchange_rate(&data).unwrap();
+        let history = history.unwrap();
+        assert_eq!(history.observations.len(), RATE_HISTORY_SIZE);
+        assert_eq!(history.next_index, 1);
+        assert_eq!(history.observations[0], observation(RATE_HISTORY_SIZE));
+        assert_eq!(history.observations[1], observation(1));
+        // every observation counts for one second until the next one replaced it
+        let latest = observation(RATE_HISTORY_SIZE);
+        assert_eq!(time_weighted_rate(&history, latest.timestamp + 1, 2), Some((latest.a_to_b + latest.a_to_b - 1.0) / 2.0));
+    }
+
+    #[test]
+    fn unpack_rejects_other_lengths() {
+        assert!(unpack_exchange_rate(&[0u8; EXCHANGE_RATE_LEN + 1]).is_err());
+        assert!(unpack_exchange_rate(&[0u8; LEGACY_EXCHANGE_RATE_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn legacy_oracle_reads_with_confidence_zero() {
+        let mut data = vec![0u8; LEGACY_EXCHANGE_RATE_LEN];
+        data[..8].copy_from_slice(&2.0f64.to_le_bytes());
+        data[8..].copy_from_slice(&0.5f64.to_le_bytes());
+        let (exchange_rate, history) = unpack_exchange_rate(&data).unwrap();
+        assert_eq!((exchange_rate.a_to_b, exchange_rate.b_to_a, exchange_rate.confidence), (2.0, 0.5, 0.0));
+        assert!(history.is_none());
+    }
+
+    #[test]
+    fn legacy_oracle_with_history_keeps_its_layout() {
+        let mut data = vec![0u8; LEGACY_EXCHANGE_RATE_LEN + RATE_HISTORY_LEN];
+        let (mut exchange_rate, history) = unpack_exchange_rate(&data).unwrap();
+        let mut history = history.unwrap();
+        exchange_rate.a_to_b = 4.0;
+        exchange_rate.b_to_a = 0.25;
+        record_observation(&mut history, observation(0));
+        pack_exchange_rate(&exchange_rate, Some(&history), &mut data).unwrap();
+
+        assert_eq!(data[..8], 4.0f64.to_le_bytes());
+        assert_eq!(data[8..16], 0.25f64.to_le_bytes());
+        let (exchange_rate, history) = unpack_exchange_rate(&data).unwrap();
+        assert_eq!(exchange_rate.a_to_b, 4.0);
+        assert_eq!(history.unwrap().observations[0], observation(0));
+    }
+
+    #[test]
+    fn legacy_oracle_rejects_a_confidence() {
+        let mut data = vec![0u8; LEGACY_EXCHANGE_RATE_LEN];
+        let exchange_rate = ExchangeRate { a_to_b: 2.0, b_to_a: 0.5, confidence: 0.01 };
+        assert!(pack_exchange_rate(&exchange_rate, None, &mut data).is_err());
+    }
+}
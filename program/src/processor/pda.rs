@@ -0,0 +1,41 @@
+// Creating PDAs of this program, owned by this program or handed to another (e.g. the token
+// program for temporary token accounts). Anyone can send lamports to an address before it exists, which
+// makes system_instruction::create_account fail, so top up, allocate and assign separately.
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+
+pub(crate) fn create_account<'a>(
+    owner: &Pubkey,
+    account: &AccountInfo<'a>,
+    space: usize,
+    seeds: &[&[u8]],
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, top_up),
+            &[payer.clone(), account.clone(), system_program.clone()]
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[seeds]
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, owner),
+        &[account.clone(), system_program.clone()],
+        &[seeds]
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    pricing,
+    processor::{escrow, pda, token, validate},
+    state::{ExchangeBooth, LimitOrder, LIMIT_ORDER_LEN},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    order_id: u64,
+    amount: f64,
+    min_rate: f64,
+    tip_lamports: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let limit_order_acc = next_account_info(account_info_iter)?;
+    let escrow = next_account_info(account_info_iter)?;
+    let from_mint_acc = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let owner_from_token_acc = next_account_info(account_info_iter)?;
+    let owner_to_token_acc = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        msg!("error: owner must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [limit_order_acc, escrow, owner_from_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+    if !(amount > 0.0 && min_rate.is_finite() && min_rate > 0.0) {
+        msg!("error: amount and min rate must be positive");
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    validate::check_system_program(system_program)?;
+    validate::check_rent_sysvar(rent_account)?;
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[vault_a, vault_b, from_mint_acc, owner_from_token_acc, owner_to_token_acc])?;
+
+    let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if exchange_booth.vault_a != *vault_a.key || exchange_booth.vault_b != *vault_b.key {
+        msg!("error: vaults do not belong to the exchange booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    // the order must sell one booth token for the other, into accounts the owner holds
+    let mint_a = token::unpack_account(&vault_a.try_borrow_data()?)?.mint;
+    let mint_b = token::unpack_account(&vault_b.try_borrow_data()?)?.mint;
+    let to_mint = if *from_mint_acc.key == mint_a {
+        mint_b
+    } else if *from_mint_acc.key == mint_b {
+        mint_a
+    } else {
+        msg!("error: {} is not traded by this booth", from_mint_acc.key);
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    };
+    let owner_from_token_account = token::unpack_account(&owner_from_token_acc.try_borrow_data()?)?;
+    let owner_to_token_account = token::unpack_account(&owner_to_token_acc.try_borrow_data()?)?;
+    if owner_from_token_account.mint != *from_mint_acc.key || owner_to_token_account.mint != to_mint {
+        msg!("error: owner token accounts do not match the traded mints");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if owner_from_token_account.owner != *owner.key || owner_to_token_account.owner != *owner.key {
+        msg!("error: owner token accounts are not held by the owner");
+        return Err(ExchangeBoothError::InvalidAccountOwner.into())
+    }
+
+    let (limit_order_key, order_bump_seed) = Pubkey::find_program_address(
+        &[
+            b"limit_order",
+            exchange_booth_acc.key.as_ref(),
+            owner.key.as_ref(),
+            &order_id.to_le_bytes(),
+        ],
+        program_id,
+    );
+    if limit_order_key != *limit_order_acc.key {
+        msg!("error: limit order PDA mismatch");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    pda::create_account(
+        program_id,
+        limit_order_acc,
+        LIMIT_ORDER_LEN,
+        &[b"limit_order", exchange_booth_acc.key.as_ref(), owner.key.as_ref(), &order_id.to_le_bytes(), &[order_bump_seed]],
+        owner,
+        system_program,
+    )?;
+    // the tip rides on the order account and is paid out when it gets closed
+    if tip_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(owner.key, limit_order_acc.key, tip_lamports),
+            &[owner.clone(), limit_order_acc.clone(), system_program.clone()]
+        )?;
+    }
+
+    escrow::create(
+        program_id,
+        b"limit_order_escrow",
+        limit_order_acc,
+        escrow,
+        from_mint_acc,
+        owner,
+        system_program,
+        token_program,
+        rent_account,
+    )?;
+
+    // the order sells what the escrow received, after any transfer fee
+    let from_mint = token::unpack_mint(&from_mint_acc.try_borrow_data()?)?;
+    let amount_small = pricing::to_base_units(amount, from_mint.decimals);
+    let received_small = token::transfer_checked(token_program, owner_from_token_acc, from_mint_acc, escrow, owner, amount_small, &[])?;
+    let amount = pricing::to_ui_amount(received_small, from_mint.decimals);
+
+    let limit_order = LimitOrder {
+        exchange_booth: *exchange_booth_acc.key,
+        owner: *owner.key,
+        escrow: *escrow.key,
+        from_mint: *from_mint_acc.key,
+        owner_from_token_account: *owner_from_token_acc.key,
+        owner_to_token_account: *owner_to_token_acc.key,
+        amount,
+        min_rate,
+        tip_lamports
+    };
+    limit_order.serialize(&mut &mut limit_order_acc.data.borrow_mut()[..])?;
+
+    msg!("Limit order {} escrows {} ({}) of {} at min rate {}", limit_order_acc.key, amount, received_small, from_mint_acc.key, min_rate);
+    event::emit(EventData::LimitOrderPlaced {
+        booth: *exchange_booth_acc.key,
+        order: *limit_order_acc.key,
+        owner: *owner.key,
+        from_mint: *from_mint_acc.key,
+        amount: received_small,
+        min_rate,
+    })?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::set_return_data,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    instruction::TradeDirection,
+    pricing,
+    processor::{oracle, token, validate},
+    state::ExchangeBooth,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+    direction: TradeDirection,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let oracle = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let mint_a_acc = next_account_info(account_info_iter)?;
+    let mint_b_acc = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !(amount.is_finite() && amount > 0.0) {
+        msg!("error: amount must be positive, got {}", amount);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[vault_a, vault_b, mint_a_acc, mint_b_acc])?;
+
+    let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if exchange_booth.vault_a != *vault_a.key || exchange_booth.vault_b != *vault_b.key {
+        msg!("error: vaults do not belong to the exchange booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    let vault_a_token_account = token::unpack_account(&vault_a.try_borrow_data()?)?;
+    let vault_b_token_account = token::unpack_account(&vault_b.try_borrow_data()?)?;
+    if vault_a_token_account.mint != *mint_a_acc.key || vault_b_token_account.mint != *mint_b_acc.key {
+        msg!("error: mints do not match the booth vaults");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    let mint_a = token::unpack_mint(&mint_a_acc.try_borrow_data()?)?;
+    let mint_b = token::unpack_mint(&mint_b_acc.try_borrow_data()?)?;
+
+    // same rate and rounding as Exchange, as if the vault received the full amount
+    let exchange_from_a = direction == TradeDirection::AToB;
+    let exchange_rate = oracle::load_rate(program_id, &exchange_booth, oracle)?;
+    let rate = pricing::booth_rate(
+        &exchange_booth,
+        &exchange_rate,
+        pricing::to_ui_amount(vault_a_token_account.amount, mint_a.decimals),
+        pricing::to_ui_amount(vault_b_token_account.amount, mint_b.decimals),
+        exchange_from_a,
+    );
+    let (from_decimals, to_decimals, out_vault_balance) = if exchange_from_a {
+        (mint_a.decimals, mint_b.decimals, vault_b_token_account.amount)
+    } else {
+        (mint_b.decimals, mint_a.decimals, vault_a_token_account.amount)
+    };
+    let result_small = pricing::amount_out(pricing::to_base_units(amount, from_decimals), from_decimals, to_decimals, rate);
+    if result_small > out_vault_balance {
+        msg!("error: booth holds {} of the {} an exchange would pay", out_vault_balance, result_small);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    let oracle_rate = if exchange_from_a {exchange_rate.a_to_b} else {exchange_rate.b_to_a};
+    msg!("Oracle rate {}, booth rate {} after skew and confidence", oracle_rate, rate);
+    let quote_result = pricing::quote(amount, from_decimals, to_decimals, oracle_rate, rate);
+    msg!("Quote: {} in, {} out at {} (fee {})", amount, quote_result.amount_out, quote_result.effective_rate, quote_result.fee);
+    set_return_data(&quote_result.try_to_vec()?);
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+// Booth registries: one PDA of ["booth_registry", mint, mint] per token pair listing every
+// booth that trades it. The mints are sorted before deriving, so an A/B booth and a B/A
+// booth land in the same registry and clients only need the pair to enumerate them.
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    processor::pda,
+    state::{booth_registry_len, BoothRegistry},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn sorted_mints<'a>(mint_a: &'a Pubkey, mint_b: &'a Pubkey) -> (&'a Pubkey, &'a Pubkey) {
+    if mint_a <= mint_b {
+        (mint_a, mint_b)
+    } else {
+        (mint_b, mint_a)
+    }
+}
+
+pub fn find_address(program_id: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> (Pubkey, u8) {
+    let (first, second) = sorted_mints(mint_a, mint_b);
+    Pubkey::find_program_address(&[b"booth_registry", first.as_ref(), second.as_ref()], program_id)
+}
+
+// Appends `booth` to the registry of its pair, creating the registry on the first booth and
+// growing it (with the payer topping up rent) after that.
+pub(crate) fn add<'a>(
+    program_id: &Pubkey,
+    registry: &AccountInfo<'a>,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    booth: &Pubkey,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (registry_key, bump_seed) = find_address(program_id, mint_a, mint_b);
+    if registry_key != *registry.key {
+        msg!("error: booth registry PDA mismatch");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if !registry.is_writable {
+        msg!("error: booth registry not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    let (first, second) = sorted_mints(mint_a, mint_b);
+    let registry_seeds: &[&[u8]] = &[b"booth_registry", first.as_ref(), second.as_ref(), &[bump_seed]];
+
+    let mut booth_registry = if registry.owner == program_id {
+        BoothRegistry::try_from_slice(&registry.data.borrow())
+            .map_err(|_| ExchangeBoothError::InvalidAccountData)?
+    } else {
+        pda::create_account(program_id, registry, booth_registry_len(1), registry_seeds, payer, system_program)?;
+        BoothRegistry { booths: vec![] }
+    };
+    if booth_registry.booths.contains(booth) {
+        msg!("error: booth {} already registered", booth);
+        return Err(ExchangeBoothError::AccountAlreadyInitialized.into())
+    }
+    booth_registry.booths.push(*booth);
+
+    let new_len = booth_registry_len(booth_registry.booths.len());
+    let top_up = Rent::get()?.minimum_balance(new_len).saturating_sub(registry.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, registry.key, top_up),
+            &[payer.clone(), registry.clone(), system_program.clone()]
+        )?;
+    }
+    registry.realloc(new_len, false)?;
+    booth_registry.serialize(&mut &mut registry.data.borrow_mut()[..])?;
+
+    msg!("Registered booth {} in {} ({} booths)", booth, registry.key, booth_registry.booths.len());
+    Ok(())
+}
+
+// Drops `booth` from the registry and refunds the freed rent to `recipient`. An empty
+// registry is drained entirely so the runtime garbage collects it.
+pub(crate) fn remove<'a>(
+    program_id: &Pubkey,
+    registry: &AccountInfo<'a>,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    booth: &Pubkey,
+    recipient: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (registry_key, _) = find_address(program_id, mint_a, mint_b);
+    if registry_key != *registry.key {
+        msg!("error: booth registry PDA mismatch");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if registry.owner != program_id {
+        msg!("error: booth registry not owned by this program");
+        return Err(ExchangeBoothError::InvalidAccountOwner.into())
+    }
+    let mut booth_registry = BoothRegistry::try_from_slice(&registry.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    let position = booth_registry.booths.iter().position(|registered| registered == booth).ok_or_else(|| {
+        msg!("error: booth {} not in registry {}", booth, registry.key);
+        ExchangeBoothError::InvalidAccountData
+    })?;
+    booth_registry.booths.swap_remove(position);
+
+    let (new_len, keep_lamports) = if booth_registry.booths.is_empty() {
+        (0, 0)
+    } else {
+        let new_len = booth_registry_len(booth_registry.booths.len());
+        (new_len, Rent::get()?.minimum_balance(new_len))
+    };
+    if new_len > 0 {
+        booth_registry.serialize(&mut &mut registry.data.borrow_mut()[..])?;
+    }
+    registry.realloc(new_len, false)?;
+
+    let refund = registry.lamports().saturating_sub(keep_lamports);
+    **recipient.lamports.borrow_mut() = recipient.lamports()
+        .checked_add(refund)
+        .ok_or(ExchangeBoothError::InvalidAccountData)?;
+    **registry.lamports.borrow_mut() -= refund;
+
+    msg!("Removed booth {} from {} ({} booths left)", booth, registry.key, booth_registry.booths.len());
+    Ok(())
+}
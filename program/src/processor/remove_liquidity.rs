@@ -0,0 +1,128 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    pricing,
+    processor::{token, validate},
+    state::ExchangeBooth,
+};
+
+use borsh::BorshDeserialize;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let vault_a = next_account_info(account_info_iter)?;
+    let vault_b = next_account_info(account_info_iter)?;
+    let mint_a_acc = next_account_info(account_info_iter)?;
+    let mint_b_acc = next_account_info(account_info_iter)?;
+    let lp_mint_acc = next_account_info(account_info_iter)?;
+    let provider = next_account_info(account_info_iter)?;
+    let provider_token_a_acc = next_account_info(account_info_iter)?;
+    let provider_token_b_acc = next_account_info(account_info_iter)?;
+    let provider_lp_token_acc = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !provider.is_signer {
+        msg!("error: provider must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    for account in [vault_a, vault_b, lp_mint_acc, provider_token_a_acc, provider_token_b_acc, provider_lp_token_acc] {
+        if !account.is_writable {
+            msg!("error: {} not writable", account.key);
+            return Err(ExchangeBoothError::AccountMustBeWritable.into())
+        }
+    }
+
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+    let exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if exchange_booth.vault_a != *vault_a.key || exchange_booth.vault_b != *vault_b.key {
+        msg!("error: vaults do not belong to the exchange booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    if exchange_booth.lp_mint != *lp_mint_acc.key {
+        msg!("error: lp mint does not belong to the exchange booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    token::check_program(token_program)?;
+    token::check_owner(
+        token_program,
+        &[vault_a, vault_b, mint_a_acc, mint_b_acc, lp_mint_acc, provider_token_a_acc, provider_token_b_acc, provider_lp_token_acc]
+    )?;
+    let vault_a_token_account = token::unpack_account(&vault_a.try_borrow_data()?)?;
+    let vault_b_token_account = token::unpack_account(&vault_b.try_borrow_data()?)?;
+    let lp_mint = token::unpack_mint(&lp_mint_acc.try_borrow_data()?)?;
+    if vault_a_token_account.mint != *mint_a_acc.key || vault_b_token_account.mint != *mint_b_acc.key {
+        msg!("error: mints do not match the booth vaults");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    let shares = pricing::to_base_units(amount, lp_mint.decimals);
+    if shares == 0 || shares > lp_mint.supply {
+        msg!("error: cannot redeem {} of {} lp shares", shares, lp_mint.supply);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    // pro-rata on raw vault balances, so trading gains stay with the providers
+    let amount_a_small = (vault_a_token_account.amount as u128 * shares as u128 / lp_mint.supply as u128) as u64;
+    let amount_b_small = (vault_b_token_account.amount as u128 * shares as u128 / lp_mint.supply as u128) as u64;
+    msg!("Provider redeems {} lp shares for {} A and {} B", shares, amount_a_small, amount_b_small);
+
+    token::burn(token_program, provider_lp_token_acc, lp_mint_acc, provider, shares)?;
+
+    for (amount_small, vault, mint, to) in [
+        (amount_a_small, vault_a, mint_a_acc, provider_token_a_acc),
+        (amount_b_small, vault_b, mint_b_acc, provider_token_b_acc),
+    ] {
+        if amount_small == 0 {
+            continue;
+        }
+        let (_, bump_seed) = Pubkey::find_program_address(
+            &[
+                b"exchange_booth",
+                exchange_booth.admin.as_ref(),
+                exchange_booth_acc.key.as_ref(),
+                mint.key.as_ref(),
+            ],
+            program_id,
+        );
+        token::transfer_checked(
+            token_program,
+            vault,
+            mint,
+            to,
+            vault,
+            amount_small,
+            &[&[
+                b"exchange_booth",
+                exchange_booth.admin.as_ref(),
+                exchange_booth_acc.key.as_ref(),
+                mint.key.as_ref(),
+                &[bump_seed]
+            ]],
+        )?;
+    }
+
+    event::emit(EventData::LiquidityRemoved {
+        booth: *exchange_booth_acc.key,
+        provider: *provider.key,
+        amount_a: amount_a_small,
+        amount_b: amount_b_small,
+        shares,
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::validate,
+    state::ExchangeBooth,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    target_ratio: f64,
+    sensitivity: f64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        msg!("error: Admin must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    if !exchange_booth_acc.is_writable {
+        msg!("error: exchange booth not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+
+    let mut exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if exchange_booth.admin != *admin.key {
+        msg!("error: signer is not the booth admin");
+        return Err(ExchangeBoothError::IncorrectAdmin.into())
+    }
+
+    // a ratio of 0 would make every inventory look infinitely long A
+    if !(target_ratio.is_finite() && target_ratio > 0.0) {
+        msg!("error: target ratio must be positive, got {}", target_ratio);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    if !(0.0..1.0).contains(&sensitivity) {
+        msg!("error: sensitivity must be in [0, 1), got {}", sensitivity);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    exchange_booth.skew_target_ratio = target_ratio;
+    exchange_booth.skew_sensitivity = sensitivity;
+    exchange_booth.serialize(&mut &mut exchange_booth_acc.data.borrow_mut()[..])?;
+    event::emit(EventData::InventorySkewSet {
+        booth: *exchange_booth_acc.key,
+        target_ratio,
+        sensitivity,
+    })?;
+
+    msg!("Inventory skew set: target ratio {}, sensitivity {}", target_ratio, sensitivity);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::{oracle, validate},
+    state::{ExchangeBooth, OracleSource},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    source: OracleSource,
+    max_confidence_bps: u64,
+    max_price_age: i64,
+    twap_window: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exchange_booth_acc = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let new_oracle = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        msg!("error: Admin must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    if !exchange_booth_acc.is_writable {
+        msg!("error: exchange booth not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    validate::check_program_owned(program_id, &[exchange_booth_acc])?;
+
+    let mut exchange_booth = ExchangeBooth::try_from_slice(&exchange_booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if exchange_booth.admin != *admin.key {
+        msg!("error: signer is not the booth admin");
+        return Err(ExchangeBoothError::IncorrectAdmin.into())
+    }
+    if source != OracleSource::Pushed && max_price_age <= 0 {
+        msg!("error: {:?} oracles need a positive max age, got {}", source, max_price_age);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    if twap_window < 0 || (twap_window > 0 && source != OracleSource::Pushed) {
+        msg!("error: twap window {} needs a pushed oracle with history", twap_window);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    msg!("Booth {} oracle {} -> {} ({:?})", exchange_booth_acc.key, exchange_booth.oracle, new_oracle.key, source);
+    exchange_booth.oracle = *new_oracle.key;
+    exchange_booth.oracle_source = source;
+    exchange_booth.max_confidence_bps = max_confidence_bps;
+    exchange_booth.max_price_age = max_price_age;
+    exchange_booth.twap_window = twap_window;
+
+    // the next Exchange prices with the new oracle right away, so it must already hold a usable rate
+    let exchange_rate = oracle::load_rate(program_id, &exchange_booth, new_oracle)?;
+    if !(exchange_rate.a_to_b.is_finite() && exchange_rate.a_to_b > 0.0
+        && exchange_rate.b_to_a.is_finite() && exchange_rate.b_to_a > 0.0)
+    {
+        msg!("error: oracle {} has no valid rate", new_oracle.key);
+        return Err(ExchangeBoothError::InvalidAccountData.into())
+    }
+    exchange_booth.serialize(&mut &mut exchange_booth_acc.data.borrow_mut()[..])?;
+    event::emit(EventData::OracleSet {
+        booth: *exchange_booth_acc.key,
+        oracle: *new_oracle.key,
+        source,
+    })?;
+
+    Ok(())
+}
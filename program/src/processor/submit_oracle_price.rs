@@ -0,0 +1,64 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::validate,
+    state::AggregatedOracle,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    a_to_b: f64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let oracle_acc = next_account_info(account_info_iter)?;
+    let publisher = next_account_info(account_info_iter)?;
+
+    if !publisher.is_signer {
+        msg!("error: publisher must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    if !oracle_acc.is_writable {
+        msg!("error: oracle not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    validate::check_program_owned(program_id, &[oracle_acc])?;
+    if !(a_to_b.is_finite() && a_to_b > 0.0) {
+        msg!("error: rate must be positive, got {}", a_to_b);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    let mut aggregated_oracle = AggregatedOracle::try_from_slice(&oracle_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    let submission = aggregated_oracle.submissions
+        .iter_mut()
+        .find(|submission| submission.publisher == *publisher.key)
+        .ok_or_else(|| {
+            msg!("error: {} is not a publisher of oracle {}", publisher.key, oracle_acc.key);
+            ExchangeBoothError::IncorrectAdmin
+        })?;
+    submission.a_to_b = a_to_b;
+    submission.timestamp = Clock::get()?.unix_timestamp;
+    msg!("Publisher {} submitted {} at {}", publisher.key, a_to_b, submission.timestamp);
+
+    aggregated_oracle.serialize(&mut &mut oracle_acc.data.borrow_mut()[..])?;
+    event::emit(EventData::OracleUpdated {
+        oracle: *oracle_acc.key,
+        publisher: Some(*publisher.key),
+        a_to_b,
+        confidence: 0.0,
+    })?;
+    Ok(())
+}
@@ -0,0 +1,336 @@
+// Token CPIs and account unpacking that work with both spl-token and Token-2022.
+// spl_token's instruction builders refuse any other program id, but the base instructions
+// are encoded the same way in Token-2022, so they are built for spl-token and then sent
+// to whichever token program the caller passed.
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{get_return_data, invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use spl_token::state::{Account, Mint};
+
+use crate::error::ExchangeBoothError;
+
+pub mod token_2022 {
+    solana_program::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+    pub mod native_mint {
+        solana_program::declare_id!("9pan9bMn5HatX4EJdBwg9VgCa7Uz5HL8N1m5D3NdXejP");
+    }
+}
+
+pub mod associated_token {
+    solana_program::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
+// Token-2022 pads a mint with extensions to the size of an account, then writes the
+// account type right after the base account state, followed by the extensions
+const ACCOUNT_TYPE_OFFSET: usize = Account::LEN;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+// Token-2022 only: GetAccountDataSize, answered through return data
+const GET_ACCOUNT_DATA_SIZE: u8 = 21;
+
+// Token-2022 only: InitializeMintCloseAuthority, and the size of a mint with just that extension
+// (padded base mint, account type, then a type/length header and the authority)
+const INITIALIZE_MINT_CLOSE_AUTHORITY: u8 = 25;
+const CLOSEABLE_MINT_LEN: usize = ACCOUNT_TYPE_OFFSET + 1 + 4 + 32;
+
+
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::id() || *program_id == token_2022::id()
+}
+
+// associated token accounts are derived with the token program, so each program has its own
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program_id.as_ref(), mint.as_ref()],
+        &associated_token::id(),
+    ).0
+}
+
+// wrapped SOL, for either token program
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::id() || *mint == token_2022::native_mint::id()
+}
+
+pub(crate) fn check_program(token_program: &AccountInfo) -> ProgramResult {
+    if !is_token_program(token_program.key) {
+        msg!("error: {} is not a token program", token_program.key);
+        return Err(ExchangeBoothError::InvalidTokenProgram.into())
+    }
+    Ok(())
+}
+
+// Vaults, mints and token accounts must belong to the token program the instruction calls,
+// otherwise their data is whatever their real owner wrote.
+pub(crate) fn check_owner(token_program: &AccountInfo, accounts: &[&AccountInfo]) -> ProgramResult {
+    for account in accounts {
+        if account.owner != token_program.key {
+            msg!("error: {} is not owned by token program {}", account.key, token_program.key);
+            return Err(ExchangeBoothError::InvalidTokenAccountOwner.into())
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn check_associated_token_program(associated_token_program: &AccountInfo) -> ProgramResult {
+    if *associated_token_program.key != associated_token::id() {
+        msg!("error: {} is not the associated token program", associated_token_program.key);
+        return Err(ExchangeBoothError::InvalidAssociatedTokenProgram.into())
+    }
+    Ok(())
+}
+
+pub fn unpack_account(data: &[u8]) -> Result<Account, ProgramError> {
+    if data.len() < Account::LEN || (data.len() > Account::LEN && data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_ACCOUNT) {
+        return Err(ProgramError::InvalidAccountData)
+    }
+    Account::unpack(&data[..Account::LEN])
+}
+
+pub fn unpack_mint(data: &[u8]) -> Result<Mint, ProgramError> {
+    if data.len() < Mint::LEN || (data.len() > Mint::LEN && data.get(ACCOUNT_TYPE_OFFSET) != Some(&ACCOUNT_TYPE_MINT)) {
+        return Err(ProgramError::InvalidAccountData)
+    }
+    Mint::unpack(&data[..Mint::LEN])
+}
+
+pub(crate) fn balance(account: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(unpack_account(&account.try_borrow_data()?)?.amount)
+}
+
+fn for_program(mut instruction: Instruction, token_program: &AccountInfo) -> Instruction {
+    instruction.program_id = *token_program.key;
+    instruction
+}
+
+// Space a token account for `mint` needs, including the extensions the mint requires.
+pub(crate) fn account_len<'a>(token_program: &AccountInfo<'a>, mint: &AccountInfo<'a>) -> Result<usize, ProgramError> {
+    if *token_program.key != token_2022::id() {
+        return Ok(Account::LEN)
+    }
+    invoke(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![AccountMeta::new_readonly(*mint.key, false)],
+            data: vec![GET_ACCOUNT_DATA_SIZE],
+        },
+        &[mint.clone(), token_program.clone()]
+    )?;
+    match get_return_data() {
+        Some((program_id, data)) if program_id == *token_program.key && data.len() == 8 => {
+            let mut len = [0u8; 8];
+            len.copy_from_slice(&data);
+            Ok(u64::from_le_bytes(len) as usize)
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+pub(crate) fn initialize_account<'a>(
+    token_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    owner: &Pubkey,
+    rent_account: &AccountInfo<'a>,
+) -> ProgramResult {
+    invoke(
+        &for_program(spl_token::instruction::initialize_account(&spl_token::id(), account.key, mint.key, owner)?, token_program),
+        &[token_program.clone(), account.clone(), mint.clone(), rent_account.clone()]
+    )
+}
+
+// spl-token mints can never be closed, Token-2022 ones can if the mint close authority
+// extension is set up before InitializeMint.
+pub(crate) fn closeable_mint_len(token_program: &AccountInfo) -> usize {
+    if *token_program.key == token_2022::id() {CLOSEABLE_MINT_LEN} else {Mint::LEN}
+}
+
+// Call between creating a mint of closeable_mint_len and initialize_mint. Nothing to do for spl-token.
+pub(crate) fn initialize_mint_close_authority<'a>(
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    close_authority: &Pubkey,
+) -> ProgramResult {
+    if *token_program.key != token_2022::id() {
+        return Ok(())
+    }
+    let mut data = vec![INITIALIZE_MINT_CLOSE_AUTHORITY, 1];
+    data.extend_from_slice(close_authority.as_ref());
+    invoke(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![AccountMeta::new(*mint.key, false)],
+            data,
+        },
+        &[token_program.clone(), mint.clone()]
+    )
+}
+
+pub(crate) fn initialize_mint<'a>(
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    mint_authority: &Pubkey,
+    decimals: u8,
+    rent_account: &AccountInfo<'a>,
+) -> ProgramResult {
+    invoke(
+        &for_program(spl_token::instruction::initialize_mint(&spl_token::id(), mint.key, mint_authority, None, decimals)?, token_program),
+        &[token_program.clone(), mint.clone(), rent_account.clone()]
+    )
+}
+
+// Returns what `destination` actually received, which is less than `amount` when the
+// mint charges a Token-2022 transfer fee.
+pub(crate) fn transfer_checked<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64, ProgramError> {
+    let decimals = unpack_mint(&mint.try_borrow_data()?)?.decimals;
+    let balance_before = balance(destination)?;
+    invoke_signed(
+        &for_program(
+            spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                source.key,
+                mint.key,
+                destination.key,
+                authority.key,
+                &[],
+                amount,
+                decimals
+            )?,
+            token_program
+        ),
+        &[token_program.clone(), source.clone(), mint.clone(), destination.clone(), authority.clone()],
+        signer_seeds
+    )?;
+    Ok(balance(destination)?.saturating_sub(balance_before))
+}
+
+pub(crate) fn mint_to<'a>(
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &for_program(spl_token::instruction::mint_to(&spl_token::id(), mint.key, destination.key, authority.key, &[], amount)?, token_program),
+        &[token_program.clone(), mint.clone(), destination.clone(), authority.clone()],
+        signer_seeds
+    )
+}
+
+pub(crate) fn burn<'a>(
+    token_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    invoke(
+        &for_program(spl_token::instruction::burn(&spl_token::id(), account.key, mint.key, authority.key, &[], amount)?, token_program),
+        &[token_program.clone(), account.clone(), mint.clone(), authority.clone()]
+    )
+}
+
+pub(crate) fn close_account<'a>(
+    token_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &for_program(spl_token::instruction::close_account(&spl_token::id(), account.key, destination.key, authority.key, &[])?, token_program),
+        &[token_program.clone(), account.clone(), destination.clone(), authority.clone()],
+        signer_seeds
+    )
+}
+
+// Closes a mint with no supply left if it carries a close authority, otherwise revokes its
+// mint authority so nothing can be minted from it again. `authority` is both.
+pub(crate) fn retire_mint<'a>(
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if mint.data_len() > Mint::LEN {
+        return close_account(token_program, mint, destination, authority, signer_seeds)
+    }
+    invoke_signed(
+        &for_program(
+            spl_token::instruction::set_authority(
+                &spl_token::id(),
+                mint.key,
+                None,
+                spl_token::instruction::AuthorityType::MintTokens,
+                authority.key,
+                &[]
+            )?,
+            token_program
+        ),
+        &[token_program.clone(), mint.clone(), authority.clone()],
+        signer_seeds
+    )
+}
+
+// Brings the token balance of a native account up to its lamports, less rent.
+pub(crate) fn sync_native<'a>(token_program: &AccountInfo<'a>, account: &AccountInfo<'a>) -> ProgramResult {
+    invoke(
+        &for_program(spl_token::instruction::sync_native(&spl_token::id(), account.key)?, token_program),
+        &[token_program.clone(), account.clone()]
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_associated_token_account<'a>(
+    associated_token_program: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    associated_account: &AccountInfo<'a>,
+    wallet: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    invoke(
+        &Instruction {
+            program_id: *associated_token_program.key,
+            accounts: vec![
+                AccountMeta::new(*payer.key, true),
+                AccountMeta::new(*associated_account.key, false),
+                AccountMeta::new_readonly(*wallet.key, false),
+                AccountMeta::new_readonly(*mint.key, false),
+                AccountMeta::new_readonly(*system_program.key, false),
+                AccountMeta::new_readonly(*token_program.key, false),
+            ],
+            data: vec![],
+        },
+        &[
+            payer.clone(),
+            associated_account.clone(),
+            wallet.clone(),
+            mint.clone(),
+            system_program.clone(),
+            token_program.clone(),
+            associated_token_program.clone(),
+        ]
+    )
+}
@@ -0,0 +1,62 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    processor::validate,
+    state::{multi_asset_rate_len, MultiAssetRate},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    prices: Vec<f64>
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let oracle_account_info = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    if !oracle_account_info.is_writable {
+        msg!("error: oracle not writable");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    if !authority.is_signer {
+        msg!("error: oracle authority must be signer");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    validate::check_program_owned(program_id, &[oracle_account_info])?;
+
+    // the account is sized for its booth, a different count would leave stale bytes behind
+    if oracle_account_info.data_len() != multi_asset_rate_len(prices.len()) {
+        msg!("error: oracle has {} bytes, {} prices need {}", oracle_account_info.data_len(), prices.len(), multi_asset_rate_len(prices.len()));
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    if prices.iter().any(|price| !(price.is_finite() && *price > 0.0)) {
+        msg!("error: prices must be positive");
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+
+    let mut rate = MultiAssetRate::try_from_slice(&oracle_account_info.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if rate.authority != *authority.key {
+        msg!("error: {} is not the oracle authority", authority.key);
+        return Err(ExchangeBoothError::IncorrectAdmin.into())
+    }
+
+    rate.prices = prices;
+    rate.serialize(&mut &mut oracle_account_info.data.borrow_mut()[..])?;
+
+    msg!("Oracle {} prices: {:?}", oracle_account_info.key, rate.prices);
+    event::emit(EventData::MultiAssetOracleUpdated {
+        oracle: *oracle_account_info.key,
+        prices: rate.prices,
+    })?;
+    Ok(())
+}
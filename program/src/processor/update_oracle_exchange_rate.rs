@@ -1,21 +1,26 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     program_error::ProgramError,
-    msg
+    msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
 };
 
 use crate::{
     error::ExchangeBoothError,
-    state::ExchangeRate,
+    event::{self, EventData},
+    processor::{oracle, validate},
+    state::RateObservation,
 };
 
-use borsh::{BorshDeserialize, BorshSerialize};
-
 
 pub fn process(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    exchange_rate_a_to_b: f64
+    exchange_rate_a_to_b: f64,
+    confidence: f64
 ) -> ProgramResult {
     
     let account_info_iter = &mut accounts.iter();
@@ -24,14 +29,42 @@ pub fn process(
         msg!("Oracle is not set to is_writable");
         return Err(ProgramError::MissingRequiredSignature);
     }
+    // only the oracle keypair moves the rate, and with it the TWAP history
+    if !oracle_account_info.is_signer {
+        msg!("error: oracle must sign its update");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    validate::check_program_owned(program_id, &[oracle_account_info])?;
+    if !(exchange_rate_a_to_b.is_finite() && exchange_rate_a_to_b > 0.0) {
+        msg!("error: rate must be positive, got {}", exchange_rate_a_to_b);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
+    if !(0.0..1.0).contains(&confidence) {
+        msg!("error: confidence must be in [0, 1), got {}", confidence);
+        return Err(ExchangeBoothError::InvalidInstructionInput.into())
+    }
 
-    let mut exchange_rate = ExchangeRate::try_from_slice(&oracle_account_info.data.borrow())
-        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    let (mut exchange_rate, mut history) = oracle::unpack_exchange_rate(&oracle_account_info.data.borrow())?;
 
     exchange_rate.a_to_b = exchange_rate_a_to_b;
     exchange_rate.b_to_a = 1.0 / exchange_rate_a_to_b;
+    exchange_rate.confidence = confidence;
 
-    exchange_rate.serialize(&mut *oracle_account_info.data.borrow_mut())?;
+    if let Some(history) = history.as_mut() {
+        let clock = Clock::get()?;
+        oracle::record_observation(history, RateObservation {
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            a_to_b: exchange_rate_a_to_b
+        });
+    }
+    oracle::pack_exchange_rate(&exchange_rate, history.as_ref(), &mut oracle_account_info.data.borrow_mut())?;
+    event::emit(EventData::OracleUpdated {
+        oracle: *oracle_account_info.key,
+        publisher: Some(*oracle_account_info.key),
+        a_to_b: exchange_rate.a_to_b,
+        confidence,
+    })?;
 
     msg!("Oracle created at: {}", oracle_account_info.key);
 
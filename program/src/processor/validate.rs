@@ -0,0 +1,40 @@
+// Program id and owner checks for accounts that aren't tokens. Every program passed in is
+// invoked with the booth's signer seeds somewhere, so a substitute must never get that far.
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+
+use crate::error::ExchangeBoothError;
+
+
+pub(crate) fn check_system_program(system_program: &AccountInfo) -> ProgramResult {
+    if !system_program::check_id(system_program.key) {
+        msg!("error: {} is not the system program", system_program.key);
+        return Err(ExchangeBoothError::InvalidSystemProgram.into())
+    }
+    Ok(())
+}
+
+pub(crate) fn check_rent_sysvar(rent_account: &AccountInfo) -> ProgramResult {
+    if !sysvar::rent::check_id(rent_account.key) {
+        msg!("error: {} is not the rent sysvar", rent_account.key);
+        return Err(ExchangeBoothError::InvalidRentSysvar.into())
+    }
+    Ok(())
+}
+
+// State accounts (booths, oracles, orders, schedules) are only trusted when this program wrote them.
+pub(crate) fn check_program_owned(program_id: &Pubkey, accounts: &[&AccountInfo]) -> ProgramResult {
+    for account in accounts {
+        if account.owner != program_id {
+            msg!("error: {} is not owned by this program", account.key);
+            return Err(ExchangeBoothError::InvalidAccountOwner.into())
+        }
+    }
+    Ok(())
+}
@@ -2,14 +2,13 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo}, 
     entrypoint::ProgramResult, msg,
     pubkey::Pubkey,
-    program_pack::{Pack},
-    program::{invoke, invoke_signed},
 };
 
-use spl_token::state::{Account, Mint};
-
 use crate::{
     error::ExchangeBoothError,
+    event::{self, EventData},
+    pricing,
+    processor::{token, validate},
     state::ExchangeBooth,
 };
 
@@ -28,6 +27,7 @@ pub fn process(
     let user_token_account = next_account_info(account_info_iter)?;
     let admin_account = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    let lp_mint_acc = next_account_info(account_info_iter)?;
     msg!("Amount to transfer/withdraw: {}", amount);
     if !admin_account.is_signer {
         msg!("error: Admin account must be signer!");
@@ -41,6 +41,7 @@ pub fn process(
         msg!("error: target vault not writable!");
         return Err(ExchangeBoothError::AccountMustBeWritable.into())
     }
+    validate::check_program_owned(program_id, &[exchange_booth])?;
     let exchange_booth_data = &mut exchange_booth.try_borrow_data()?;
     let deserialized_eb = ExchangeBooth::try_from_slice(exchange_booth_data).unwrap();
 
@@ -67,23 +68,37 @@ pub fn process(
         return Err(ExchangeBoothError::InvalidAccountAddress.into())
     }
 
-    let mint = Mint::unpack(&mint_account.try_borrow_data()?)?;
-    let amount_small: u64 = (amount * f64::powf(10., mint.decimals.into())) as u64;
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[target_vault, mint_account, user_token_account, lp_mint_acc])?;
+
+    // once liquidity providers hold shares the vaults are theirs too, the admin leaves through RemoveLiquidity like them
+    if deserialized_eb.lp_mint != *lp_mint_acc.key {
+        msg!("error: lp mint does not belong to the booth");
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+    let lp_mint = token::unpack_mint(&lp_mint_acc.try_borrow_data()?)?;
+    if lp_mint.supply > 0 {
+        msg!("error: {} lp shares outstanding", lp_mint.supply);
+        return Err(ExchangeBoothError::LiquidityOutstanding.into())
+    }
+    let mint = token::unpack_mint(&mint_account.try_borrow_data()?)?;
+    let amount_small = pricing::to_base_units(amount, mint.decimals);
     msg!("amount small: {}", amount_small);
-    invoke_signed(
-        &spl_token::instruction::transfer_checked(
-            &token_program.key,
-            &target_vault.key,
-            &mint_account.key,
-            &user_token_account.key,
-            &target_vault.key,
-            &[target_vault.key],
-            amount_small,
-            mint.decimals
-        )?,
-        &[token_program.clone(), target_vault.clone(), mint_account.clone(), user_token_account.clone()],
+    token::transfer_checked(
+        token_program,
+        target_vault,
+        mint_account,
+        user_token_account,
+        target_vault,
+        amount_small,
         &[&[b"exchange_booth", admin_account.key.as_ref(), exchange_booth.key.as_ref(), mint_account.key.as_ref(), &[bump_seed]]]
     )?;
+    event::emit(EventData::Withdrawn {
+        booth: *exchange_booth.key,
+        admin: *admin_account.key,
+        mint: *mint_account.key,
+        amount: amount_small,
+    })?;
 
     // to verify this function, look at the target vault account on explorer immediately after depositing and immediately after 
     // withdrawing. You can also look at the user token account before withdrawing (the client prints this out).
@@ -0,0 +1,94 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ExchangeBoothError,
+    event::{self, EventData},
+    pricing,
+    processor::{token, validate},
+    state::MultiAssetBooth,
+};
+
+use borsh::BorshDeserialize;
+
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: f64,
+    asset_index: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let booth_acc = next_account_info(account_info_iter)?;
+    let target_vault = next_account_info(account_info_iter)?;
+    let mint_account = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let admin_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !admin_account.is_signer {
+        msg!("error: Admin account must be signer!");
+        return Err(ExchangeBoothError::AccountMustBeSigner.into())
+    }
+    if !user_token_account.is_writable {
+        msg!("error: User token account is not writable!");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+    if !target_vault.is_writable {
+        msg!("error: target vault not writable!");
+        return Err(ExchangeBoothError::AccountMustBeWritable.into())
+    }
+
+    validate::check_program_owned(program_id, &[booth_acc])?;
+    let booth = MultiAssetBooth::try_from_slice(&booth_acc.data.borrow())
+        .map_err(|_| ExchangeBoothError::InvalidAccountData)?;
+    if booth.admin != *admin_account.key {
+        msg!("error: signer is not the booth admin");
+        return Err(ExchangeBoothError::IncorrectAdmin.into())
+    }
+    let asset = booth.assets.get(asset_index as usize).ok_or_else(|| {
+        msg!("error: booth has no asset {}", asset_index);
+        ExchangeBoothError::InvalidInstructionInput
+    })?;
+    if asset.vault != *target_vault.key || asset.mint != *mint_account.key {
+        msg!("error: vault or mint does not match asset {}", asset_index);
+        return Err(ExchangeBoothError::InvalidAccountAddress.into())
+    }
+
+    let (_, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"exchange_booth",
+            admin_account.key.as_ref(),
+            booth_acc.key.as_ref(),
+            mint_account.key.as_ref()
+        ],
+        program_id,
+    );
+
+    token::check_program(token_program)?;
+    token::check_owner(token_program, &[target_vault, mint_account, user_token_account])?;
+    let mint = token::unpack_mint(&mint_account.try_borrow_data()?)?;
+    let amount_small = pricing::to_base_units(amount, mint.decimals);
+    msg!("Withdrawing {} ({}) of asset {}", amount, amount_small, asset_index);
+    token::transfer_checked(
+        token_program,
+        target_vault,
+        mint_account,
+        user_token_account,
+        target_vault,
+        amount_small,
+        &[&[b"exchange_booth", admin_account.key.as_ref(), booth_acc.key.as_ref(), mint_account.key.as_ref(), &[bump_seed]]]
+    )?;
+    event::emit(EventData::Withdrawn {
+        booth: *booth_acc.key,
+        admin: *admin_account.key,
+        mint: *mint_account.key,
+        amount: amount_small,
+    })?;
+
+    Ok(())
+}
@@ -6,15 +6,191 @@ use std::mem::size_of;
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct ExchangeRate {
     pub a_to_b: f64,
-    pub b_to_a: f64
+    pub b_to_a: f64,
+    // uncertainty of the rate as a fraction of it, widens the booth's spread by as much
+    pub confidence: f64
 }
 
-pub const EXCHANGE_BOOTH_LEN: usize = size_of::<Pubkey>() * 4;
+pub const EXCHANGE_RATE_LEN: usize = size_of::<f64>() * 3;
+
+// oracles created before confidence was added hold only a_to_b and b_to_a, optionally followed
+// by a RateHistory too. They read as confidence 0 and can't be given any other.
+pub const LEGACY_EXCHANGE_RATE_LEN: usize = size_of::<f64>() * 2;
+
+// updates an oracle with history remembers, the oldest one is overwritten first
+pub const RATE_HISTORY_SIZE: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateObservation {
+    pub slot: u64,
+    // 0 for a slot that was never written
+    pub timestamp: i64,
+    pub a_to_b: f64
+}
+
+// Optional tail of an ExchangeRate account. Oracles allocated with
+// EXCHANGE_RATE_LEN + RATE_HISTORY_LEN bytes record every update here.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RateHistory {
+    // slot the next update goes to, which is also the oldest observation
+    pub next_index: u32,
+    pub observations: Vec<RateObservation>
+}
+
+pub const RATE_HISTORY_LEN: usize = size_of::<u32>() * 2
+    + (size_of::<u64>() + size_of::<i64>() + size_of::<f64>()) * RATE_HISTORY_SIZE;
+
+// fee charged on flash loans, in basis points of the borrowed amount (rounded up)
+pub const FLASH_LOAN_FEE_BPS: u64 = 9;
+
+// where a booth's oracle account gets its rate from, see processor::oracle
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum OracleSource {
+    // ExchangeRate account updated with UpdateOracleExchangeRate
+    Pushed,
+    // external price account in the Pyth v2 layout, quoting the price of A in B
+    Pyth,
+    // AggregatedOracle, priced at the median of its fresh submissions
+    Aggregated,
+}
+
+pub const EXCHANGE_BOOTH_LEN: usize = size_of::<Pubkey>() * 5 + size_of::<f64>() * 2
+    + size_of::<u8>() + size_of::<u64>() + size_of::<i64>() * 2;
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct ExchangeBooth {
     pub admin: Pubkey,
     pub oracle: Pubkey,
     pub vault_a: Pubkey, 
-    pub vault_b: Pubkey
+    pub vault_b: Pubkey,
+    // PDA mint for liquidity provider shares, is its own mint authority like the vaults
+    pub lp_mint: Pubkey,
+    // target value of vault A (priced in B) over the balance of vault B
+    pub skew_target_ratio: f64,
+    // 0 disables inventory skew, must be < 1 so the skewed rates stay positive
+    pub skew_sensitivity: f64,
+    pub oracle_source: OracleSource,
+    // widest confidence interval accepted, in basis points of the rate, 0 for no limit
+    pub max_confidence_bps: u64,
+    // Pyth and Aggregated: oldest price accepted, in seconds before now
+    pub max_price_age: i64,
+    // Pushed only: price at the time weighted average of this many seconds of rate history, 0 for spot
+    pub twap_window: i64
+}
+
+pub const QUOTE_NONCE_LEN: usize = size_of::<u64>();
+
+// last quote nonce a customer filled on a booth, for replay protection
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct QuoteNonce {
+    pub last_nonce: u64
+}
+
+// every booth trading a token pair, see processor::registry for the address
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct BoothRegistry {
+    pub booths: Vec<Pubkey>
+}
+
+pub fn booth_registry_len(num_booths: usize) -> usize {
+    size_of::<u32>() + size_of::<Pubkey>() * num_booths
+}
+
+pub const LIMIT_ORDER_LEN: usize = size_of::<Pubkey>() * 6 + size_of::<f64>() * 2 + size_of::<u64>();
+
+// resting order, the escrow token account holds `amount` of from_mint until it is filled or cancelled
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LimitOrder {
+    pub exchange_booth: Pubkey,
+    pub owner: Pubkey,
+    pub escrow: Pubkey,
+    pub from_mint: Pubkey,
+    // refunds and leftovers go here
+    pub owner_from_token_account: Pubkey,
+    // fills are paid here
+    pub owner_to_token_account: Pubkey,
+    pub amount: f64,
+    // worst rate (to tokens per from token) the owner accepts
+    pub min_rate: f64,
+    // lamports on top of rent paid to whoever cranks the fill
+    pub tip_lamports: u64
+}
+
+pub const DCA_SCHEDULE_LEN: usize = size_of::<Pubkey>() * 6 + size_of::<f64>() + size_of::<i64>() * 2 + size_of::<u64>();
+
+// recurring exchange of `amount` every `interval_seconds`, the escrow holds what is left to sell
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct DcaSchedule {
+    pub exchange_booth: Pubkey,
+    pub owner: Pubkey,
+    pub escrow: Pubkey,
+    pub from_mint: Pubkey,
+    // leftovers go here once the schedule completes
+    pub owner_from_token_account: Pubkey,
+    // every execution is paid here
+    pub owner_to_token_account: Pubkey,
+    pub amount: f64,
+    pub interval_seconds: i64,
+    // unix timestamp from which the next execution is allowed
+    pub next_execution_ts: i64,
+    pub remaining: u64
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct BoothAsset {
+    pub mint: Pubkey,
+    // PDA of ["exchange_booth", admin, booth, mint], same as the two token booth vaults
+    pub vault: Pubkey
+}
+
+// booth trading any pair among `assets`, sized for its asset count at creation
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MultiAssetBooth {
+    pub admin: Pubkey,
+    // holds a MultiAssetRate with one price per asset, in the same order
+    pub oracle: Pubkey,
+    pub assets: Vec<BoothAsset>
+}
+
+pub fn multi_asset_booth_len(num_assets: usize) -> usize {
+    size_of::<Pubkey>() * 2 + size_of::<u32>() + size_of::<Pubkey>() * 2 * num_assets
+}
+
+// price of each asset of a MultiAssetBooth in units of a common quote asset
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MultiAssetRate {
+    // the only key that can update the prices, set to the booth admin by InitializeMultiAssetBooth
+    pub authority: Pubkey,
+    // 0 until the first UpdateMultiAssetOracle
+    pub prices: Vec<f64>
+}
+
+pub fn multi_asset_rate_len(num_assets: usize) -> usize {
+    size_of::<Pubkey>() + size_of::<u32>() + size_of::<f64>() * num_assets
+}
+
+// bounds the account size and the sort every priced trade does
+pub const MAX_ORACLE_PUBLISHERS: usize = 16;
+
+// one publisher's latest rate in an AggregatedOracle
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct OracleSubmission {
+    pub publisher: Pubkey,
+    pub a_to_b: f64,
+    // unix timestamp of the last submission, 0 until the publisher submits
+    pub timestamp: i64
+}
+
+// rate reported by several publishers, each writing only its own submission
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AggregatedOracle {
+    pub admin: Pubkey,
+    // fresh submissions needed before booths will price with it
+    pub quorum: u8,
+    pub submissions: Vec<OracleSubmission>
+}
+
+pub fn aggregated_oracle_len(num_publishers: usize) -> usize {
+    size_of::<Pubkey>() + size_of::<u8>() + size_of::<u32>()
+        + (size_of::<Pubkey>() + size_of::<f64>() + size_of::<i64>()) * num_publishers
 }
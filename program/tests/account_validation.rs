@@ -0,0 +1,281 @@
+// Every program id, account owner and derived or recorded address a processor checks, substituted
+// one at a time. The families with state of their own (orders, schedules, routes, ...) keep these
+// cases next to their other tests.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+use exchangebooth::{
+    client::{self, BoothAccounts},
+    error::ExchangeBoothError,
+    instruction::TradeDirection,
+    state::OracleSource,
+};
+
+use common::{assert_error, process, token_balance, BoothFixture, CUSTOMER_BALANCE};
+
+
+#[tokio::test]
+async fn exchange_with_genuine_accounts() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE - 1_000_000);
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_000_000);
+}
+
+#[tokio::test]
+async fn exchange_rejects_fake_system_program() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let mut instruction = fixture.exchange();
+    instruction.accounts[9].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidSystemProgram);
+}
+
+#[tokio::test]
+async fn exchange_rejects_fake_token_program() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let mut instruction = fixture.exchange();
+    instruction.accounts[10].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn exchange_rejects_fake_associated_token_program() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let mut instruction = client::exchange(
+        &fixture.program_id,
+        &fixture.booth,
+        &fixture.customer.pubkey(),
+        &fixture.customer_a,
+        &fixture.customer_b,
+        1.0,
+        true,
+    );
+    instruction.accounts[11].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAssociatedTokenProgram);
+}
+
+#[tokio::test]
+async fn initialize_rejects_fake_rent_sysvar() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let booth = BoothAccounts::new(
+        &fixture.program_id,
+        &fixture.admin.pubkey(),
+        &fixture.booth.oracle,
+        &fixture.booth.mint_a,
+        &fixture.booth.mint_b,
+        1,
+        &spl_token::id(),
+    );
+    let mut instruction = client::initialize_exchange_booth(&fixture.program_id, &booth, 1);
+    instruction.accounts[9].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[instruction], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidRentSysvar);
+}
+
+#[tokio::test]
+async fn exchange_rejects_booth_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn exchange_rejects_oracle_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let oracle = fixture.booth.oracle;
+    fixture.set_owner(&oracle, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn exchange_rejects_vault_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let vault = fixture.booth.vault_b;
+    fixture.set_owner(&vault, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn exchange_rejects_mint_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let mint = fixture.booth.mint_a;
+    fixture.set_owner(&mint, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+// admin takes 1 A out into a token account of their own
+fn withdraw(fixture: &mut BoothFixture) -> Instruction {
+    let (mint_a, admin) = (fixture.booth.mint_a, fixture.admin.pubkey());
+    let admin_a = fixture.add_token_account(&mint_a, &admin, 0);
+    client::withdraw(&fixture.program_id, &fixture.booth, &mint_a, &admin_a, 1.0)
+}
+
+#[tokio::test]
+async fn withdraw_rejects_booth_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let instruction = withdraw(&mut fixture);
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn withdraw_rejects_fake_token_program() {
+    let mut fixture = BoothFixture::new();
+    let mut instruction = withdraw(&mut fixture);
+    instruction.accounts[5].pubkey = Pubkey::new_unique();
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn withdraw_rejects_vault_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let instruction = withdraw(&mut fixture);
+    let vault = fixture.booth.vault_a;
+    fixture.set_owner(&vault, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn withdraw_rejects_the_vault_of_the_other_mint() {
+    let mut fixture = BoothFixture::new();
+    let mut instruction = withdraw(&mut fixture);
+    // vault B is the booth's, but not the PDA for mint A
+    instruction.accounts[1].pubkey = fixture.booth.vault_b;
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+fn quote(fixture: &BoothFixture) -> Instruction {
+    client::quote(&fixture.program_id, &fixture.booth, 1.0, TradeDirection::AToB)
+}
+
+#[tokio::test]
+async fn quote_rejects_booth_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[quote(&fixture)], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn quote_rejects_fake_token_program() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let mut instruction = quote(&fixture);
+    instruction.accounts[6].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[instruction], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn quote_rejects_oracle_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let oracle = fixture.booth.oracle;
+    fixture.set_owner(&oracle, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[quote(&fixture)], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn quote_rejects_another_booths_oracle() {
+    let mut fixture = BoothFixture::new();
+    let mint_a = fixture.booth.mint_a;
+    let other_booth = fixture.add_booth(&mint_a, 3.0);
+    let (mut context, fixture) = fixture.start().await;
+    let mut instruction = quote(&fixture);
+    instruction.accounts[1].pubkey = other_booth.oracle;
+    let result = process(&mut context, &[instruction], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn quote_rejects_another_booths_vault() {
+    let mut fixture = BoothFixture::new();
+    let mint_a = fixture.booth.mint_a;
+    let other_booth = fixture.add_booth(&mint_a, 3.0);
+    let (mut context, fixture) = fixture.start().await;
+    let mut instruction = quote(&fixture);
+    instruction.accounts[2].pubkey = other_booth.vault_a;
+    let result = process(&mut context, &[instruction], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+fn set_oracle(fixture: &BoothFixture, new_oracle: &Pubkey) -> Instruction {
+    client::set_oracle(&fixture.program_id, &fixture.booth, new_oracle, OracleSource::Pushed, 0, 0, 0)
+}
+
+#[tokio::test]
+async fn set_oracle_rejects_booth_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let mint_a = fixture.booth.mint_a;
+    let new_oracle = fixture.add_booth(&mint_a, 3.0).oracle;
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[set_oracle(&fixture, &new_oracle)], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn set_oracle_rejects_oracle_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let mint_a = fixture.booth.mint_a;
+    let new_oracle = fixture.add_booth(&mint_a, 3.0).oracle;
+    fixture.set_owner(&new_oracle, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[set_oracle(&fixture, &new_oracle)], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn set_oracle_rejects_another_signer() {
+    let mut fixture = BoothFixture::new();
+    let mint_a = fixture.booth.mint_a;
+    let new_oracle = fixture.add_booth(&mint_a, 3.0).oracle;
+    let (mut context, fixture) = fixture.start().await;
+    let mut instruction = set_oracle(&fixture, &new_oracle);
+    instruction.accounts[1].pubkey = fixture.customer.pubkey();
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::IncorrectAdmin);
+}
+
+#[tokio::test]
+async fn set_oracle_switches_to_a_program_owned_oracle() {
+    let mut fixture = BoothFixture::new();
+    let mint_a = fixture.booth.mint_a;
+    let new_oracle = fixture.add_booth(&mint_a, 3.0).oracle;
+    let (mut context, fixture) = fixture.start().await;
+    process(&mut context, &[set_oracle(&fixture, &new_oracle)], &[&fixture.admin]).await.unwrap();
+    let mut booth = fixture.booth.clone();
+    booth.oracle = new_oracle;
+    let instruction = client::exchange(&fixture.program_id, &booth, &fixture.customer.pubkey(), &fixture.customer_a, &fixture.customer_b, 1.0, false);
+    process(&mut context, &[instruction], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 3_000_000);
+}
@@ -0,0 +1,178 @@
+// Aggregated oracles: the admin lists the publishers, each publisher submits its own rate and
+// booths price at the median of the fresh submissions once a quorum of them is in.
+#![cfg(feature = "client")]
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{clock::Clock, instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+use exchangebooth::{
+    client,
+    error::ExchangeBoothError,
+    state::{aggregated_oracle_len, AggregatedOracle, ExchangeBooth, OracleSource, OracleSubmission},
+};
+
+use common::{assert_error, process, program_account, system_account, token_balance, BoothFixture};
+
+const NOW: i64 = 1_650_000_000;
+const MAX_PRICE_AGE: i64 = 60;
+const QUORUM: u8 = 2;
+
+struct AggregatedFixture {
+    fixture: BoothFixture,
+    publishers: [Keypair; 3],
+}
+
+// The fixture booth priced by an aggregated oracle of three publishers. `submissions` are
+// (a_to_b, timestamp) per publisher already in the oracle, None leaves it uninitialized.
+fn aggregated_fixture(submissions: Option<[(f64, i64); 3]>) -> AggregatedFixture {
+    let mut fixture = BoothFixture::new();
+    let publishers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for publisher in publishers.iter() {
+        fixture.accounts.insert(publisher.pubkey(), system_account());
+    }
+    let mut data = vec![0; aggregated_oracle_len(publishers.len())];
+    if let Some(submissions) = submissions {
+        AggregatedOracle {
+            admin: fixture.admin.pubkey(),
+            quorum: QUORUM,
+            submissions: publishers
+                .iter()
+                .zip(submissions)
+                .map(|(publisher, (a_to_b, timestamp))| OracleSubmission { publisher: publisher.pubkey(), a_to_b, timestamp })
+                .collect(),
+        }.serialize(&mut &mut data[..]).unwrap();
+    }
+    fixture.accounts.insert(fixture.booth.oracle, program_account(&fixture.program_id, data));
+    fixture.set_booth(&ExchangeBooth {
+        admin: fixture.booth.admin,
+        oracle: fixture.booth.oracle,
+        vault_a: fixture.booth.vault_a,
+        vault_b: fixture.booth.vault_b,
+        lp_mint: fixture.booth.lp_mint,
+        skew_target_ratio: 1.0,
+        skew_sensitivity: 0.0,
+        oracle_source: OracleSource::Aggregated,
+        max_confidence_bps: 0,
+        max_price_age: MAX_PRICE_AGE,
+        twap_window: 0,
+    });
+    AggregatedFixture { fixture, publishers }
+}
+
+async fn start(aggregated: AggregatedFixture) -> (ProgramTestContext, BoothFixture, [Keypair; 3]) {
+    let (mut context, fixture) = aggregated.fixture.start().await;
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = NOW;
+    context.set_sysvar(&clock);
+    (context, fixture, aggregated.publishers)
+}
+
+fn initialize(fixture: &BoothFixture, publishers: Vec<Pubkey>, quorum: u8) -> Instruction {
+    client::initialize_aggregated_oracle(&fixture.program_id, &fixture.booth.oracle, &fixture.admin.pubkey(), publishers, quorum)
+}
+
+fn submit(fixture: &BoothFixture, publisher: &Keypair, a_to_b: f64) -> Instruction {
+    client::submit_oracle_price(&fixture.program_id, &fixture.booth.oracle, &publisher.pubkey(), a_to_b)
+}
+
+async fn load_oracle(context: &mut ProgramTestContext, fixture: &BoothFixture) -> AggregatedOracle {
+    let account = context.banks_client.get_account(fixture.booth.oracle).await.unwrap().unwrap();
+    AggregatedOracle::try_from_slice(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn initialize_lists_the_publishers_without_submissions() {
+    let (mut context, fixture, publishers) = start(aggregated_fixture(None)).await;
+    let keys: Vec<Pubkey> = publishers.iter().map(|publisher| publisher.pubkey()).collect();
+    process(&mut context, &[initialize(&fixture, keys.clone(), QUORUM)], &[&fixture.admin]).await.unwrap();
+
+    let oracle = load_oracle(&mut context, &fixture).await;
+    assert_eq!(oracle.admin, fixture.admin.pubkey());
+    assert_eq!(oracle.quorum, QUORUM);
+    let expected: Vec<OracleSubmission> = keys.into_iter()
+        .map(|publisher| OracleSubmission { publisher, a_to_b: 0.0, timestamp: 0 })
+        .collect();
+    assert_eq!(oracle.submissions, expected);
+}
+
+#[tokio::test]
+async fn initialize_rejects_a_quorum_above_the_publishers() {
+    let (mut context, fixture, publishers) = start(aggregated_fixture(None)).await;
+    let keys = publishers.iter().map(|publisher| publisher.pubkey()).collect();
+    let result = process(&mut context, &[initialize(&fixture, keys, 4)], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn initialize_rejects_a_publisher_listed_twice() {
+    let (mut context, fixture, publishers) = start(aggregated_fixture(None)).await;
+    let keys = vec![publishers[0].pubkey(), publishers[1].pubkey(), publishers[0].pubkey()];
+    let result = process(&mut context, &[initialize(&fixture, keys, QUORUM)], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn initialize_rejects_an_initialized_oracle() {
+    let (mut context, fixture, publishers) = start(aggregated_fixture(Some([(2.0, NOW); 3]))).await;
+    let keys = publishers.iter().map(|publisher| publisher.pubkey()).collect();
+    let result = process(&mut context, &[initialize(&fixture, keys, QUORUM)], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::AccountAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn submit_records_the_rate_and_time() {
+    let (mut context, fixture, publishers) = start(aggregated_fixture(Some([(0.0, 0); 3]))).await;
+    process(&mut context, &[submit(&fixture, &publishers[1], 2.5)], &[&publishers[1]]).await.unwrap();
+    let oracle = load_oracle(&mut context, &fixture).await;
+    assert_eq!(oracle.submissions[1], OracleSubmission { publisher: publishers[1].pubkey(), a_to_b: 2.5, timestamp: NOW });
+    assert_eq!(oracle.submissions[0].timestamp, 0);
+}
+
+#[tokio::test]
+async fn submit_rejects_someone_not_listed() {
+    let (mut context, fixture, _) = start(aggregated_fixture(Some([(0.0, 0); 3]))).await;
+    let outsider = &fixture.customer;
+    let result = process(&mut context, &[submit(&fixture, outsider, 2.5)], &[outsider]).await;
+    assert_error(result, ExchangeBoothError::IncorrectAdmin);
+}
+
+#[tokio::test]
+async fn exchange_prices_at_the_median_submission() {
+    // the third publisher is far off, the median ignores it
+    let (mut context, fixture, _) = start(aggregated_fixture(Some([(1.9, NOW), (2.0, NOW - 10), (50.0, NOW)]))).await;
+    process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_000_000);
+}
+
+#[tokio::test]
+async fn exchange_prices_at_the_mean_of_the_middle_two() {
+    // the third submission is too old to count, leaving an even number
+    let submissions = [(2.0, NOW), (3.0, NOW), (50.0, NOW - MAX_PRICE_AGE - 1)];
+    let (mut context, fixture, _) = start(aggregated_fixture(Some(submissions))).await;
+    process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_500_000);
+}
+
+#[tokio::test]
+async fn exchange_rejects_fewer_fresh_submissions_than_the_quorum() {
+    let submissions = [(2.0, NOW), (2.0, NOW - MAX_PRICE_AGE - 1), (0.0, 0)];
+    let (mut context, fixture, _) = start(aggregated_fixture(Some(submissions))).await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::OracleQuorumNotMet);
+}
+
+#[tokio::test]
+async fn submissions_reach_the_quorum_for_the_next_exchange() {
+    let (mut context, fixture, publishers) = start(aggregated_fixture(Some([(0.0, 0); 3]))).await;
+    let instructions = [
+        submit(&fixture, &publishers[0], 2.0),
+        submit(&fixture, &publishers[2], 3.0),
+        fixture.exchange(),
+    ];
+    process(&mut context, &instructions, &[&publishers[0], &publishers[2], &fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_500_000);
+}
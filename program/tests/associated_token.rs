@@ -0,0 +1,58 @@
+// Exchange with an associated token program after its accounts: the customer is paid into their
+// associated token account for the bought mint, which is created if it is missing.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+use exchangebooth::{client, error::ExchangeBoothError, processor::token};
+
+use common::{assert_error, process, token_account, token_balance, BoothFixture};
+
+fn customer_ata(fixture: &BoothFixture) -> Pubkey {
+    token::associated_token_address(&fixture.customer.pubkey(), &fixture.booth.mint_b, &spl_token::id())
+}
+
+fn exchange_into(fixture: &BoothFixture, customer_to_token_account: &Pubkey) -> Instruction {
+    client::exchange(
+        &fixture.program_id,
+        &fixture.booth,
+        &fixture.customer.pubkey(),
+        &fixture.customer_a,
+        customer_to_token_account,
+        1.0,
+        true,
+    )
+}
+
+#[tokio::test]
+async fn pays_into_an_existing_associated_token_account() {
+    let mut fixture = BoothFixture::new();
+    let ata = customer_ata(&fixture);
+    let (customer, mint_b) = (fixture.customer.pubkey(), fixture.booth.mint_b);
+    fixture.accounts.insert(ata, token_account(&mint_b, &customer, 0));
+    let (mut context, fixture) = fixture.start().await;
+    process(&mut context, &[exchange_into(&fixture, &ata)], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &ata).await, 2_000_000);
+}
+
+#[tokio::test]
+async fn rejects_a_destination_that_is_not_the_associated_token_account() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let result = process(&mut context, &[exchange_into(&fixture, &fixture.customer_b)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+// The associated token program creates the account through CPI, which only the BPF build can
+// follow in solana-program-test 1.9
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn creates_a_missing_associated_token_account() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let ata = customer_ata(&fixture);
+    process(&mut context, &[exchange_into(&fixture, &ata)], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &ata).await, 2_000_000);
+}
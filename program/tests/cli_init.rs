@@ -0,0 +1,89 @@
+// What `exchange-booth-cli init --oracle-keypair` sends: a new oracle with rate history, its
+// first rate and the booth, all in one transaction.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::{pubkey::Pubkey, rent::Rent};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+use exchangebooth::{
+    client,
+    processor::oracle,
+    state::{ExchangeRate, RateHistory},
+};
+
+use common::{process, BoothFixture, A_TO_B};
+
+
+// the part of init that used to revert: the first update of an oracle allocated with history
+#[tokio::test]
+async fn init_creates_oracle_with_history() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let oracle_keypair = Keypair::new();
+    let instructions = client::create_oracle(
+        &fixture.program_id,
+        &fixture.admin.pubkey(),
+        &oracle_keypair.pubkey(),
+        Rent::default().minimum_balance(client::ORACLE_WITH_HISTORY_LEN),
+        A_TO_B,
+    );
+    process(&mut context, &instructions, &[&fixture.admin, &oracle_keypair]).await.unwrap();
+    let (exchange_rate, history) = load_oracle(&mut context, &oracle_keypair.pubkey()).await;
+    assert_eq!(exchange_rate.a_to_b, A_TO_B);
+    let history = history.unwrap();
+    assert_eq!(history.next_index, 1);
+    assert_eq!(history.observations[0].a_to_b, A_TO_B);
+
+    // and what `set-rate` sends afterwards
+    let update = client::update_oracle_exchange_rate(&fixture.program_id, &oracle_keypair.pubkey(), 2.5, 0.0);
+    process(&mut context, &[update], &[&oracle_keypair]).await.unwrap();
+    let (exchange_rate, history) = load_oracle(&mut context, &oracle_keypair.pubkey()).await;
+    assert_eq!(exchange_rate.a_to_b, 2.5);
+    assert_eq!(history.unwrap().next_index, 2);
+}
+
+// InitializeExchangeBooth creates accounts through CPI, which native processors can't do in
+// solana-program-test 1.9, so the whole transaction only runs against the BPF build
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn init_creates_oracle_and_booth_in_one_transaction() {
+    use borsh::BorshDeserialize;
+    use exchangebooth::state::{ExchangeBooth, OracleSource};
+
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let oracle_keypair = Keypair::new();
+    let admin = &fixture.admin;
+    let booth = client::BoothAccounts::new(
+        &fixture.program_id,
+        &admin.pubkey(),
+        &oracle_keypair.pubkey(),
+        &fixture.booth.mint_a,
+        &fixture.booth.mint_b,
+        1,
+        &spl_token::id(),
+    );
+    let mut instructions = client::create_oracle(
+        &fixture.program_id,
+        &admin.pubkey(),
+        &oracle_keypair.pubkey(),
+        Rent::default().minimum_balance(client::ORACLE_WITH_HISTORY_LEN),
+        A_TO_B,
+    );
+    instructions.push(client::initialize_exchange_booth(&fixture.program_id, &booth, 1));
+    process(&mut context, &instructions, &[admin, &oracle_keypair]).await.unwrap();
+
+    let (exchange_rate, history) = load_oracle(&mut context, &oracle_keypair.pubkey()).await;
+    assert_eq!(exchange_rate.a_to_b, A_TO_B);
+    assert_eq!(history.unwrap().next_index, 1);
+    let booth_account = context.banks_client.get_account(booth.exchange_booth).await.unwrap().unwrap();
+    let exchange_booth = ExchangeBooth::try_from_slice(&booth_account.data).unwrap();
+    assert_eq!(exchange_booth.oracle, oracle_keypair.pubkey());
+    assert_eq!(exchange_booth.oracle_source, OracleSource::Pushed);
+}
+
+async fn load_oracle(context: &mut ProgramTestContext, oracle_address: &Pubkey) -> (ExchangeRate, Option<RateHistory>) {
+    let account = context.banks_client.get_account(*oracle_address).await.unwrap().unwrap();
+    oracle::unpack_exchange_rate(&account.data).unwrap()
+}
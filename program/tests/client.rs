@@ -0,0 +1,55 @@
+// The client builders against the program: addresses they derive and a run of a booth's
+// instructions built only through them.
+#![cfg(feature = "client")]
+
+mod common;
+
+use borsh::BorshDeserialize;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+use exchangebooth::{
+    client::{self, BoothAccounts},
+    state::ExchangeBooth,
+};
+
+use common::{process, token_balance, BoothFixture, CUSTOMER_BALANCE, VAULT_BALANCE};
+
+#[tokio::test]
+async fn booth_accounts_read_from_chain_match_the_derived_ones() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let account = context.banks_client.get_account(fixture.booth.exchange_booth).await.unwrap().unwrap();
+    let exchange_booth = ExchangeBooth::try_from_slice(&account.data).unwrap();
+    let booth = &fixture.booth;
+    let from_booth = BoothAccounts::from_booth(&booth.exchange_booth, &exchange_booth, &booth.mint_a, &booth.mint_b, &booth.token_program);
+    assert_eq!(&from_booth, booth);
+}
+
+#[tokio::test]
+async fn trade_both_ways_then_withdraw() {
+    let mut fixture = BoothFixture::new();
+    let (mint_a, mint_b, admin) = (fixture.booth.mint_a, fixture.booth.mint_b, fixture.admin.pubkey());
+    let admin_a = fixture.add_token_account(&mint_a, &admin, 0);
+    let admin_b = fixture.add_token_account(&mint_b, &admin, 0);
+    let (mut context, fixture) = fixture.start().await;
+    let (program_id, booth, customer) = (&fixture.program_id, &fixture.booth, fixture.customer.pubkey());
+
+    // 1 A for 2 B, then 1 B back for 0.5 A
+    let instructions = [
+        client::exchange(program_id, booth, &customer, &fixture.customer_a, &fixture.customer_b, 1.0, false),
+        client::exchange(program_id, booth, &customer, &fixture.customer_b, &fixture.customer_a, 1.0, false),
+    ];
+    process(&mut context, &instructions, &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE - 500_000);
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 1_000_000);
+
+    let instructions = [
+        client::withdraw(program_id, booth, &mint_a, &admin_a, 0.5),
+        client::withdraw(program_id, booth, &mint_b, &admin_b, 1.0),
+    ];
+    process(&mut context, &instructions, &[&fixture.admin]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &admin_a).await, 500_000);
+    assert_eq!(token_balance(&mut context, &admin_b).await, 1_000_000);
+    assert_eq!(token_balance(&mut context, &booth.vault_a).await, VAULT_BALANCE);
+    assert_eq!(token_balance(&mut context, &booth.vault_b).await, VAULT_BALANCE - 2_000_000);
+}
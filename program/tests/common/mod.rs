@@ -0,0 +1,263 @@
+// A funded booth trading A for B at 2 B per A, laid out directly in the genesis accounts so a
+// test can tamper with any of them (owner, data) before the bank starts.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+use exchangebooth::{
+    client::{self, BoothAccounts},
+    error::ExchangeBoothError,
+    processor::Processor,
+    state::{ExchangeBooth, ExchangeRate, OracleSource, EXCHANGE_BOOTH_LEN, EXCHANGE_RATE_LEN},
+};
+
+pub const DECIMALS: u8 = 6;
+pub const VAULT_BALANCE: u64 = 1_000 * 1_000_000;
+pub const CUSTOMER_BALANCE: u64 = 100 * 1_000_000;
+pub const A_TO_B: f64 = 2.0;
+
+pub struct BoothFixture {
+    pub program_id: Pubkey,
+    pub admin: Keypair,
+    pub customer: Keypair,
+    pub booth: BoothAccounts,
+    pub customer_a: Pubkey,
+    pub customer_b: Pubkey,
+    pub accounts: HashMap<Pubkey, Account>,
+}
+
+impl BoothFixture {
+    pub fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        let admin = Keypair::new();
+        let customer = Keypair::new();
+        let booth = BoothAccounts::new(
+            &program_id,
+            &admin.pubkey(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            0,
+            &spl_token::id(),
+        );
+        let customer_a = Pubkey::new_unique();
+        let customer_b = Pubkey::new_unique();
+
+        let mut accounts = HashMap::new();
+        accounts.insert(admin.pubkey(), system_account());
+        accounts.insert(customer.pubkey(), system_account());
+        accounts.insert(booth.mint_a, mint_account(0));
+        accounts.insert(booth.mint_b, mint_account(0));
+        accounts.insert(customer_a, token_account(&booth.mint_a, &customer.pubkey(), CUSTOMER_BALANCE));
+        accounts.insert(customer_b, token_account(&booth.mint_b, &customer.pubkey(), 0));
+
+        let mut fixture = BoothFixture { program_id, admin, customer, booth, customer_a, customer_b, accounts };
+        let booth = fixture.booth.clone();
+        fixture.insert_booth(&booth, A_TO_B);
+        fixture
+    }
+
+    // another booth of the admin, funded like the first one, selling `mint_a` for a new mint at `a_to_b`
+    pub fn add_booth(&mut self, mint_a: &Pubkey, a_to_b: f64) -> BoothAccounts {
+        let mint_b = Pubkey::new_unique();
+        self.accounts.insert(mint_b, mint_account(0));
+        let booth = BoothAccounts::new(
+            &self.program_id,
+            &self.admin.pubkey(),
+            &Pubkey::new_unique(),
+            mint_a,
+            &mint_b,
+            0,
+            &spl_token::id(),
+        );
+        self.insert_booth(&booth, a_to_b);
+        booth
+    }
+
+    // the booth, its funded vaults, lp mint and an oracle at `a_to_b`
+    fn insert_booth(&mut self, booth: &BoothAccounts, a_to_b: f64) {
+        self.accounts.insert(booth.vault_a, token_account(&booth.mint_a, &booth.vault_a, VAULT_BALANCE));
+        self.accounts.insert(booth.vault_b, token_account(&booth.mint_b, &booth.vault_b, VAULT_BALANCE));
+        self.accounts.insert(booth.lp_mint, mint_account_with_authority(COption::Some(booth.lp_mint), 0));
+
+        let mut oracle_data = vec![0; EXCHANGE_RATE_LEN];
+        ExchangeRate { a_to_b, b_to_a: 1.0 / a_to_b, confidence: 0.0 }
+            .serialize(&mut &mut oracle_data[..])
+            .unwrap();
+        self.accounts.insert(booth.oracle, program_account(&self.program_id, oracle_data));
+
+        let mut data = vec![0; EXCHANGE_BOOTH_LEN];
+        ExchangeBooth {
+            admin: booth.admin,
+            oracle: booth.oracle,
+            vault_a: booth.vault_a,
+            vault_b: booth.vault_b,
+            lp_mint: booth.lp_mint,
+            skew_target_ratio: 1.0,
+            skew_sensitivity: 0.0,
+            oracle_source: OracleSource::Pushed,
+            max_confidence_bps: 0,
+            max_price_age: 0,
+            twap_window: 0,
+        }.serialize(&mut &mut data[..]).unwrap();
+        self.accounts.insert(booth.exchange_booth, program_account(&self.program_id, data));
+    }
+
+    pub fn set_booth(&mut self, exchange_booth: &ExchangeBooth) {
+        let mut data = vec![0; EXCHANGE_BOOTH_LEN];
+        exchange_booth.serialize(&mut &mut data[..]).unwrap();
+        self.accounts.insert(self.booth.exchange_booth, program_account(&self.program_id, data));
+    }
+
+    // moves the oracle account to another address, e.g. a key the test can sign quotes with
+    pub fn set_oracle(&mut self, oracle: &Pubkey) {
+        let account = self.accounts.remove(&self.booth.oracle).unwrap();
+        self.accounts.insert(*oracle, account);
+        self.booth.oracle = *oracle;
+        let mut exchange_booth = ExchangeBooth::try_from_slice(&self.accounts[&self.booth.exchange_booth].data).unwrap();
+        exchange_booth.oracle = *oracle;
+        self.set_booth(&exchange_booth);
+    }
+
+    pub fn set_mint_decimals(&mut self, mint: &Pubkey, decimals: u8) {
+        let account = self.accounts.get_mut(mint).unwrap();
+        let mut mint_state = Mint::unpack(&account.data).unwrap();
+        mint_state.decimals = decimals;
+        mint_state.pack_into_slice(&mut account.data);
+    }
+
+    pub fn set_owner(&mut self, address: &Pubkey, owner: &Pubkey) {
+        self.accounts.get_mut(address).unwrap().owner = *owner;
+    }
+
+    // rewrites the amount of a token account already in the fixture
+    pub fn set_token_balance(&mut self, address: &Pubkey, amount: u64) {
+        let account = self.accounts.get_mut(address).unwrap();
+        let mut token_account = TokenAccount::unpack(&account.data).unwrap();
+        token_account.amount = amount;
+        token_account.pack_into_slice(&mut account.data);
+    }
+
+    // a new token account in the fixture
+    pub fn add_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.accounts.insert(address, token_account(mint, owner, amount));
+        address
+    }
+
+    // customer sells 1 A for B
+    pub fn exchange(&self) -> Instruction {
+        client::exchange(&self.program_id, &self.booth, &self.customer.pubkey(), &self.customer_a, &self.customer_b, 1.0, false)
+    }
+
+    // the program with every fixture account, tests that need other programs add them before starting
+    pub fn program_test(&self) -> ProgramTest {
+        let mut program_test = ProgramTest::new("exchangebooth", self.program_id, processor!(Processor::process_instruction));
+        for (address, account) in self.accounts.iter() {
+            program_test.add_account(*address, account.clone());
+        }
+        program_test
+    }
+
+    pub async fn start(self) -> (ProgramTestContext, Self) {
+        let program_test = self.program_test();
+        self.start_with(program_test).await
+    }
+
+    pub async fn start_with(self, program_test: ProgramTest) -> (ProgramTestContext, Self) {
+        (program_test.start_with_context().await, self)
+    }
+}
+
+pub fn system_account() -> Account {
+    Account { lamports: 10_000_000_000, owner: system_program::id(), ..Account::default() }
+}
+
+pub fn program_account(owner: &Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+pub fn mint_account(supply: u64) -> Account {
+    mint_account_with_authority(COption::None, supply)
+}
+
+// the booth's lp mint is its own authority
+pub fn mint_account_with_authority(mint_authority: COption<Pubkey>, supply: u64) -> Account {
+    let mut data = vec![0; Mint::LEN];
+    Mint {
+        mint_authority,
+        supply,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }.pack_into_slice(&mut data);
+    program_account(&spl_token::id(), data)
+}
+
+pub fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }.pack_into_slice(&mut data);
+    program_account(&spl_token::id(), data)
+}
+
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransportError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    let account = context.banks_client.get_account(*address).await.unwrap().unwrap();
+    TokenAccount::unpack(&account.data).unwrap().amount
+}
+
+pub fn assert_error(result: Result<(), TransportError>, error: ExchangeBoothError) {
+    match result {
+        Err(TransportError::TransactionError(TransactionError::InstructionError(_, InstructionError::Custom(code)))) => {
+            assert_eq!(code, error as u32, "expected {:?}", error)
+        }
+        other => panic!("expected {:?}, got {:?}", error, other),
+    }
+}
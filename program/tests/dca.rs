@@ -0,0 +1,277 @@
+// DCA schedules: ExecuteDca sells one slice of the escrow per interval, CancelDcaSchedule refunds the rest.
+#![cfg(feature = "client")]
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{clock::Clock, instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::Signer;
+
+use exchangebooth::{
+    client,
+    error::ExchangeBoothError,
+    state::{DcaSchedule, DCA_SCHEDULE_LEN},
+};
+
+use common::{assert_error, process, program_account, token_account, token_balance, BoothFixture, CUSTOMER_BALANCE};
+
+const SLICE: u64 = 1_000_000;
+const INTERVAL_SECONDS: i64 = 3_600;
+
+// The customer's schedule selling 1 A per hour, already created with `remaining` slices left in
+// escrow: native program-test can't create the accounts through CreateDcaSchedule's CPIs.
+fn add_schedule(fixture: &mut BoothFixture, next_execution_ts: i64, remaining: u64) -> Pubkey {
+    let owner = fixture.customer.pubkey();
+    let dca_schedule = client::dca_schedule_address(&fixture.program_id, &fixture.booth.exchange_booth, &owner, 0).0;
+    let escrow = client::dca_escrow_address(&fixture.program_id, &dca_schedule).0;
+    let mut data = vec![0; DCA_SCHEDULE_LEN];
+    DcaSchedule {
+        exchange_booth: fixture.booth.exchange_booth,
+        owner,
+        escrow,
+        from_mint: fixture.booth.mint_a,
+        owner_from_token_account: fixture.customer_a,
+        owner_to_token_account: fixture.customer_b,
+        amount: 1.0,
+        interval_seconds: INTERVAL_SECONDS,
+        next_execution_ts,
+        remaining,
+    }.serialize(&mut &mut data[..]).unwrap();
+    fixture.accounts.insert(dca_schedule, program_account(&fixture.program_id, data));
+    fixture.accounts.insert(escrow, token_account(&fixture.booth.mint_a, &escrow, SLICE * remaining));
+    dca_schedule
+}
+
+async fn start(next_execution_ts: i64, remaining: u64) -> (ProgramTestContext, BoothFixture, Pubkey) {
+    let mut fixture = BoothFixture::new();
+    let dca_schedule = add_schedule(&mut fixture, next_execution_ts, remaining);
+    let (context, fixture) = fixture.start().await;
+    (context, fixture, dca_schedule)
+}
+
+fn execute(fixture: &BoothFixture, dca_schedule: &Pubkey) -> Instruction {
+    client::execute_dca(
+        &fixture.program_id,
+        &fixture.booth,
+        dca_schedule,
+        &fixture.customer.pubkey(),
+        &fixture.customer_a,
+        &fixture.customer_b,
+    )
+}
+
+fn cancel(fixture: &BoothFixture, dca_schedule: &Pubkey) -> Instruction {
+    client::cancel_dca_schedule(
+        &fixture.program_id,
+        dca_schedule,
+        &fixture.customer.pubkey(),
+        &fixture.customer_a,
+        &fixture.booth.mint_a,
+        &spl_token::id(),
+    )
+}
+
+// the customer's schedule 0, selling 1 A every `interval_seconds` three times
+fn create(fixture: &BoothFixture, interval_seconds: i64) -> Instruction {
+    client::create_dca_schedule(
+        &fixture.program_id,
+        &fixture.booth,
+        &fixture.customer.pubkey(),
+        &fixture.booth.mint_a,
+        &fixture.customer_a,
+        &fixture.customer_b,
+        0,
+        1.0,
+        interval_seconds,
+        3,
+    )
+}
+
+async fn load_schedule(context: &mut ProgramTestContext, dca_schedule: &Pubkey) -> Option<DcaSchedule> {
+    let account = context.banks_client.get_account(*dca_schedule).await.unwrap()?;
+    Some(DcaSchedule::try_from_slice(&account.data).unwrap())
+}
+
+#[tokio::test]
+async fn due_execution_sells_one_slice_and_waits_an_interval() {
+    let (mut context, fixture, dca_schedule) = start(0, 3).await;
+    process(&mut context, &[execute(&fixture, &dca_schedule)], &[]).await.unwrap();
+    // 1 A at 2 B per A
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_000_000);
+    let escrow = client::dca_escrow_address(&fixture.program_id, &dca_schedule).0;
+    assert_eq!(token_balance(&mut context, &escrow).await, 2 * SLICE);
+
+    let schedule = load_schedule(&mut context, &dca_schedule).await.unwrap();
+    assert_eq!(schedule.remaining, 2);
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_eq!(schedule.next_execution_ts, clock.unix_timestamp + INTERVAL_SECONDS);
+}
+
+#[tokio::test]
+async fn execution_before_the_interval_is_up_fails() {
+    let (mut context, fixture, dca_schedule) = start(0, 3).await;
+    // the second execution in the same transaction comes an interval too early
+    let result = process(&mut context, &[execute(&fixture, &dca_schedule), execute(&fixture, &dca_schedule)], &[]).await;
+    assert_error(result, ExchangeBoothError::DcaNotDue);
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 0);
+}
+
+#[tokio::test]
+async fn execution_before_the_first_due_time_fails() {
+    let (mut context, fixture, dca_schedule) = start(i64::MAX, 3).await;
+    let result = process(&mut context, &[execute(&fixture, &dca_schedule)], &[]).await;
+    assert_error(result, ExchangeBoothError::DcaNotDue);
+}
+
+#[tokio::test]
+async fn last_execution_closes_the_schedule() {
+    let (mut context, fixture, dca_schedule) = start(0, 1).await;
+    process(&mut context, &[execute(&fixture, &dca_schedule)], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_000_000);
+    assert!(load_schedule(&mut context, &dca_schedule).await.is_none());
+}
+
+#[tokio::test]
+async fn execution_rejects_another_payout_account() {
+    let (mut context, fixture, dca_schedule) = start(0, 3).await;
+    let mut execute = execute(&fixture, &dca_schedule);
+    // owner_to_token_account
+    execute.accounts[10].pubkey = fixture.booth.vault_b;
+    let result = process(&mut context, &[execute], &[]).await;
+    assert_error(result, ExchangeBoothError::DcaScheduleMismatch);
+}
+
+#[tokio::test]
+async fn cancel_refunds_the_remaining_slices() {
+    let (mut context, fixture, dca_schedule) = start(i64::MAX, 3).await;
+    process(&mut context, &[cancel(&fixture, &dca_schedule)], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE + 3 * SLICE);
+    assert!(load_schedule(&mut context, &dca_schedule).await.is_none());
+}
+
+#[tokio::test]
+async fn create_rejects_a_zero_interval() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let create = create(&fixture, 0);
+    let result = process(&mut context, &[create], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn create_rejects_booth_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[create(&fixture, INTERVAL_SECONDS)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn create_rejects_fake_programs_and_sysvar() {
+    for (index, error) in [
+        (9, ExchangeBoothError::InvalidSystemProgram),
+        (10, ExchangeBoothError::InvalidTokenProgram),
+        (11, ExchangeBoothError::InvalidRentSysvar),
+    ] {
+        let (mut context, fixture) = BoothFixture::new().start().await;
+        let mut create = create(&fixture, INTERVAL_SECONDS);
+        create.accounts[index].pubkey = Pubkey::new_unique();
+        let result = process(&mut context, &[create], &[&fixture.customer]).await;
+        assert_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn create_rejects_a_schedule_address_for_another_id() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let mut create = create(&fixture, INTERVAL_SECONDS);
+    let other_schedule = client::dca_schedule_address(&fixture.program_id, &fixture.booth.exchange_booth, &fixture.customer.pubkey(), 1).0;
+    create.accounts[3].pubkey = other_schedule;
+    let result = process(&mut context, &[create], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn execution_rejects_a_schedule_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let dca_schedule = add_schedule(&mut fixture, 0, 3);
+    fixture.set_owner(&dca_schedule, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[execute(&fixture, &dca_schedule)], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn execution_rejects_booth_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let dca_schedule = add_schedule(&mut fixture, 0, 3);
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[execute(&fixture, &dca_schedule)], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn execution_rejects_fake_token_program() {
+    let (mut context, fixture, dca_schedule) = start(0, 3).await;
+    let mut execute = execute(&fixture, &dca_schedule);
+    execute.accounts[12].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[execute], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn execution_rejects_another_escrow() {
+    let (mut context, fixture, dca_schedule) = start(0, 3).await;
+    let mut execute = execute(&fixture, &dca_schedule);
+    execute.accounts[7].pubkey = fixture.booth.vault_a;
+    let result = process(&mut context, &[execute], &[]).await;
+    assert_error(result, ExchangeBoothError::DcaScheduleMismatch);
+}
+
+#[tokio::test]
+async fn cancel_rejects_a_schedule_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let dca_schedule = add_schedule(&mut fixture, i64::MAX, 3);
+    fixture.set_owner(&dca_schedule, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[cancel(&fixture, &dca_schedule)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn cancel_rejects_fake_token_program() {
+    let (mut context, fixture, dca_schedule) = start(i64::MAX, 3).await;
+    let mut cancel = cancel(&fixture, &dca_schedule);
+    cancel.accounts[5].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[cancel], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn cancel_rejects_another_escrow() {
+    let (mut context, fixture, dca_schedule) = start(i64::MAX, 3).await;
+    let mut cancel = cancel(&fixture, &dca_schedule);
+    cancel.accounts[1].pubkey = fixture.booth.vault_a;
+    let result = process(&mut context, &[cancel], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::DcaScheduleMismatch);
+}
+
+// CreateDcaSchedule creates the schedule and its escrow through CPI, which only the BPF build
+// can do in solana-program-test 1.9
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn created_schedule_escrows_every_slice_and_is_due_at_once() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let owner = fixture.customer.pubkey();
+    let create = create(&fixture, INTERVAL_SECONDS);
+    let dca_schedule = client::dca_schedule_address(&fixture.program_id, &fixture.booth.exchange_booth, &owner, 0).0;
+    process(&mut context, &[create, execute(&fixture, &dca_schedule)], &[&fixture.customer]).await.unwrap();
+    let escrow = client::dca_escrow_address(&fixture.program_id, &dca_schedule).0;
+    assert_eq!(token_balance(&mut context, &escrow).await, 2 * SLICE);
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE - 3 * SLICE);
+    assert_eq!(load_schedule(&mut context, &dca_schedule).await.unwrap().remaining, 2);
+}
@@ -0,0 +1,194 @@
+// ExchangeRoute from A to C through the fixture's A/B booth and a B/C booth.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+use exchangebooth::{
+    client::{self, BoothAccounts},
+    error::ExchangeBoothError,
+};
+
+use common::{assert_error, process, token_balance, BoothFixture, CUSTOMER_BALANCE, VAULT_BALANCE};
+
+// B/C trades at 0.3 C per B and C has 9 decimals, where scaling base units straight across
+// rounds differently from Exchange
+const B_TO_C: f64 = 0.3;
+
+struct Route {
+    fixture: BoothFixture,
+    booth_c: BoothAccounts,
+    customer_c: Pubkey,
+}
+
+fn route_fixture() -> Route {
+    let mut fixture = BoothFixture::new();
+    let mint_b = fixture.booth.mint_b;
+    let booth_c = fixture.add_booth(&mint_b, B_TO_C);
+    fixture.set_mint_decimals(&booth_c.mint_b, 9);
+    fixture.set_token_balance(&booth_c.vault_b, 1_000 * 1_000_000_000);
+    let customer = fixture.customer.pubkey();
+    let customer_c = fixture.add_token_account(&booth_c.mint_b, &customer, 0);
+    Route { fixture, booth_c, customer_c }
+}
+
+fn exchange_route(route: &Route, next_booths: &[BoothAccounts], amount: f64, min_amount_out: f64) -> Instruction {
+    let fixture = &route.fixture;
+    client::exchange_route(
+        &fixture.program_id,
+        &fixture.booth,
+        next_booths,
+        &fixture.customer.pubkey(),
+        &fixture.customer_a,
+        &route.customer_c,
+        amount,
+        min_amount_out,
+    )
+}
+
+#[tokio::test]
+async fn route_chains_both_booths() {
+    let route = route_fixture();
+    let instruction = exchange_route(&route, &[route.booth_c.clone()], 1.5, 0.8);
+    let (mut context, fixture) = route.fixture.start().await;
+    process(&mut context, &[instruction], &[&fixture.customer]).await.unwrap();
+    // 1.5 A is 3 B at the first booth, which the second booth takes for 0.9 C less the rounding
+    assert_eq!(token_balance(&mut context, &route.customer_c).await, 899_999_999);
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE - 1_500_000);
+    // the B never leaves the booths
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 0);
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_b).await, VAULT_BALANCE - 3_000_000);
+    assert_eq!(token_balance(&mut context, &route.booth_c.vault_a).await, VAULT_BALANCE + 3_000_000);
+}
+
+#[tokio::test]
+async fn hop_pays_what_exchange_pays() {
+    // the 3 B of the route's first hop, sold to the B/C booth with a plain Exchange
+    let mut route = route_fixture();
+    let customer_b = route.fixture.customer_b;
+    route.fixture.set_token_balance(&customer_b, 3_000_000);
+    let exchange = client::exchange(
+        &route.fixture.program_id,
+        &route.booth_c,
+        &route.fixture.customer.pubkey(),
+        &customer_b,
+        &route.customer_c,
+        3.0,
+        false,
+    );
+    let (mut context, fixture) = route.fixture.start().await;
+    process(&mut context, &[exchange], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &route.customer_c).await, 899_999_999);
+}
+
+#[tokio::test]
+async fn route_rejects_less_than_the_minimum() {
+    let route = route_fixture();
+    let instruction = exchange_route(&route, &[route.booth_c.clone()], 1.5, 0.9);
+    let (mut context, fixture) = route.fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::SlippageExceeded);
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE);
+}
+
+#[tokio::test]
+async fn route_rejects_a_booth_twice() {
+    // A to B, back to A and to B again, all through the fixture booth
+    let mut route = route_fixture();
+    let (customer, mint_b) = (route.fixture.customer.pubkey(), route.fixture.booth.mint_b);
+    let customer_b = route.fixture.add_token_account(&mint_b, &customer, 0);
+    let booth = route.fixture.booth.clone();
+    let instruction = client::exchange_route(
+        &route.fixture.program_id,
+        &booth,
+        &[booth.clone(), booth.clone()],
+        &customer,
+        &route.fixture.customer_a,
+        &customer_b,
+        1.0,
+        0.0,
+    );
+    let (mut context, fixture) = route.fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn route_rejects_a_booth_off_the_path() {
+    // the booths in reverse, so A goes first to the booth that trades B and C
+    let route = route_fixture();
+    let fixture = &route.fixture;
+    let instruction = client::exchange_route(
+        &fixture.program_id,
+        &route.booth_c,
+        &[fixture.booth.clone()],
+        &fixture.customer.pubkey(),
+        &fixture.customer_a,
+        &route.customer_c,
+        1.5,
+        0.0,
+    );
+    let (mut context, fixture) = route.fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountData);
+}
+
+// accounts of the second hop start after the customer's four and the first hop's six
+const SECOND_HOP: usize = 10;
+
+#[tokio::test]
+async fn route_rejects_a_booth_owned_by_another_program() {
+    let mut route = route_fixture();
+    let booth_c = route.booth_c.exchange_booth;
+    route.fixture.set_owner(&booth_c, &Pubkey::new_unique());
+    let instruction = exchange_route(&route, &[route.booth_c.clone()], 1.5, 0.8);
+    let (mut context, fixture) = route.fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn route_rejects_an_oracle_owned_by_another_program() {
+    let mut route = route_fixture();
+    let oracle_c = route.booth_c.oracle;
+    route.fixture.set_owner(&oracle_c, &Pubkey::new_unique());
+    let instruction = exchange_route(&route, &[route.booth_c.clone()], 1.5, 0.8);
+    let (mut context, fixture) = route.fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn route_rejects_fake_token_program() {
+    let route = route_fixture();
+    let mut instruction = exchange_route(&route, &[route.booth_c.clone()], 1.5, 0.8);
+    instruction.accounts[3].pubkey = Pubkey::new_unique();
+    let (mut context, fixture) = route.fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn route_rejects_another_booths_oracle() {
+    let route = route_fixture();
+    let mut instruction = exchange_route(&route, &[route.booth_c.clone()], 1.5, 0.8);
+    // the A/B oracle quoting the B/C hop
+    instruction.accounts[SECOND_HOP + 1].pubkey = route.fixture.booth.oracle;
+    let (mut context, fixture) = route.fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn route_rejects_another_booths_vault() {
+    let route = route_fixture();
+    let mut instruction = exchange_route(&route, &[route.booth_c.clone()], 1.5, 0.8);
+    // the A/B booth's B vault in place of the B/C booth's
+    instruction.accounts[SECOND_HOP + 2].pubkey = route.fixture.booth.vault_b;
+    let (mut context, fixture) = route.fixture.start().await;
+    let result = process(&mut context, &[instruction], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
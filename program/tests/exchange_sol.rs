@@ -0,0 +1,156 @@
+// ExchangeSol on a SOL/B booth: the customer pays or receives lamports, wrapped and unwrapped
+// through a temporary wSOL account.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::{instruction::Instruction, program_option::COption, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+use spl_token::state::{Account as TokenAccount, Mint};
+
+use exchangebooth::{
+    client::{self, BoothAccounts},
+    error::ExchangeBoothError,
+};
+
+use common::{assert_error, mint_account, process, token_account, BoothFixture};
+
+// 1 SOL is worth 20 B
+const SOL_TO_B: f64 = 20.0;
+const SOL_VAULT_BALANCE: u64 = 100_000_000_000;
+
+struct SolBooth {
+    fixture: BoothFixture,
+    booth: BoothAccounts,
+    // the customer's B
+    customer_token: Pubkey,
+}
+
+// A booth selling SOL for a new mint B, with a native vault holding SOL_VAULT_BALANCE lamports
+// on top of its rent. The customer holds 100 B.
+fn sol_booth() -> SolBooth {
+    let mut fixture = BoothFixture::new();
+    let native_mint = spl_token::native_mint::id();
+    let mut mint = mint_account(0);
+    let mut mint_state = Mint::unpack(&mint.data).unwrap();
+    mint_state.decimals = spl_token::native_mint::DECIMALS;
+    mint_state.pack_into_slice(&mut mint.data);
+    fixture.accounts.insert(native_mint, mint);
+
+    let booth = fixture.add_booth(&native_mint, SOL_TO_B);
+    let rent = Rent::default().minimum_balance(TokenAccount::LEN);
+    let mut vault = token_account(&native_mint, &booth.vault_a, SOL_VAULT_BALANCE);
+    let mut vault_state = TokenAccount::unpack(&vault.data).unwrap();
+    vault_state.is_native = COption::Some(rent);
+    vault_state.pack_into_slice(&mut vault.data);
+    vault.lamports = rent + SOL_VAULT_BALANCE;
+    fixture.accounts.insert(booth.vault_a, vault);
+
+    let customer = fixture.customer.pubkey();
+    let customer_token = fixture.add_token_account(&booth.mint_b, &customer, 100_000_000);
+    SolBooth { fixture, booth, customer_token }
+}
+
+fn sell_one_sol(sol_booth: &SolBooth) -> Instruction {
+    let fixture = &sol_booth.fixture;
+    client::exchange_sol(&fixture.program_id, &sol_booth.booth, &fixture.customer.pubkey(), &sol_booth.customer_token, 1.0, true)
+}
+
+#[tokio::test]
+async fn exchange_sol_rejects_a_booth_without_wrapped_sol() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let exchange = client::exchange_sol(&fixture.program_id, &fixture.booth, &fixture.customer.pubkey(), &fixture.customer_b, 1.0, true);
+    let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn exchange_sol_rejects_another_wrapped_account() {
+    let sol_booth = sol_booth();
+    let mut exchange = sell_one_sol(&sol_booth);
+    // wsol_account
+    exchange.accounts[8].pubkey = Pubkey::new_unique();
+    let (mut context, fixture) = sol_booth.fixture.start().await;
+    let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn exchange_sol_rejects_booth_owned_by_another_program() {
+    let mut sol_booth = sol_booth();
+    let exchange = sell_one_sol(&sol_booth);
+    sol_booth.fixture.set_owner(&sol_booth.booth.exchange_booth, &Pubkey::new_unique());
+    let (mut context, fixture) = sol_booth.fixture.start().await;
+    let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn exchange_sol_rejects_mint_owned_by_another_program() {
+    let mut sol_booth = sol_booth();
+    let exchange = sell_one_sol(&sol_booth);
+    sol_booth.fixture.set_owner(&sol_booth.booth.mint_b, &Pubkey::new_unique());
+    let (mut context, fixture) = sol_booth.fixture.start().await;
+    let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn exchange_sol_rejects_fake_programs_and_sysvar() {
+    for (index, error) in [
+        (9, ExchangeBoothError::InvalidSystemProgram),
+        (10, ExchangeBoothError::InvalidTokenProgram),
+        (11, ExchangeBoothError::InvalidRentSysvar),
+    ] {
+        let sol_booth = sol_booth();
+        let mut exchange = sell_one_sol(&sol_booth);
+        exchange.accounts[index].pubkey = Pubkey::new_unique();
+        let (mut context, fixture) = sol_booth.fixture.start().await;
+        let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+        assert_error(result, error);
+    }
+}
+
+#[cfg(feature = "test-bpf")]
+async fn lamports(context: &mut solana_program_test::ProgramTestContext, address: &Pubkey) -> u64 {
+    context.banks_client.get_account(*address).await.unwrap().unwrap().lamports
+}
+
+// ExchangeSol creates the wrapped account through CPI, which only the BPF build can do in
+// solana-program-test 1.9
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn selling_sol_wraps_the_customers_lamports() {
+    use common::token_balance;
+
+    let sol_booth = sol_booth();
+    let customer = sol_booth.fixture.customer.pubkey();
+    let sell = client::exchange_sol(&sol_booth.fixture.program_id, &sol_booth.booth, &customer, &sol_booth.customer_token, 0.5, true);
+    let (mut context, fixture) = sol_booth.fixture.start().await;
+    let before = lamports(&mut context, &customer).await;
+    // the payer is someone else, so the customer's lamports only move for the trade
+    process(&mut context, &[sell], &[&fixture.customer]).await.unwrap();
+    assert_eq!(lamports(&mut context, &customer).await, before - 500_000_000);
+    assert_eq!(token_balance(&mut context, &sol_booth.customer_token).await, 110_000_000);
+    assert_eq!(token_balance(&mut context, &sol_booth.booth.vault_a).await, SOL_VAULT_BALANCE + 500_000_000);
+    let wsol = client::wsol_address(&fixture.program_id, &sol_booth.booth.exchange_booth, &customer).0;
+    assert!(context.banks_client.get_account(wsol).await.unwrap().is_none());
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn buying_sol_unwraps_to_the_customer() {
+    use common::{token_balance, VAULT_BALANCE};
+
+    let sol_booth = sol_booth();
+    let customer = sol_booth.fixture.customer.pubkey();
+    let buy = client::exchange_sol(&sol_booth.fixture.program_id, &sol_booth.booth, &customer, &sol_booth.customer_token, 10.0, false);
+    let (mut context, fixture) = sol_booth.fixture.start().await;
+    let before = lamports(&mut context, &customer).await;
+    process(&mut context, &[buy], &[&fixture.customer]).await.unwrap();
+    // 10 B is 0.5 SOL
+    assert_eq!(lamports(&mut context, &customer).await, before + 500_000_000);
+    assert_eq!(token_balance(&mut context, &sol_booth.customer_token).await, 90_000_000);
+    assert_eq!(token_balance(&mut context, &sol_booth.booth.vault_b).await, VAULT_BALANCE + 10_000_000);
+}
@@ -0,0 +1,162 @@
+// FlashLoan against a booth vault, with borrower programs that do and don't pay the fee.
+#![cfg(feature = "client")]
+
+mod common;
+
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+use solana_program_test::{processor, tokio, ProgramTestContext};
+
+use exchangebooth::{client, error::ExchangeBoothError, instruction::FlashLoanReceiverInstruction};
+
+use common::{assert_error, process, token_balance, BoothFixture, VAULT_BALANCE};
+
+const BORROWER_BALANCE: u64 = 1_000_000;
+
+// Pays back the loan and the fee out of its token account, which its PDA of ["borrower"] owns.
+// Accounts: borrower token account, vault, PDA, token program.
+fn repaying_borrower(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let loan = FlashLoanReceiverInstruction::try_from_slice(data)?;
+    repay(program_id, accounts, loan.amount + loan.fee)
+}
+
+// pays back exactly what it borrowed
+fn fee_dodging_borrower(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let loan = FlashLoanReceiverInstruction::try_from_slice(data)?;
+    repay(program_id, accounts, loan.amount)
+}
+
+fn repay(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let (borrower_token, vault, authority, token_program) = (&accounts[0], &accounts[1], &accounts[2], &accounts[3]);
+    let (_, bump_seed) = Pubkey::find_program_address(&[b"borrower"], program_id);
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, borrower_token.key, vault.key, authority.key, &[], amount)?,
+        &[borrower_token.clone(), vault.clone(), authority.clone(), token_program.clone()],
+        &[&[b"borrower", &[bump_seed]]],
+    )
+}
+
+struct Borrower {
+    program_id: Pubkey,
+    token_account: Pubkey,
+    authority: Pubkey,
+}
+
+async fn start(pays_fee: bool) -> (ProgramTestContext, BoothFixture, Borrower) {
+    start_tampered(pays_fee, |_| {}).await
+}
+
+// like start, with `tamper` applied to the booth accounts first
+async fn start_tampered(pays_fee: bool, tamper: impl FnOnce(&mut BoothFixture)) -> (ProgramTestContext, BoothFixture, Borrower) {
+    let mut fixture = BoothFixture::new();
+    tamper(&mut fixture);
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::find_program_address(&[b"borrower"], &program_id).0;
+    let mint_a = fixture.booth.mint_a;
+    let token_account = fixture.add_token_account(&mint_a, &authority, BORROWER_BALANCE);
+    let mut program_test = fixture.program_test();
+    if pays_fee {
+        program_test.add_program("repaying_borrower", program_id, processor!(repaying_borrower));
+    } else {
+        program_test.add_program("fee_dodging_borrower", program_id, processor!(fee_dodging_borrower));
+    }
+    let (context, fixture) = fixture.start_with(program_test).await;
+    (context, fixture, Borrower { program_id, token_account, authority })
+}
+
+fn flash_loan(fixture: &BoothFixture, borrower: &Borrower, amount: f64) -> Instruction {
+    client::flash_loan(
+        &fixture.program_id,
+        &fixture.booth,
+        &fixture.booth.mint_a,
+        &borrower.token_account,
+        &borrower.program_id,
+        &[
+            AccountMeta::new(borrower.token_account, false),
+            AccountMeta::new(fixture.booth.vault_a, false),
+            AccountMeta::new_readonly(borrower.authority, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        amount,
+    )
+}
+
+#[tokio::test]
+async fn flash_loan_repaid_with_fee() {
+    let (mut context, fixture, borrower) = start(true).await;
+    process(&mut context, &[flash_loan(&fixture, &borrower, 100.0)], &[]).await.unwrap();
+    // 9 bps of 100 A
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_a).await, VAULT_BALANCE + 90_000);
+    assert_eq!(token_balance(&mut context, &borrower.token_account).await, BORROWER_BALANCE - 90_000);
+}
+
+#[tokio::test]
+async fn flash_loan_fee_rounds_up() {
+    let (mut context, fixture, borrower) = start(true).await;
+    // 9 bps of 1 base unit is still 1
+    process(&mut context, &[flash_loan(&fixture, &borrower, 0.000_001)], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_a).await, VAULT_BALANCE + 1);
+}
+
+#[tokio::test]
+async fn flash_loan_fails_without_the_fee() {
+    let (mut context, fixture, borrower) = start(false).await;
+    let result = process(&mut context, &[flash_loan(&fixture, &borrower, 100.0)], &[]).await;
+    assert_error(result, ExchangeBoothError::FlashLoanNotRepaid);
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_a).await, VAULT_BALANCE);
+}
+
+#[tokio::test]
+async fn flash_loan_rejects_more_than_the_vault() {
+    let (mut context, fixture, borrower) = start(true).await;
+    let result = process(&mut context, &[flash_loan(&fixture, &borrower, 1_001.0)], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn flash_loan_rejects_booth_owned_by_another_program() {
+    let (mut context, fixture, borrower) = start_tampered(true, |fixture| {
+        let booth = fixture.booth.exchange_booth;
+        fixture.set_owner(&booth, &Pubkey::new_unique());
+    }).await;
+    let result = process(&mut context, &[flash_loan(&fixture, &borrower, 100.0)], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn flash_loan_rejects_fake_token_program() {
+    let (mut context, fixture, borrower) = start(true).await;
+    let mut instruction = flash_loan(&fixture, &borrower, 100.0);
+    instruction.accounts[5].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[instruction], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn flash_loan_rejects_vault_owned_by_another_program() {
+    let (mut context, fixture, borrower) = start_tampered(true, |fixture| {
+        let vault = fixture.booth.vault_a;
+        fixture.set_owner(&vault, &Pubkey::new_unique());
+    }).await;
+    let result = process(&mut context, &[flash_loan(&fixture, &borrower, 100.0)], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn flash_loan_rejects_another_booths_vault() {
+    let mut other_vault = Pubkey::default();
+    let (mut context, fixture, borrower) = start_tampered(true, |fixture| {
+        let mint_a = fixture.booth.mint_a;
+        other_vault = fixture.add_booth(&mint_a, 3.0).vault_a;
+    }).await;
+    let mut instruction = flash_loan(&fixture, &borrower, 100.0);
+    instruction.accounts[1].pubkey = other_vault;
+    let result = process(&mut context, &[instruction], &[]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
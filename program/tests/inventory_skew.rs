@@ -0,0 +1,71 @@
+// SetInventorySkew and the rates Exchange gives once the vaults are off their target ratio.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+use exchangebooth::{client, error::ExchangeBoothError};
+
+use common::{assert_error, process, token_balance, BoothFixture, CUSTOMER_BALANCE};
+
+
+// The fixture vaults hold 1000 A and 1000 B at 2 B per A, so A is worth 2000 B against a
+// target of 1000 B: imbalance (2000 - 1000) / (2000 + 1000) = 1/3, skew 0.5 * 1/3 = 1/6.
+
+#[tokio::test]
+async fn selling_the_long_side_gets_a_worse_rate() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let set_skew = client::set_inventory_skew(&fixture.program_id, &fixture.booth, 1.0, 0.5);
+    process(&mut context, &[set_skew, fixture.exchange()], &[&fixture.admin, &fixture.customer]).await.unwrap();
+    // 2 * (1 - 1/6) = 1.666666.. B for 1 A
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 1_666_666);
+}
+
+#[tokio::test]
+async fn buying_the_long_side_gets_a_better_rate() {
+    let mut fixture = BoothFixture::new();
+    let customer_b = fixture.customer_b;
+    fixture.set_token_balance(&customer_b, CUSTOMER_BALANCE);
+    let (mut context, fixture) = fixture.start().await;
+    let set_skew = client::set_inventory_skew(&fixture.program_id, &fixture.booth, 1.0, 0.5);
+    let sell_b = client::exchange(
+        &fixture.program_id,
+        &fixture.booth,
+        &fixture.customer.pubkey(),
+        &fixture.customer_b,
+        &fixture.customer_a,
+        1.0,
+        false,
+    );
+    process(&mut context, &[set_skew, sell_b], &[&fixture.admin, &fixture.customer]).await.unwrap();
+    // 0.5 * (1 + 1/6) = 0.583333.. A for 1 B
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE + 583_333);
+}
+
+#[tokio::test]
+async fn balanced_vaults_trade_at_the_oracle_rate() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    // 1000 A at 2 B per A against 1000 B at a target ratio of 2 is exactly on target
+    let set_skew = client::set_inventory_skew(&fixture.program_id, &fixture.booth, 2.0, 0.5);
+    process(&mut context, &[set_skew, fixture.exchange()], &[&fixture.admin, &fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_000_000);
+}
+
+#[tokio::test]
+async fn set_inventory_skew_rejects_other_signers() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let mut set_skew = client::set_inventory_skew(&fixture.program_id, &fixture.booth, 1.0, 0.5);
+    set_skew.accounts[1].pubkey = fixture.customer.pubkey();
+    let result = process(&mut context, &[set_skew], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::IncorrectAdmin);
+}
+
+#[tokio::test]
+async fn set_inventory_skew_rejects_sensitivity_of_one() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let set_skew = client::set_inventory_skew(&fixture.program_id, &fixture.booth, 1.0, 1.0);
+    let result = process(&mut context, &[set_skew], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
@@ -0,0 +1,281 @@
+// Limit orders resting on a booth: filled by a cranker once the booth rate reaches them, or cancelled.
+#![cfg(feature = "client")]
+
+mod common;
+
+use borsh::BorshSerialize;
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+use exchangebooth::{
+    client::{self, LimitOrderAccounts},
+    error::ExchangeBoothError,
+    state::{LimitOrder, LIMIT_ORDER_LEN},
+};
+
+use common::{
+    assert_error, process, program_account, system_account, token_account, token_balance, BoothFixture,
+    CUSTOMER_BALANCE, VAULT_BALANCE,
+};
+
+const ORDER_AMOUNT: u64 = 10_000_000;
+const TIP_LAMPORTS: u64 = 5_000;
+
+// The customer's order to sell 10 A, already placed: the order account and its escrow are in the
+// genesis accounts, since native program-test can't create them through PlaceLimitOrder's CPIs.
+fn add_order(fixture: &mut BoothFixture, min_rate: f64) -> LimitOrderAccounts {
+    let owner = fixture.customer.pubkey();
+    let limit_order = client::limit_order_address(&fixture.program_id, &fixture.booth.exchange_booth, &owner, 0).0;
+    let escrow = client::limit_order_escrow_address(&fixture.program_id, &limit_order).0;
+    let mut data = vec![0; LIMIT_ORDER_LEN];
+    LimitOrder {
+        exchange_booth: fixture.booth.exchange_booth,
+        owner,
+        escrow,
+        from_mint: fixture.booth.mint_a,
+        owner_from_token_account: fixture.customer_a,
+        owner_to_token_account: fixture.customer_b,
+        amount: 10.0,
+        min_rate,
+        tip_lamports: TIP_LAMPORTS,
+    }.serialize(&mut &mut data[..]).unwrap();
+    let mut order_account = program_account(&fixture.program_id, data);
+    order_account.lamports += TIP_LAMPORTS;
+    fixture.accounts.insert(limit_order, order_account);
+    fixture.accounts.insert(escrow, token_account(&fixture.booth.mint_a, &escrow, ORDER_AMOUNT));
+    LimitOrderAccounts {
+        limit_order,
+        owner,
+        owner_from_token_account: fixture.customer_a,
+        owner_to_token_account: fixture.customer_b,
+    }
+}
+
+async fn start(min_rate: f64) -> (ProgramTestContext, BoothFixture, LimitOrderAccounts, Keypair) {
+    let mut fixture = BoothFixture::new();
+    let order = add_order(&mut fixture, min_rate);
+    let cranker = Keypair::new();
+    fixture.accounts.insert(cranker.pubkey(), system_account());
+    let (context, fixture) = fixture.start().await;
+    (context, fixture, order, cranker)
+}
+
+// the customer's order 0 to sell 10 A, at no less than `min_rate`
+fn place(fixture: &BoothFixture, min_rate: f64) -> Instruction {
+    client::place_limit_order(
+        &fixture.program_id,
+        &fixture.booth,
+        &fixture.customer.pubkey(),
+        &fixture.booth.mint_a,
+        &fixture.customer_a,
+        &fixture.customer_b,
+        0,
+        10.0,
+        min_rate,
+        TIP_LAMPORTS,
+    )
+}
+
+fn cancel(fixture: &BoothFixture, order: &LimitOrderAccounts) -> Instruction {
+    client::cancel_limit_order(
+        &fixture.program_id,
+        &order.limit_order,
+        &order.owner,
+        &order.owner_from_token_account,
+        &fixture.booth.mint_a,
+        &spl_token::id(),
+    )
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> Option<u64> {
+    context.banks_client.get_account(*address).await.unwrap().map(|account| account.lamports)
+}
+
+#[tokio::test]
+async fn fill_pays_the_owner_and_tips_the_cranker() {
+    let (mut context, fixture, order, cranker) = start(1.5).await;
+    let fill = client::fill_limit_orders(&fixture.program_id, &fixture.booth, &cranker.pubkey(), &[order.clone()]);
+    process(&mut context, &[fill], &[&cranker]).await.unwrap();
+    // 10 A at 2 B per A
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 20_000_000);
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_a).await, VAULT_BALANCE + ORDER_AMOUNT);
+    assert_eq!(lamports(&mut context, &cranker.pubkey()).await, Some(system_account().lamports + TIP_LAMPORTS));
+    // rent of the order and the escrow goes back to the owner
+    let rent = Rent::default().minimum_balance(LIMIT_ORDER_LEN) + Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    assert_eq!(lamports(&mut context, &fixture.customer.pubkey()).await, Some(system_account().lamports + rent));
+    assert_eq!(lamports(&mut context, &order.limit_order).await, None);
+}
+
+#[tokio::test]
+async fn fill_leaves_orders_below_the_booth_rate() {
+    let (mut context, fixture, order, cranker) = start(2.5).await;
+    let fill = client::fill_limit_orders(&fixture.program_id, &fixture.booth, &cranker.pubkey(), &[order.clone()]);
+    process(&mut context, &[fill], &[&cranker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 0);
+    let escrow = client::limit_order_escrow_address(&fixture.program_id, &order.limit_order).0;
+    assert_eq!(token_balance(&mut context, &escrow).await, ORDER_AMOUNT);
+    assert_eq!(lamports(&mut context, &cranker.pubkey()).await, Some(system_account().lamports));
+}
+
+#[tokio::test]
+async fn fill_rejects_another_owners_payout_account() {
+    let (mut context, fixture, order, cranker) = start(1.5).await;
+    let redirected = LimitOrderAccounts { owner_to_token_account: fixture.booth.vault_b, ..order };
+    let fill = client::fill_limit_orders(&fixture.program_id, &fixture.booth, &cranker.pubkey(), &[redirected]);
+    let result = process(&mut context, &[fill], &[&cranker]).await;
+    assert_error(result, ExchangeBoothError::LimitOrderMismatch);
+}
+
+#[tokio::test]
+async fn cancel_refunds_the_escrow() {
+    let (mut context, fixture, order, _) = start(1.5).await;
+    process(&mut context, &[cancel(&fixture, &order)], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE + ORDER_AMOUNT);
+    assert_eq!(lamports(&mut context, &order.limit_order).await, None);
+}
+
+#[tokio::test]
+async fn cancel_rejects_another_signer() {
+    let (mut context, fixture, order, _) = start(1.5).await;
+    let cancel = client::cancel_limit_order(
+        &fixture.program_id,
+        &order.limit_order,
+        &fixture.admin.pubkey(),
+        &order.owner_from_token_account,
+        &fixture.booth.mint_a,
+        &spl_token::id(),
+    );
+    let result = process(&mut context, &[cancel], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::LimitOrderMismatch);
+}
+
+#[tokio::test]
+async fn place_rejects_a_rate_of_zero() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let place = place(&fixture, 0.0);
+    let result = process(&mut context, &[place], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn place_rejects_booth_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[place(&fixture, 1.5)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn place_rejects_fake_programs_and_sysvar() {
+    for (index, error) in [
+        (9, ExchangeBoothError::InvalidSystemProgram),
+        (10, ExchangeBoothError::InvalidTokenProgram),
+        (11, ExchangeBoothError::InvalidRentSysvar),
+    ] {
+        let (mut context, fixture) = BoothFixture::new().start().await;
+        let mut place = place(&fixture, 1.5);
+        place.accounts[index].pubkey = Pubkey::new_unique();
+        let result = process(&mut context, &[place], &[&fixture.customer]).await;
+        assert_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn place_rejects_an_order_address_for_another_id() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let mut place = place(&fixture, 1.5);
+    let other_order = client::limit_order_address(&fixture.program_id, &fixture.booth.exchange_booth, &fixture.customer.pubkey(), 1).0;
+    place.accounts[3].pubkey = other_order;
+    let result = process(&mut context, &[place], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn fill_rejects_an_order_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let order = add_order(&mut fixture, 1.5);
+    fixture.set_owner(&order.limit_order, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let fill = client::fill_limit_orders(&fixture.program_id, &fixture.booth, &fixture.customer.pubkey(), &[order]);
+    let result = process(&mut context, &[fill], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn fill_rejects_booth_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let order = add_order(&mut fixture, 1.5);
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let fill = client::fill_limit_orders(&fixture.program_id, &fixture.booth, &fixture.customer.pubkey(), &[order]);
+    let result = process(&mut context, &[fill], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn fill_rejects_fake_token_program() {
+    let (mut context, fixture, order, cranker) = start(1.5).await;
+    let mut fill = client::fill_limit_orders(&fixture.program_id, &fixture.booth, &cranker.pubkey(), &[order]);
+    fill.accounts[8].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[fill], &[&cranker]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn fill_rejects_another_escrow() {
+    let (mut context, fixture, order, cranker) = start(1.5).await;
+    let mut fill = client::fill_limit_orders(&fixture.program_id, &fixture.booth, &cranker.pubkey(), &[order]);
+    // a token account of the same mint, but not the order's escrow PDA
+    fill.accounts[10].pubkey = fixture.customer_a;
+    let result = process(&mut context, &[fill], &[&cranker]).await;
+    assert_error(result, ExchangeBoothError::LimitOrderMismatch);
+}
+
+#[tokio::test]
+async fn cancel_rejects_an_order_owned_by_another_program() {
+    let mut fixture = BoothFixture::new();
+    let order = add_order(&mut fixture, 1.5);
+    fixture.set_owner(&order.limit_order, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let result = process(&mut context, &[cancel(&fixture, &order)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn cancel_rejects_fake_token_program() {
+    let (mut context, fixture, order, _) = start(1.5).await;
+    let mut cancel = cancel(&fixture, &order);
+    cancel.accounts[5].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[cancel], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn cancel_rejects_another_escrow() {
+    let (mut context, fixture, order, _) = start(1.5).await;
+    let mut cancel = cancel(&fixture, &order);
+    cancel.accounts[1].pubkey = fixture.booth.vault_a;
+    let result = process(&mut context, &[cancel], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::LimitOrderMismatch);
+}
+
+// PlaceLimitOrder creates the order and its escrow through CPI, which only the BPF build can do
+// in solana-program-test 1.9
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn placed_order_escrows_the_amount_and_tip() {
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    let owner = fixture.customer.pubkey();
+    let place = place(&fixture, 1.5);
+    process(&mut context, &[place], &[&fixture.customer]).await.unwrap();
+    let limit_order = client::limit_order_address(&fixture.program_id, &fixture.booth.exchange_booth, &owner, 0).0;
+    let escrow = client::limit_order_escrow_address(&fixture.program_id, &limit_order).0;
+    assert_eq!(token_balance(&mut context, &escrow).await, ORDER_AMOUNT);
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE - ORDER_AMOUNT);
+    let rent = Rent::default().minimum_balance(LIMIT_ORDER_LEN);
+    assert_eq!(lamports(&mut context, &limit_order).await, Some(rent + TIP_LAMPORTS));
+}
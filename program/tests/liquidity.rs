@@ -0,0 +1,298 @@
+// AddLiquidity and RemoveLiquidity, and how lp shares keep Withdraw away from the vaults.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::{instruction::Instruction, program_option::COption, pubkey::Pubkey};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transport::TransportError,
+};
+
+use exchangebooth::{client, error::ExchangeBoothError};
+
+use common::{
+    assert_error, mint_account_with_authority, process, token_balance, BoothFixture, CUSTOMER_BALANCE,
+    VAULT_BALANCE,
+};
+
+
+// token accounts of one provider, A and B funded with CUSTOMER_BALANCE
+struct Provider {
+    token_a: Pubkey,
+    token_b: Pubkey,
+    lp: Pubkey,
+}
+
+fn add_provider(fixture: &mut BoothFixture, owner: &Pubkey) -> Provider {
+    let (mint_a, mint_b, lp_mint) = (fixture.booth.mint_a, fixture.booth.mint_b, fixture.booth.lp_mint);
+    Provider {
+        token_a: fixture.add_token_account(&mint_a, owner, CUSTOMER_BALANCE),
+        token_b: fixture.add_token_account(&mint_b, owner, CUSTOMER_BALANCE),
+        lp: fixture.add_token_account(&lp_mint, owner, 0),
+    }
+}
+
+async fn add_liquidity(
+    context: &mut ProgramTestContext,
+    fixture: &BoothFixture,
+    provider: &Keypair,
+    accounts: &Provider,
+    amount_a: f64,
+    amount_b: f64,
+) -> Result<(), TransportError> {
+    let instruction = client::add_liquidity(
+        &fixture.program_id,
+        &fixture.booth,
+        &provider.pubkey(),
+        &accounts.token_a,
+        &accounts.token_b,
+        &accounts.lp,
+        amount_a,
+        amount_b,
+    );
+    process(context, &[instruction], &[provider]).await
+}
+
+#[tokio::test]
+async fn admin_seeds_shares_for_the_funded_vaults() {
+    let mut fixture = BoothFixture::new();
+    let admin = fixture.admin.pubkey();
+    let admin_accounts = add_provider(&mut fixture, &admin);
+    let (mut context, fixture) = fixture.start().await;
+
+    add_liquidity(&mut context, &fixture, &fixture.admin, &admin_accounts, 0.0, 10.0).await.unwrap();
+    // 1000 A at 2 B per A, 1000 B already in the vaults and 10 B deposited, in 6 decimal B
+    assert_eq!(token_balance(&mut context, &admin_accounts.lp).await, 3_010_000_000);
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_b).await, VAULT_BALANCE + 10_000_000);
+}
+
+#[tokio::test]
+async fn provider_cannot_seed_funded_booth() {
+    let mut fixture = BoothFixture::new();
+    let customer = fixture.customer.pubkey();
+    let customer_accounts = add_provider(&mut fixture, &customer);
+    let (mut context, fixture) = fixture.start().await;
+
+    let result = add_liquidity(&mut context, &fixture, &fixture.customer, &customer_accounts, 1.0, 0.0).await;
+    assert_error(result, ExchangeBoothError::IncorrectAdmin);
+}
+
+#[tokio::test]
+async fn provider_cannot_seed_empty_booth() {
+    // the admin's next direct deposit would back these shares
+    let mut fixture = BoothFixture::new();
+    let (vault_a, vault_b) = (fixture.booth.vault_a, fixture.booth.vault_b);
+    fixture.set_token_balance(&vault_a, 0);
+    fixture.set_token_balance(&vault_b, 0);
+    let customer = fixture.customer.pubkey();
+    let customer_accounts = add_provider(&mut fixture, &customer);
+    let (mut context, fixture) = fixture.start().await;
+
+    let result = add_liquidity(&mut context, &fixture, &fixture.customer, &customer_accounts, 1.0, 0.0).await;
+    assert_error(result, ExchangeBoothError::IncorrectAdmin);
+}
+
+#[tokio::test]
+async fn provider_joins_and_redeems_pro_rata() {
+    let mut fixture = BoothFixture::new();
+    let admin = fixture.admin.pubkey();
+    let customer = fixture.customer.pubkey();
+    let admin_accounts = add_provider(&mut fixture, &admin);
+    let customer_accounts = add_provider(&mut fixture, &customer);
+    let (mut context, fixture) = fixture.start().await;
+
+    add_liquidity(&mut context, &fixture, &fixture.admin, &admin_accounts, 0.0, 10.0).await.unwrap();
+    // 1 A is worth 2 B of a 3010 B pool backed by 3010 lp tokens
+    add_liquidity(&mut context, &fixture, &fixture.customer, &customer_accounts, 1.0, 0.0).await.unwrap();
+    assert_eq!(token_balance(&mut context, &customer_accounts.lp).await, 2_000_000);
+
+    let remove = client::remove_liquidity(
+        &fixture.program_id,
+        &fixture.booth,
+        &customer,
+        &customer_accounts.token_a,
+        &customer_accounts.token_b,
+        &customer_accounts.lp,
+        2.0,
+    );
+    process(&mut context, &[remove], &[&fixture.customer]).await.unwrap();
+    // 2 of 3012 lp tokens against vaults of 1001 A and 1010 B, rounded down
+    assert_eq!(token_balance(&mut context, &customer_accounts.token_a).await, CUSTOMER_BALANCE - 1_000_000 + 664_674);
+    assert_eq!(token_balance(&mut context, &customer_accounts.token_b).await, CUSTOMER_BALANCE + 670_650);
+    assert_eq!(token_balance(&mut context, &customer_accounts.lp).await, 0);
+}
+
+#[tokio::test]
+async fn remove_liquidity_rejects_more_than_the_supply() {
+    let mut fixture = BoothFixture::new();
+    let lp_mint = fixture.booth.lp_mint;
+    fixture.accounts.insert(lp_mint, mint_account_with_authority(COption::Some(lp_mint), 1_000_000));
+    let admin = fixture.admin.pubkey();
+    let admin_accounts = add_provider(&mut fixture, &admin);
+    fixture.set_token_balance(&admin_accounts.lp, 1_000_000);
+    let (mut context, fixture) = fixture.start().await;
+
+    let remove = client::remove_liquidity(
+        &fixture.program_id,
+        &fixture.booth,
+        &admin,
+        &admin_accounts.token_a,
+        &admin_accounts.token_b,
+        &admin_accounts.lp,
+        2.0,
+    );
+    let result = process(&mut context, &[remove], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn withdraw_before_any_lp_shares() {
+    let mut fixture = BoothFixture::new();
+    let (mint_a, admin) = (fixture.booth.mint_a, fixture.admin.pubkey());
+    let admin_a = fixture.add_token_account(&mint_a, &admin, 0);
+    let (mut context, fixture) = fixture.start().await;
+
+    let withdraw = client::withdraw(&fixture.program_id, &fixture.booth, &fixture.booth.mint_a, &admin_a, 5.0);
+    process(&mut context, &[withdraw], &[&fixture.admin]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &admin_a).await, 5_000_000);
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_a).await, VAULT_BALANCE - 5_000_000);
+}
+
+#[tokio::test]
+async fn withdraw_rejected_once_lp_shares_exist() {
+    let mut fixture = BoothFixture::new();
+    let admin = fixture.admin.pubkey();
+    let admin_accounts = add_provider(&mut fixture, &admin);
+    let (mut context, fixture) = fixture.start().await;
+
+    add_liquidity(&mut context, &fixture, &fixture.admin, &admin_accounts, 0.0, 10.0).await.unwrap();
+    let withdraw = client::withdraw(&fixture.program_id, &fixture.booth, &fixture.booth.mint_a, &admin_accounts.token_a, 5.0);
+    let result = process(&mut context, &[withdraw], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::LiquidityOutstanding);
+}
+
+// a provider joining a booth the admin already seeded, with `tamper` applied to the instruction
+async fn add_liquidity_with(tamper: impl FnOnce(&mut BoothFixture, &mut Instruction)) -> Result<(), TransportError> {
+    let mut fixture = BoothFixture::new();
+    let lp_mint = fixture.booth.lp_mint;
+    fixture.accounts.insert(lp_mint, mint_account_with_authority(COption::Some(lp_mint), 1_000_000));
+    let customer = fixture.customer.pubkey();
+    let accounts = add_provider(&mut fixture, &customer);
+    let mut instruction = client::add_liquidity(
+        &fixture.program_id,
+        &fixture.booth,
+        &customer,
+        &accounts.token_a,
+        &accounts.token_b,
+        &accounts.lp,
+        1.0,
+        1.0,
+    );
+    tamper(&mut fixture, &mut instruction);
+    let (mut context, fixture) = fixture.start().await;
+    process(&mut context, &[instruction], &[&fixture.customer]).await
+}
+
+#[tokio::test]
+async fn add_liquidity_rejects_booth_owned_by_another_program() {
+    let result = add_liquidity_with(|fixture, _| {
+        let booth = fixture.booth.exchange_booth;
+        fixture.set_owner(&booth, &Pubkey::new_unique());
+    }).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn add_liquidity_rejects_fake_token_program() {
+    let result = add_liquidity_with(|_, instruction| instruction.accounts[11].pubkey = Pubkey::new_unique()).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn add_liquidity_rejects_lp_mint_owned_by_another_program() {
+    let result = add_liquidity_with(|fixture, _| {
+        let lp_mint = fixture.booth.lp_mint;
+        fixture.set_owner(&lp_mint, &Pubkey::new_unique());
+    }).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn add_liquidity_rejects_another_booths_oracle() {
+    let result = add_liquidity_with(|fixture, instruction| {
+        let mint_a = fixture.booth.mint_a;
+        instruction.accounts[1].pubkey = fixture.add_booth(&mint_a, 3.0).oracle;
+    }).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn add_liquidity_rejects_another_lp_mint() {
+    let result = add_liquidity_with(|fixture, instruction| {
+        let mint_a = fixture.booth.mint_a;
+        instruction.accounts[6].pubkey = fixture.add_booth(&mint_a, 3.0).lp_mint;
+    }).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+// the admin redeeming 1 of 1 lp share, with `tamper` applied to the instruction
+async fn remove_liquidity_with(tamper: impl FnOnce(&mut BoothFixture, &mut Instruction)) -> Result<(), TransportError> {
+    let mut fixture = BoothFixture::new();
+    let lp_mint = fixture.booth.lp_mint;
+    fixture.accounts.insert(lp_mint, mint_account_with_authority(COption::Some(lp_mint), 1_000_000));
+    let admin = fixture.admin.pubkey();
+    let accounts = add_provider(&mut fixture, &admin);
+    fixture.set_token_balance(&accounts.lp, 1_000_000);
+    let mut instruction = client::remove_liquidity(
+        &fixture.program_id,
+        &fixture.booth,
+        &admin,
+        &accounts.token_a,
+        &accounts.token_b,
+        &accounts.lp,
+        1.0,
+    );
+    tamper(&mut fixture, &mut instruction);
+    let (mut context, fixture) = fixture.start().await;
+    process(&mut context, &[instruction], &[&fixture.admin]).await
+}
+
+#[tokio::test]
+async fn remove_liquidity_rejects_booth_owned_by_another_program() {
+    let result = remove_liquidity_with(|fixture, _| {
+        let booth = fixture.booth.exchange_booth;
+        fixture.set_owner(&booth, &Pubkey::new_unique());
+    }).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn remove_liquidity_rejects_fake_token_program() {
+    let result = remove_liquidity_with(|_, instruction| instruction.accounts[10].pubkey = Pubkey::new_unique()).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn remove_liquidity_rejects_vault_owned_by_another_program() {
+    let result = remove_liquidity_with(|fixture, _| {
+        let vault = fixture.booth.vault_a;
+        fixture.set_owner(&vault, &Pubkey::new_unique());
+    }).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn remove_liquidity_rejects_another_booths_vault() {
+    let result = remove_liquidity_with(|fixture, instruction| {
+        let mint_a = fixture.booth.mint_a;
+        instruction.accounts[1].pubkey = fixture.add_booth(&mint_a, 3.0).vault_a;
+    }).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn remove_liquidity_redeems_with_genuine_accounts() {
+    remove_liquidity_with(|_, _| {}).await.unwrap();
+}
@@ -0,0 +1,387 @@
+// A booth of three assets priced against a common quote asset: trades between any two of them,
+// oracle updates and withdrawals.
+#![cfg(feature = "client")]
+
+mod common;
+
+use std::collections::HashMap;
+
+use borsh::BorshSerialize;
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey, rent::Rent, system_instruction};
+use solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transport::TransportError,
+};
+use spl_token::state::Mint;
+
+use exchangebooth::{
+    client::{self, MultiAssetBoothAccounts},
+    error::ExchangeBoothError,
+    processor::Processor,
+    state::{multi_asset_booth_len, multi_asset_rate_len, BoothAsset, MultiAssetBooth, MultiAssetRate},
+};
+
+use common::{assert_error, mint_account, process, program_account, system_account, token_account, token_balance};
+
+// asset 1 has 9 decimals, the others 6
+const DECIMALS: [u8; 3] = [6, 9, 6];
+const PRICES: [f64; 3] = [1.0, 2.0, 0.5];
+const VAULT_BALANCE: u64 = 1_000;
+const CUSTOMER_BALANCE: u64 = 100;
+
+struct MultiAssetFixture {
+    program_id: Pubkey,
+    admin: Keypair,
+    customer: Keypair,
+    booth: MultiAssetBoothAccounts,
+    mints: [Pubkey; 3],
+    // the customer's token account of each asset
+    customer_accounts: [Pubkey; 3],
+}
+
+fn base_units(ui_amount: u64, asset: usize) -> u64 {
+    ui_amount * 10u64.pow(DECIMALS[asset].into())
+}
+
+// the booth and its funded vaults are laid out in the genesis accounts, as InitializeMultiAssetBooth
+// creates the vaults through CPI which native program-test can't do
+async fn start() -> (ProgramTestContext, MultiAssetFixture) {
+    start_tampered(|_, _| {}).await
+}
+
+// like start, with `tamper` applied to the genesis accounts first
+async fn start_tampered(
+    tamper: impl FnOnce(&MultiAssetFixture, &mut HashMap<Pubkey, Account>),
+) -> (ProgramTestContext, MultiAssetFixture) {
+    let program_id = Pubkey::new_unique();
+    let admin = Keypair::new();
+    let customer = Keypair::new();
+    let booth = MultiAssetBoothAccounts {
+        admin: admin.pubkey(),
+        multi_asset_booth: Pubkey::new_unique(),
+        oracle: Pubkey::new_unique(),
+        token_program: spl_token::id(),
+    };
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let customer_accounts = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let mut accounts = HashMap::new();
+    accounts.insert(admin.pubkey(), system_account());
+    accounts.insert(customer.pubkey(), system_account());
+    let mut assets = Vec::new();
+    for (asset, mint) in mints.iter().enumerate() {
+        let mut mint_acc = mint_account(0);
+        let mut mint_state = Mint::unpack(&mint_acc.data).unwrap();
+        mint_state.decimals = DECIMALS[asset];
+        mint_state.pack_into_slice(&mut mint_acc.data);
+        accounts.insert(*mint, mint_acc);
+        let vault = booth.vault(&program_id, mint);
+        accounts.insert(vault, token_account(mint, &vault, base_units(VAULT_BALANCE, asset)));
+        let customer_balance = if asset == 2 { base_units(CUSTOMER_BALANCE, asset) } else { 0 };
+        accounts.insert(customer_accounts[asset], token_account(mint, &customer.pubkey(), customer_balance));
+        assets.push(BoothAsset { mint: *mint, vault });
+    }
+
+    let mut booth_data = vec![0; multi_asset_booth_len(3)];
+    MultiAssetBooth { admin: admin.pubkey(), oracle: booth.oracle, assets }
+        .serialize(&mut &mut booth_data[..])
+        .unwrap();
+    accounts.insert(booth.multi_asset_booth, program_account(&program_id, booth_data));
+    let mut oracle_data = vec![0; multi_asset_rate_len(3)];
+    MultiAssetRate { authority: admin.pubkey(), prices: PRICES.to_vec() }
+        .serialize(&mut &mut oracle_data[..])
+        .unwrap();
+    accounts.insert(booth.oracle, program_account(&program_id, oracle_data));
+
+    let fixture = MultiAssetFixture { program_id, admin, customer, booth, mints, customer_accounts };
+    tamper(&fixture, &mut accounts);
+    let mut program_test = ProgramTest::new("exchangebooth", program_id, processor!(Processor::process_instruction));
+    for (address, account) in accounts {
+        program_test.add_account(address, account);
+    }
+    (program_test.start_with_context().await, fixture)
+}
+
+// `address` handed to another program
+fn set_owner(accounts: &mut HashMap<Pubkey, Account>, address: &Pubkey) {
+    accounts.get_mut(address).unwrap().owner = Pubkey::new_unique();
+}
+
+// the customer sells `amount` of asset `from` for asset `to`
+fn exchange(fixture: &MultiAssetFixture, from: usize, to: usize, amount: f64) -> Instruction {
+    client::exchange_multi_asset(
+        &fixture.program_id,
+        &fixture.booth,
+        (from as u8, &fixture.mints[from]),
+        (to as u8, &fixture.mints[to]),
+        &fixture.customer.pubkey(),
+        &fixture.customer_accounts[from],
+        &fixture.customer_accounts[to],
+        amount,
+    )
+}
+
+#[tokio::test]
+async fn exchange_between_assets_at_the_cross_rate() {
+    let (mut context, fixture) = start().await;
+    process(&mut context, &[exchange(&fixture, 2, 1, 10.0)], &[&fixture.customer]).await.unwrap();
+    // 10 at 0.5 is worth 5 in the quote asset, 2.5 of asset 1 at 2, in 9 decimals
+    assert_eq!(token_balance(&mut context, &fixture.customer_accounts[1]).await, 2_500_000_000);
+    assert_eq!(token_balance(&mut context, &fixture.customer_accounts[2]).await, 90_000_000);
+    let vault_2 = fixture.booth.vault(&fixture.program_id, &fixture.mints[2]);
+    assert_eq!(token_balance(&mut context, &vault_2).await, base_units(VAULT_BALANCE, 2) + 10_000_000);
+}
+
+#[tokio::test]
+async fn exchange_rejects_an_asset_for_itself() {
+    let (mut context, fixture) = start().await;
+    let result = process(&mut context, &[exchange(&fixture, 2, 2, 10.0)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn exchange_rejects_the_vault_of_another_asset() {
+    let (mut context, fixture) = start().await;
+    let mut exchange = exchange(&fixture, 2, 1, 10.0);
+    // to_vault of asset 0 while the index says asset 1
+    exchange.accounts[3].pubkey = fixture.booth.vault(&fixture.program_id, &fixture.mints[0]);
+    let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn updated_prices_apply_to_the_next_exchange() {
+    let (mut context, fixture) = start().await;
+    let update = client::update_multi_asset_oracle(&fixture.program_id, &fixture.booth.oracle, &fixture.admin.pubkey(), vec![1.0, 2.0, 1.0]);
+    process(&mut context, &[update, exchange(&fixture, 2, 0, 10.0)], &[&fixture.admin, &fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_accounts[0]).await, 10_000_000);
+}
+
+#[tokio::test]
+async fn oracle_update_rejects_another_signer() {
+    let (mut context, fixture) = start().await;
+    let update = client::update_multi_asset_oracle(&fixture.program_id, &fixture.booth.oracle, &fixture.customer.pubkey(), vec![1.0, 2.0, 1.0]);
+    let result = process(&mut context, &[update], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::IncorrectAdmin);
+}
+
+#[tokio::test]
+async fn admin_withdraws_one_asset() {
+    let (mut context, fixture) = start().await;
+    let withdraw = client::withdraw_multi_asset(&fixture.program_id, &fixture.booth, 1, &fixture.mints[1], &fixture.customer_accounts[1], 1.5);
+    process(&mut context, &[withdraw], &[&fixture.admin]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_accounts[1]).await, 1_500_000_000);
+}
+
+#[tokio::test]
+async fn withdraw_rejects_another_signer() {
+    let (mut context, fixture) = start().await;
+    let booth = MultiAssetBoothAccounts { admin: fixture.customer.pubkey(), ..fixture.booth.clone() };
+    let mut withdraw = client::withdraw_multi_asset(&fixture.program_id, &booth, 1, &fixture.mints[1], &fixture.customer_accounts[1], 1.5);
+    // the real vault, only the signer differs
+    withdraw.accounts[1].pubkey = fixture.booth.vault(&fixture.program_id, &fixture.mints[1]);
+    let result = process(&mut context, &[withdraw], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::IncorrectAdmin);
+}
+
+#[tokio::test]
+async fn initialize_rejects_a_single_asset() {
+    let (mut context, fixture) = start().await;
+    let booth_keypair = Keypair::new();
+    let booth = MultiAssetBoothAccounts { multi_asset_booth: booth_keypair.pubkey(), ..fixture.booth.clone() };
+    let create_booth = system_instruction::create_account(
+        &fixture.admin.pubkey(),
+        &booth.multi_asset_booth,
+        Rent::default().minimum_balance(multi_asset_booth_len(1)),
+        multi_asset_booth_len(1) as u64,
+        &fixture.program_id,
+    );
+    let initialize = client::initialize_multi_asset_booth(&fixture.program_id, &booth, &fixture.mints[..1]);
+    let result = process(&mut context, &[create_booth, initialize], &[&fixture.admin, &booth_keypair]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn exchange_rejects_booth_owned_by_another_program() {
+    let (mut context, fixture) = start_tampered(|fixture, accounts| set_owner(accounts, &fixture.booth.multi_asset_booth)).await;
+    let result = process(&mut context, &[exchange(&fixture, 2, 1, 10.0)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn exchange_rejects_oracle_owned_by_another_program() {
+    let (mut context, fixture) = start_tampered(|fixture, accounts| set_owner(accounts, &fixture.booth.oracle)).await;
+    let result = process(&mut context, &[exchange(&fixture, 2, 1, 10.0)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn exchange_rejects_vault_owned_by_another_program() {
+    let (mut context, fixture) = start_tampered(|fixture, accounts| {
+        set_owner(accounts, &fixture.booth.vault(&fixture.program_id, &fixture.mints[1]))
+    }).await;
+    let result = process(&mut context, &[exchange(&fixture, 2, 1, 10.0)], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn exchange_rejects_fake_token_program() {
+    let (mut context, fixture) = start().await;
+    let mut exchange = exchange(&fixture, 2, 1, 10.0);
+    exchange.accounts[9].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn exchange_rejects_another_oracle() {
+    let other_oracle = Pubkey::new_unique();
+    let (mut context, fixture) = start_tampered(|fixture, accounts| {
+        let oracle = accounts[&fixture.booth.oracle].clone();
+        accounts.insert(other_oracle, oracle);
+    }).await;
+    let mut exchange = exchange(&fixture, 2, 1, 10.0);
+    exchange.accounts[1].pubkey = other_oracle;
+    let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn oracle_update_rejects_oracle_owned_by_another_program() {
+    let (mut context, fixture) = start_tampered(|fixture, accounts| set_owner(accounts, &fixture.booth.oracle)).await;
+    let update = client::update_multi_asset_oracle(&fixture.program_id, &fixture.booth.oracle, &fixture.admin.pubkey(), vec![1.0, 2.0, 1.0]);
+    let result = process(&mut context, &[update], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+fn withdraw(fixture: &MultiAssetFixture) -> Instruction {
+    client::withdraw_multi_asset(&fixture.program_id, &fixture.booth, 1, &fixture.mints[1], &fixture.customer_accounts[1], 1.5)
+}
+
+#[tokio::test]
+async fn withdraw_rejects_booth_owned_by_another_program() {
+    let (mut context, fixture) = start_tampered(|fixture, accounts| set_owner(accounts, &fixture.booth.multi_asset_booth)).await;
+    let result = process(&mut context, &[withdraw(&fixture)], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn withdraw_rejects_fake_token_program() {
+    let (mut context, fixture) = start().await;
+    let mut withdraw = withdraw(&fixture);
+    withdraw.accounts[5].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[withdraw], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn withdraw_rejects_the_vault_of_another_asset() {
+    let (mut context, fixture) = start().await;
+    let mut withdraw = withdraw(&fixture);
+    withdraw.accounts[1].pubkey = fixture.booth.vault(&fixture.program_id, &fixture.mints[0]);
+    let result = process(&mut context, &[withdraw], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+// a fresh two asset booth and oracle of `booth_owner`, created in the same transaction as
+// `tamper`ed InitializeMultiAssetBooth
+async fn initialize_with(booth_owner: Option<Pubkey>, tamper: impl FnOnce(&mut Instruction)) -> Result<(), TransportError> {
+    let (mut context, fixture) = start().await;
+    let (booth_keypair, oracle_keypair) = (Keypair::new(), Keypair::new());
+    let booth = MultiAssetBoothAccounts {
+        multi_asset_booth: booth_keypair.pubkey(),
+        oracle: oracle_keypair.pubkey(),
+        ..fixture.booth.clone()
+    };
+    let admin = fixture.admin.pubkey();
+    let mut initialize = client::initialize_multi_asset_booth(&fixture.program_id, &booth, &fixture.mints[..2]);
+    tamper(&mut initialize);
+    let instructions = [
+        system_instruction::create_account(
+            &admin,
+            &booth.multi_asset_booth,
+            Rent::default().minimum_balance(multi_asset_booth_len(2)),
+            multi_asset_booth_len(2) as u64,
+            &booth_owner.unwrap_or(fixture.program_id),
+        ),
+        system_instruction::create_account(
+            &admin,
+            &booth.oracle,
+            Rent::default().minimum_balance(multi_asset_rate_len(2)),
+            multi_asset_rate_len(2) as u64,
+            &fixture.program_id,
+        ),
+        initialize,
+    ];
+    process(&mut context, &instructions, &[&fixture.admin, &booth_keypair, &oracle_keypair]).await
+}
+
+#[tokio::test]
+async fn initialize_rejects_booth_owned_by_another_program() {
+    let result = initialize_with(Some(Pubkey::new_unique()), |_| {}).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn initialize_rejects_fake_programs_and_sysvar() {
+    for (index, error) in [
+        (3, ExchangeBoothError::InvalidSystemProgram),
+        (4, ExchangeBoothError::InvalidTokenProgram),
+        (5, ExchangeBoothError::InvalidRentSysvar),
+    ] {
+        let result = initialize_with(None, |initialize| initialize.accounts[index].pubkey = Pubkey::new_unique()).await;
+        assert_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn initialize_rejects_a_vault_off_its_pda() {
+    // the vault of the second mint in the first mint's place
+    let result = initialize_with(None, |initialize| initialize.accounts[7].pubkey = initialize.accounts[9].pubkey).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+// InitializeMultiAssetBooth creates the vaults through CPI, which only the BPF build can do in
+// solana-program-test 1.9
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn initialize_creates_a_vault_per_asset() {
+    use borsh::BorshDeserialize;
+
+    let (mut context, fixture) = start().await;
+    let (booth_keypair, oracle_keypair) = (Keypair::new(), Keypair::new());
+    let booth = MultiAssetBoothAccounts {
+        multi_asset_booth: booth_keypair.pubkey(),
+        oracle: oracle_keypair.pubkey(),
+        ..fixture.booth.clone()
+    };
+    let admin = fixture.admin.pubkey();
+    let instructions = [
+        system_instruction::create_account(
+            &admin,
+            &booth.multi_asset_booth,
+            Rent::default().minimum_balance(multi_asset_booth_len(3)),
+            multi_asset_booth_len(3) as u64,
+            &fixture.program_id,
+        ),
+        system_instruction::create_account(
+            &admin,
+            &booth.oracle,
+            Rent::default().minimum_balance(multi_asset_rate_len(3)),
+            multi_asset_rate_len(3) as u64,
+            &fixture.program_id,
+        ),
+        client::initialize_multi_asset_booth(&fixture.program_id, &booth, &fixture.mints),
+    ];
+    process(&mut context, &instructions, &[&fixture.admin, &booth_keypair, &oracle_keypair]).await.unwrap();
+
+    let account = context.banks_client.get_account(booth.multi_asset_booth).await.unwrap().unwrap();
+    let state = MultiAssetBooth::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.assets.len(), 3);
+    for (asset, mint) in state.assets.iter().zip(fixture.mints.iter()) {
+        assert_eq!(asset.vault, booth.vault(&fixture.program_id, mint));
+        assert_eq!(token_balance(&mut context, &asset.vault).await, 0);
+    }
+}
@@ -0,0 +1,77 @@
+// Oracles whose keypair pushes rates with UpdateOracleExchangeRate, in the current layout and in
+// the one from before confidence.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::instruction::Instruction;
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+use exchangebooth::{client, error::ExchangeBoothError, state::LEGACY_EXCHANGE_RATE_LEN};
+
+use common::{assert_error, process, program_account, token_balance, BoothFixture};
+
+// The fixture booth with its oracle at the address of a keypair the test holds. A `legacy`
+// oracle is 16 bytes of a_to_b and b_to_a at 2 B per A.
+async fn start(legacy: bool) -> (ProgramTestContext, BoothFixture, Keypair) {
+    let mut fixture = BoothFixture::new();
+    let oracle = Keypair::new();
+    fixture.set_oracle(&oracle.pubkey());
+    if legacy {
+        let mut data = vec![0; LEGACY_EXCHANGE_RATE_LEN];
+        data[..8].copy_from_slice(&2.0f64.to_le_bytes());
+        data[8..].copy_from_slice(&0.5f64.to_le_bytes());
+        fixture.accounts.insert(oracle.pubkey(), program_account(&fixture.program_id, data));
+    }
+    let (context, fixture) = fixture.start().await;
+    (context, fixture, oracle)
+}
+
+fn update(fixture: &BoothFixture, oracle: &Keypair, a_to_b: f64, confidence: f64) -> Instruction {
+    client::update_oracle_exchange_rate(&fixture.program_id, &oracle.pubkey(), a_to_b, confidence)
+}
+
+#[tokio::test]
+async fn confidence_comes_off_what_the_customer_gets() {
+    let (mut context, fixture, oracle) = start(false).await;
+    let instructions = [update(&fixture, &oracle, 2.0, 0.01), fixture.exchange()];
+    process(&mut context, &instructions, &[&oracle, &fixture.customer]).await.unwrap();
+    // 1 A at 2 B per A, less 1%
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 1_980_000);
+}
+
+#[tokio::test]
+async fn update_rejects_rates_that_are_not_positive() {
+    // borsh won't carry a NaN in instruction data in the first place
+    for a_to_b in [0.0, -2.0, f64::INFINITY] {
+        let (mut context, fixture, oracle) = start(false).await;
+        let result = process(&mut context, &[update(&fixture, &oracle, a_to_b, 0.0)], &[&oracle]).await;
+        assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+    }
+}
+
+#[tokio::test]
+async fn exchange_prices_from_a_legacy_oracle() {
+    let (mut context, fixture, _) = start(true).await;
+    process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_000_000);
+}
+
+#[tokio::test]
+async fn legacy_oracle_takes_updates_in_its_own_layout() {
+    let (mut context, fixture, oracle) = start(true).await;
+    let instructions = [update(&fixture, &oracle, 3.0, 0.0), fixture.exchange()];
+    process(&mut context, &instructions, &[&oracle, &fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 3_000_000);
+    let account = context.banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+    assert_eq!(account.data[..8], 3.0f64.to_le_bytes());
+    assert_eq!(account.data.len(), LEGACY_EXCHANGE_RATE_LEN);
+}
+
+#[tokio::test]
+async fn legacy_oracle_rejects_a_confidence() {
+    let (mut context, fixture, oracle) = start(true).await;
+    let result = process(&mut context, &[update(&fixture, &oracle, 3.0, 0.01)], &[&oracle]).await;
+    assert_error(result, ExchangeBoothError::InvalidInstructionInput);
+}
@@ -0,0 +1,124 @@
+// Exchange priced from an oracle account in the Pyth v2 price layout, published by another program.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::{clock::Clock, pubkey::Pubkey};
+use solana_program_test::{tokio, ProgramTestContext};
+
+use exchangebooth::{
+    error::ExchangeBoothError,
+    pricing,
+    state::{ExchangeBooth, OracleSource},
+};
+
+use common::{assert_error, process, program_account, token_balance, BoothFixture, DECIMALS};
+
+const NOW: i64 = 1_650_000_000;
+const MAX_PRICE_AGE: i64 = 60;
+const MAX_CONFIDENCE_BPS: u64 = 100;
+// 2 B per A at exponent -8
+const PRICE: i64 = 200_000_000;
+const EXPONENT: i32 = -8;
+
+struct PythPrice {
+    magic: u32,
+    status: u32,
+    price: i64,
+    conf: u64,
+    publish_time: i64,
+}
+
+impl Default for PythPrice {
+    fn default() -> Self {
+        PythPrice { magic: 0xa1b2c3d4, status: 1, price: PRICE, conf: 10_000, publish_time: NOW - 5 }
+    }
+}
+
+// only the fields processor::oracle reads, at their offsets in a v2 price account
+fn pyth_price_data(price: &PythPrice) -> Vec<u8> {
+    let mut data = vec![0; 3312];
+    data[0..4].copy_from_slice(&price.magic.to_le_bytes());
+    data[4..8].copy_from_slice(&2u32.to_le_bytes());
+    data[8..12].copy_from_slice(&3u32.to_le_bytes());
+    data[20..24].copy_from_slice(&EXPONENT.to_le_bytes());
+    data[96..104].copy_from_slice(&price.publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.price.to_le_bytes());
+    data[216..224].copy_from_slice(&price.conf.to_le_bytes());
+    data[224..228].copy_from_slice(&price.status.to_le_bytes());
+    data
+}
+
+fn pyth_booth(fixture: &BoothFixture) -> ExchangeBooth {
+    ExchangeBooth {
+        admin: fixture.booth.admin,
+        oracle: fixture.booth.oracle,
+        vault_a: fixture.booth.vault_a,
+        vault_b: fixture.booth.vault_b,
+        lp_mint: fixture.booth.lp_mint,
+        skew_target_ratio: 1.0,
+        skew_sensitivity: 0.0,
+        oracle_source: OracleSource::Pyth,
+        max_confidence_bps: MAX_CONFIDENCE_BPS,
+        max_price_age: MAX_PRICE_AGE,
+        twap_window: 0,
+    }
+}
+
+async fn start(price: &PythPrice) -> (ProgramTestContext, BoothFixture) {
+    let mut fixture = BoothFixture::new();
+    let exchange_booth = pyth_booth(&fixture);
+    fixture.set_booth(&exchange_booth);
+    let pyth_program = Pubkey::new_unique();
+    fixture.accounts.insert(fixture.booth.oracle, program_account(&pyth_program, pyth_price_data(price)));
+    let (mut context, fixture) = fixture.start().await;
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = NOW;
+    context.set_sysvar(&clock);
+    (context, fixture)
+}
+
+#[tokio::test]
+async fn exchange_at_pyth_price() {
+    let price = PythPrice::default();
+    let (mut context, fixture) = start(&price).await;
+    process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await.unwrap();
+
+    // booth_rate without skew, which would log outside of a program here
+    let a_to_b = PRICE as f64 * 10f64.powi(EXPONENT);
+    let rate = a_to_b * (1.0 - price.conf as f64 / price.price as f64);
+    assert_eq!(
+        token_balance(&mut context, &fixture.customer_b).await,
+        pricing::amount_out(1_000_000, DECIMALS, DECIMALS, rate)
+    );
+}
+
+#[tokio::test]
+async fn exchange_rejects_stale_pyth_price() {
+    let (mut context, fixture) = start(&PythPrice { publish_time: NOW - MAX_PRICE_AGE - 1, ..PythPrice::default() }).await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::StaleOraclePrice);
+}
+
+#[tokio::test]
+async fn exchange_rejects_wide_pyth_confidence() {
+    // 2% of the price, over the 1% limit
+    let (mut context, fixture) = start(&PythPrice { conf: 4_000_000, ..PythPrice::default() }).await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::OracleConfidenceTooWide);
+}
+
+#[tokio::test]
+async fn exchange_rejects_pyth_price_not_trading() {
+    let (mut context, fixture) = start(&PythPrice { status: 0, ..PythPrice::default() }).await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountData);
+}
+
+#[tokio::test]
+async fn exchange_rejects_account_without_pyth_magic() {
+    let (mut context, fixture) = start(&PythPrice { magic: 0, ..PythPrice::default() }).await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountData);
+}
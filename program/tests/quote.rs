@@ -0,0 +1,115 @@
+// Quote's return data against what Exchange then pays. solana-program-test 1.9 drops return
+// data from native programs, so the test wraps its syscall stubs to keep what Quote sets.
+#![cfg(feature = "client")]
+
+mod common;
+
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program::get_return_data,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::Signer;
+use std::sync::Once;
+
+use exchangebooth::{
+    client,
+    instruction::{QuoteResult, TradeDirection},
+    pricing,
+};
+
+use common::{process, token_balance, BoothFixture, CUSTOMER_BALANCE, DECIMALS};
+
+// program-test's stubs, except that they keep the last return data set
+struct ReturnDataStubs {
+    stubs: Box<dyn SyscallStubs>,
+    return_data: Option<Vec<u8>>,
+}
+
+impl SyscallStubs for ReturnDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.stubs.sol_log(message)
+    }
+    fn sol_invoke_signed(&self, instruction: &Instruction, account_infos: &[AccountInfo], signers_seeds: &[&[&[u8]]]) -> ProgramResult {
+        self.stubs.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.stubs.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.stubs.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.stubs.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.stubs.sol_get_rent_sysvar(var_addr)
+    }
+    // only Quote sets return data here, which program set it isn't tracked
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.return_data.clone().map(|data| (Pubkey::default(), data))
+    }
+    fn sol_set_return_data(&mut self, data: &[u8]) {
+        self.return_data = Some(data.to_vec());
+    }
+}
+
+// program-test installs its own stubs the first time a test starts, wrap them after that
+fn keep_return_data() {
+    static WRAP: Once = Once::new();
+    WRAP.call_once(|| {
+        let stubs = set_syscall_stubs(Box::new(DefaultStubs));
+        set_syscall_stubs(Box::new(ReturnDataStubs { stubs, return_data: None }));
+    });
+}
+
+// placeholder while program-test's stubs move into the wrapper
+struct DefaultStubs;
+impl SyscallStubs for DefaultStubs {}
+
+async fn quote(context: &mut ProgramTestContext, fixture: &BoothFixture, amount: f64, direction: TradeDirection) -> QuoteResult {
+    process(context, &[client::quote(&fixture.program_id, &fixture.booth, amount, direction)], &[]).await.unwrap();
+    let (_, data) = get_return_data().expect("Quote set no return data");
+    QuoteResult::try_from_slice(&data).unwrap()
+}
+
+// Quotes `amount`, exchanges it and checks the customer got exactly the quoted amount_out.
+async fn assert_quote_matches_exchange(skew_sensitivity: f64, direction: TradeDirection, amount: f64) {
+    let mut fixture = BoothFixture::new();
+    let customer_b = fixture.customer_b;
+    fixture.set_token_balance(&customer_b, CUSTOMER_BALANCE);
+    let (mut context, fixture) = fixture.start().await;
+    keep_return_data();
+    if skew_sensitivity > 0.0 {
+        let set_skew = client::set_inventory_skew(&fixture.program_id, &fixture.booth, 1.0, skew_sensitivity);
+        process(&mut context, &[set_skew], &[&fixture.admin]).await.unwrap();
+    }
+
+    let quote_result = quote(&mut context, &fixture, amount, direction).await;
+    let (from, to) = match direction {
+        TradeDirection::AToB => (fixture.customer_a, fixture.customer_b),
+        TradeDirection::BToA => (fixture.customer_b, fixture.customer_a),
+    };
+    let exchange = client::exchange(&fixture.program_id, &fixture.booth, &fixture.customer.pubkey(), &from, &to, amount, false);
+    process(&mut context, &[exchange], &[&fixture.customer]).await.unwrap();
+
+    let received = token_balance(&mut context, &to).await - CUSTOMER_BALANCE;
+    assert_eq!(received, pricing::to_base_units(quote_result.amount_out, DECIMALS));
+    assert_eq!(quote_result.effective_rate, quote_result.amount_out / amount);
+}
+
+// One test for every case, the stubs and their return data are shared by the whole binary.
+#[tokio::test]
+async fn quote_returns_what_exchange_pays() {
+    // at the oracle rate both ways
+    assert_quote_matches_exchange(0.0, TradeDirection::AToB, 2.5).await;
+    assert_quote_matches_exchange(0.0, TradeDirection::BToA, 2.5).await;
+    // skewed rates that don't come out to whole base units
+    assert_quote_matches_exchange(0.5, TradeDirection::AToB, 1.0).await;
+    assert_quote_matches_exchange(0.5, TradeDirection::BToA, 1.0).await;
+}
@@ -0,0 +1,173 @@
+// Booth registries: CloseExchangeBooth takes a booth back out of the registry of its pair,
+// InitializeExchangeBooth puts it in.
+#![cfg(feature = "client")]
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::Signer;
+
+use exchangebooth::{
+    client,
+    error::ExchangeBoothError,
+    state::{booth_registry_len, BoothRegistry},
+};
+
+use common::{assert_error, process, program_account, BoothFixture};
+
+// an emptied fixture booth, registered after `other_booths` of the same pair
+fn closeable_booth(other_booths: &[Pubkey]) -> BoothFixture {
+    let mut fixture = BoothFixture::new();
+    let (vault_a, vault_b) = (fixture.booth.vault_a, fixture.booth.vault_b);
+    fixture.set_token_balance(&vault_a, 0);
+    fixture.set_token_balance(&vault_b, 0);
+    let mut booths = other_booths.to_vec();
+    booths.push(fixture.booth.exchange_booth);
+    let mut data = vec![0; booth_registry_len(booths.len())];
+    BoothRegistry { booths }.serialize(&mut &mut data[..]).unwrap();
+    let registry = client::booth_registry_address(&fixture.program_id, &fixture.booth.mint_a, &fixture.booth.mint_b).0;
+    fixture.accounts.insert(registry, program_account(&fixture.program_id, data));
+    fixture
+}
+
+async fn load_registry(context: &mut ProgramTestContext, fixture: &BoothFixture) -> Option<(u64, BoothRegistry)> {
+    let registry = client::booth_registry_address(&fixture.program_id, &fixture.booth.mint_a, &fixture.booth.mint_b).0;
+    let account = context.banks_client.get_account(registry).await.unwrap()?;
+    Some((account.lamports, BoothRegistry::try_from_slice(&account.data).unwrap()))
+}
+
+#[test]
+fn registry_address_ignores_mint_order() {
+    let fixture = BoothFixture::new();
+    let (mint_a, mint_b) = (fixture.booth.mint_a, fixture.booth.mint_b);
+    assert_eq!(
+        client::booth_registry_address(&fixture.program_id, &mint_a, &mint_b),
+        client::booth_registry_address(&fixture.program_id, &mint_b, &mint_a),
+    );
+}
+
+// Resizing the registry goes through AccountInfo::realloc, which needs the BPF account layout,
+// so closing a registered booth only runs against the BPF build
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn close_takes_the_booth_out_of_its_registry() {
+    use solana_program::rent::Rent;
+
+    let other_booth = Pubkey::new_unique();
+    let (mut context, fixture) = closeable_booth(&[other_booth]).start().await;
+    let close = client::close_exchange_booth(&fixture.program_id, &fixture.booth);
+    process(&mut context, &[close], &[&fixture.admin]).await.unwrap();
+
+    let (lamports, registry) = load_registry(&mut context, &fixture).await.unwrap();
+    assert_eq!(registry.booths, vec![other_booth]);
+    // the rent of the freed entry went to the admin with everything else
+    assert_eq!(lamports, Rent::default().minimum_balance(booth_registry_len(1)));
+    let booth = context.banks_client.get_account(fixture.booth.exchange_booth).await.unwrap();
+    assert!(booth.is_none());
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn closing_the_last_booth_drops_the_registry() {
+    use common::system_account;
+
+    let (mut context, fixture) = closeable_booth(&[]).start().await;
+    let close = client::close_exchange_booth(&fixture.program_id, &fixture.booth);
+    process(&mut context, &[close], &[&fixture.admin]).await.unwrap();
+    assert!(load_registry(&mut context, &fixture).await.is_none());
+    let admin = context.banks_client.get_account(fixture.admin.pubkey()).await.unwrap().unwrap();
+    assert!(admin.lamports > system_account().lamports);
+}
+
+#[tokio::test]
+async fn close_rejects_a_booth_with_funds() {
+    let mut fixture = closeable_booth(&[]);
+    let vault_b = fixture.booth.vault_b;
+    fixture.set_token_balance(&vault_b, 1);
+    let (mut context, fixture) = fixture.start().await;
+    let close = client::close_exchange_booth(&fixture.program_id, &fixture.booth);
+    let result = process(&mut context, &[close], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::BoothNotEmpty);
+    assert_eq!(load_registry(&mut context, &fixture).await.unwrap().1.booths, vec![fixture.booth.exchange_booth]);
+}
+
+#[tokio::test]
+async fn close_rejects_another_signer() {
+    let (mut context, fixture) = closeable_booth(&[]).start().await;
+    let mut close = client::close_exchange_booth(&fixture.program_id, &fixture.booth);
+    close.accounts[3].pubkey = fixture.customer.pubkey();
+    let result = process(&mut context, &[close], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::IncorrectAdmin);
+}
+
+// InitializeExchangeBooth creates the booth accounts and the registry through CPI, which only the
+// BPF build can do in solana-program-test 1.9
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn initialize_adds_each_booth_of_a_pair_to_one_registry() {
+    use solana_program::rent::Rent;
+
+    let (mut context, fixture) = BoothFixture::new().start().await;
+    // the fixture booth is in the genesis accounts only, so the registry starts with the new ones
+    let booths: Vec<_> = (1..3).map(|index| {
+        client::BoothAccounts::new(
+            &fixture.program_id,
+            &fixture.admin.pubkey(),
+            &fixture.booth.oracle,
+            &fixture.booth.mint_a,
+            &fixture.booth.mint_b,
+            index,
+            &spl_token::id(),
+        )
+    }).collect();
+    for (index, booth) in (1..3).zip(booths.iter()) {
+        let initialize = client::initialize_exchange_booth(&fixture.program_id, booth, index);
+        process(&mut context, &[initialize], &[&fixture.admin]).await.unwrap();
+    }
+    let (lamports, registry) = load_registry(&mut context, &fixture).await.unwrap();
+    assert_eq!(registry.booths, booths.iter().map(|booth| booth.exchange_booth).collect::<Vec<_>>());
+    assert_eq!(lamports, Rent::default().minimum_balance(booth_registry_len(2)));
+}
+
+#[tokio::test]
+async fn close_rejects_booth_owned_by_another_program() {
+    let mut fixture = closeable_booth(&[]);
+    let booth = fixture.booth.exchange_booth;
+    fixture.set_owner(&booth, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let close = client::close_exchange_booth(&fixture.program_id, &fixture.booth);
+    let result = process(&mut context, &[close], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn close_rejects_fake_token_program() {
+    let (mut context, fixture) = closeable_booth(&[]).start().await;
+    let mut close = client::close_exchange_booth(&fixture.program_id, &fixture.booth);
+    close.accounts[5].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[close], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn close_rejects_lp_mint_owned_by_another_program() {
+    let mut fixture = closeable_booth(&[]);
+    let lp_mint = fixture.booth.lp_mint;
+    fixture.set_owner(&lp_mint, &Pubkey::new_unique());
+    let (mut context, fixture) = fixture.start().await;
+    let close = client::close_exchange_booth(&fixture.program_id, &fixture.booth);
+    let result = process(&mut context, &[close], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn close_rejects_a_vault_outside_the_booth() {
+    let (mut context, fixture) = closeable_booth(&[]).start().await;
+    let mut close = client::close_exchange_booth(&fixture.program_id, &fixture.booth);
+    // an empty account of the customer's instead of the funded vault
+    close.accounts[2].pubkey = fixture.customer_b;
+    let result = process(&mut context, &[close], &[&fixture.admin]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
@@ -0,0 +1,210 @@
+// ExchangeWithQuote: fills at a rate the booth's oracle key signed off-chain, once per nonce.
+#![cfg(feature = "client")]
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    ed25519_program,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+    transport::TransportError,
+};
+
+use exchangebooth::{
+    client,
+    error::ExchangeBoothError,
+    instruction::{Quote, TradeDirection},
+    state::{QuoteNonce, QUOTE_NONCE_LEN},
+};
+
+use common::{assert_error, process, program_account, token_balance, BoothFixture, CUSTOMER_BALANCE};
+
+// the layout solana_sdk::ed25519_instruction writes: two header bytes, the offsets, then
+// pubkey, signature and message, all in this instruction (index u16::MAX)
+fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    let (public_key_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+    let mut data = vec![1, 0];
+    for offset in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signer.sign_message(message).as_ref());
+    data.extend_from_slice(message);
+    Instruction { program_id: ed25519_program::id(), accounts: vec![], data }
+}
+
+// The nonce account is already there, with `last_nonce`, so nothing has to be created on the
+// way: native program-test can't create accounts from a CPI.
+async fn start(last_nonce: u64) -> (ProgramTestContext, BoothFixture, Keypair) {
+    start_tampered(last_nonce, |_| {}).await
+}
+
+// like start, with `tamper` applied to the genesis accounts first
+async fn start_tampered(last_nonce: u64, tamper: impl FnOnce(&mut BoothFixture)) -> (ProgramTestContext, BoothFixture, Keypair) {
+    let mut fixture = BoothFixture::new();
+    let oracle = Keypair::new();
+    fixture.set_oracle(&oracle.pubkey());
+    let quote_nonce = client::quote_nonce_address(&fixture.program_id, &fixture.booth.exchange_booth, &fixture.customer.pubkey()).0;
+    let mut data = vec![0; QUOTE_NONCE_LEN];
+    QuoteNonce { last_nonce }.serialize(&mut &mut data[..]).unwrap();
+    fixture.accounts.insert(quote_nonce, program_account(&fixture.program_id, data));
+    tamper(&mut fixture);
+    let (context, fixture) = fixture.start().await;
+    (context, fixture, oracle)
+}
+
+fn quote(fixture: &BoothFixture, nonce: u64) -> Quote {
+    Quote {
+        exchange_booth: fixture.booth.exchange_booth,
+        customer: fixture.customer.pubkey(),
+        a_to_b: 2.5,
+        direction: TradeDirection::AToB,
+        max_amount: 10.0,
+        expiry_slot: u64::MAX,
+        nonce,
+    }
+}
+
+fn fill(fixture: &BoothFixture, signer: &Keypair, quote: Quote, amount: f64) -> [Instruction; 2] {
+    [
+        ed25519_instruction(signer, &quote.try_to_vec().unwrap()),
+        client::exchange_with_quote(&fixture.program_id, &fixture.booth, &fixture.customer_a, &fixture.customer_b, amount, quote),
+    ]
+}
+
+#[tokio::test]
+async fn fills_at_the_quoted_rate() {
+    let (mut context, fixture, oracle) = start(0).await;
+    process(&mut context, &fill(&fixture, &oracle, quote(&fixture, 1), 1.0), &[&fixture.customer]).await.unwrap();
+    // 2.5 B for 1 A, not the oracle account's 2
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_500_000);
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE - 1_000_000);
+    let quote_nonce = client::quote_nonce_address(&fixture.program_id, &fixture.booth.exchange_booth, &fixture.customer.pubkey()).0;
+    let account = context.banks_client.get_account(quote_nonce).await.unwrap().unwrap();
+    assert_eq!(QuoteNonce::try_from_slice(&account.data).unwrap().last_nonce, 1);
+}
+
+#[tokio::test]
+async fn rejects_a_used_nonce() {
+    let (mut context, fixture, oracle) = start(1).await;
+    let result = process(&mut context, &fill(&fixture, &oracle, quote(&fixture, 1), 1.0), &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::QuoteNonceReused);
+}
+
+#[tokio::test]
+async fn rejects_a_quote_signed_by_another_key() {
+    let (mut context, fixture, _) = start(0).await;
+    let result = process(&mut context, &fill(&fixture, &Keypair::new(), quote(&fixture, 1), 1.0), &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidQuoteSignature);
+}
+
+#[tokio::test]
+async fn rejects_a_quote_without_its_signature() {
+    let (mut context, fixture, _) = start(0).await;
+    let [_, exchange] = fill(&fixture, &Keypair::new(), quote(&fixture, 1), 1.0);
+    let result = process(&mut context, &[exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidQuoteSignature);
+}
+
+#[tokio::test]
+async fn rejects_an_expired_quote() {
+    let (mut context, fixture, oracle) = start(0).await;
+    context.warp_to_slot(10).unwrap();
+    let quote = Quote { expiry_slot: 5, ..quote(&fixture, 1) };
+    let result = process(&mut context, &fill(&fixture, &oracle, quote, 1.0), &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::QuoteExpired);
+}
+
+#[tokio::test]
+async fn rejects_more_than_the_quoted_size() {
+    let (mut context, fixture, oracle) = start(0).await;
+    let result = process(&mut context, &fill(&fixture, &oracle, quote(&fixture, 1), 11.0), &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::QuoteSizeExceeded);
+}
+
+#[tokio::test]
+async fn rejects_booth_owned_by_another_program() {
+    let (mut context, fixture, oracle) = start_tampered(0, |fixture| {
+        let booth = fixture.booth.exchange_booth;
+        fixture.set_owner(&booth, &Pubkey::new_unique());
+    }).await;
+    let result = process(&mut context, &fill(&fixture, &oracle, quote(&fixture, 1), 1.0), &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn rejects_a_nonce_account_owned_by_another_program() {
+    let (mut context, fixture, oracle) = start_tampered(0, |fixture| {
+        let quote_nonce = client::quote_nonce_address(&fixture.program_id, &fixture.booth.exchange_booth, &fixture.customer.pubkey()).0;
+        fixture.set_owner(&quote_nonce, &Pubkey::new_unique());
+    }).await;
+    let result = process(&mut context, &fill(&fixture, &oracle, quote(&fixture, 1), 1.0), &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn rejects_another_customers_nonce_account() {
+    let (mut context, fixture, oracle) = start(0).await;
+    let [verify, mut exchange] = fill(&fixture, &oracle, quote(&fixture, 1), 1.0);
+    exchange.accounts[11].pubkey = client::quote_nonce_address(&fixture.program_id, &fixture.booth.exchange_booth, &fixture.admin.pubkey()).0;
+    let result = process(&mut context, &[verify, exchange], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn rejects_fake_programs() {
+    for (index, error) in [(9, ExchangeBoothError::InvalidSystemProgram), (10, ExchangeBoothError::InvalidTokenProgram)] {
+        let (mut context, fixture, oracle) = start(0).await;
+        let [verify, mut exchange] = fill(&fixture, &oracle, quote(&fixture, 1), 1.0);
+        exchange.accounts[index].pubkey = Pubkey::new_unique();
+        let result = process(&mut context, &[verify, exchange], &[&fixture.customer]).await;
+        assert_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn rejects_a_fake_instructions_sysvar() {
+    let (mut context, fixture, oracle) = start(0).await;
+    let [verify, mut exchange] = fill(&fixture, &oracle, quote(&fixture, 1), 1.0);
+    exchange.accounts[12].pubkey = Pubkey::new_unique();
+    let result = process(&mut context, &[verify, exchange], &[&fixture.customer]).await;
+    match result {
+        Err(TransportError::TransactionError(TransactionError::InstructionError(1, InstructionError::UnsupportedSysvar))) => {}
+        other => panic!("expected UnsupportedSysvar, got {:?}", other),
+    }
+}
+
+// The first fill creates the nonce account through CPI, which only the BPF build can do in
+// solana-program-test 1.9. Someone sent lamports to the address first, which must not block it.
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn first_fill_creates_the_nonce_account() {
+    use common::system_account;
+
+    let mut fixture = BoothFixture::new();
+    let oracle = Keypair::new();
+    fixture.set_oracle(&oracle.pubkey());
+    let quote_nonce = client::quote_nonce_address(&fixture.program_id, &fixture.booth.exchange_booth, &fixture.customer.pubkey()).0;
+    fixture.accounts.insert(quote_nonce, system_account());
+    let (mut context, fixture) = fixture.start().await;
+
+    process(&mut context, &fill(&fixture, &oracle, quote(&fixture, 1), 1.0), &[&fixture.customer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 2_500_000);
+    let account = context.banks_client.get_account(quote_nonce).await.unwrap().unwrap();
+    assert_eq!(account.owner, fixture.program_id);
+    assert_eq!(QuoteNonce::try_from_slice(&account.data).unwrap().last_nonce, 1);
+}
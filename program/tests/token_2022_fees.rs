@@ -0,0 +1,92 @@
+// A booth on a Token-2022 mint pair that charges transfer fees: trades are priced on what the
+// vault received and the customer gets what is left after the fee on the way out.
+#![cfg(feature = "client")]
+
+mod common;
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_pack::Pack, pubkey::Pubkey,
+};
+use solana_program_test::{processor, tokio, ProgramTestContext};
+use solana_sdk::signature::Signer;
+use spl_token::{instruction::TokenInstruction, state::Account};
+
+use exchangebooth::{client, error::ExchangeBoothError, processor::token::token_2022};
+
+use common::{assert_error, process, token_balance, BoothFixture, CUSTOMER_BALANCE, VAULT_BALANCE};
+
+const FEE_BPS: u64 = 100;
+
+fn fee(amount: u64) -> u64 {
+    (amount * FEE_BPS + 9_999) / 10_000
+}
+
+// There is no Token-2022 build for program-test 1.9, so this stands in for it: the spl-token
+// processor run under the Token-2022 id, withholding FEE_BPS of every TransferChecked from
+// the destination the way a mint with a transfer fee extension does.
+fn fee_charging_token(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+    spl_token::processor::Processor::process(program_id, accounts, input)?;
+    if let TokenInstruction::TransferChecked { amount, .. } = TokenInstruction::unpack(input)? {
+        let destination = &accounts[2];
+        let mut account = Account::unpack(&destination.data.borrow())?;
+        account.amount -= fee(amount);
+        Account::pack(account, &mut destination.data.borrow_mut())?;
+    }
+    Ok(())
+}
+
+// the fixture booth with every token account and mint moved to Token-2022
+fn token_2022_fixture() -> BoothFixture {
+    let mut fixture = BoothFixture::new();
+    let booth = fixture.booth.clone();
+    for address in [booth.mint_a, booth.mint_b, booth.vault_a, booth.vault_b, booth.lp_mint, fixture.customer_a, fixture.customer_b] {
+        fixture.set_owner(&address, &token_2022::id());
+    }
+    fixture.booth.token_program = token_2022::id();
+    fixture
+}
+
+async fn start(fixture: BoothFixture) -> (ProgramTestContext, BoothFixture) {
+    let mut program_test = fixture.program_test();
+    program_test.add_program("fee_charging_token", token_2022::id(), processor!(fee_charging_token));
+    fixture.start_with(program_test).await
+}
+
+#[tokio::test]
+async fn exchange_prices_what_the_vault_received() {
+    let (mut context, fixture) = start(token_2022_fixture()).await;
+    process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await.unwrap();
+    // 1 A sold, 0.99 A reached the vault, worth 1.98 B of which 1% is withheld on the way out
+    assert_eq!(token_balance(&mut context, &fixture.customer_a).await, CUSTOMER_BALANCE - 1_000_000);
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_a).await, VAULT_BALANCE + 990_000);
+    assert_eq!(token_balance(&mut context, &fixture.booth.vault_b).await, VAULT_BALANCE - 1_980_000);
+    assert_eq!(token_balance(&mut context, &fixture.customer_b).await, 1_980_000 - 19_800);
+}
+
+#[tokio::test]
+async fn exchange_rejects_accounts_of_the_other_token_program() {
+    let mut fixture = token_2022_fixture();
+    let customer_a = fixture.customer_a;
+    fixture.set_owner(&customer_a, &spl_token::id());
+    let (mut context, fixture) = start(fixture).await;
+    let result = process(&mut context, &[fixture.exchange()], &[&fixture.customer]).await;
+    assert_error(result, ExchangeBoothError::InvalidTokenAccountOwner);
+}
+
+#[tokio::test]
+async fn lp_shares_are_priced_on_what_the_vault_received() {
+    let mut fixture = token_2022_fixture();
+    let (admin, booth) = (fixture.admin.pubkey(), fixture.booth.clone());
+    let admin_a = fixture.add_token_account(&booth.mint_a, &admin, 0);
+    let admin_b = fixture.add_token_account(&booth.mint_b, &admin, CUSTOMER_BALANCE);
+    let admin_lp = fixture.add_token_account(&booth.lp_mint, &admin, 0);
+    for address in [admin_a, admin_b, admin_lp] {
+        fixture.set_owner(&address, &token_2022::id());
+    }
+    let (mut context, fixture) = start(fixture).await;
+
+    let add_liquidity = client::add_liquidity(&fixture.program_id, &fixture.booth, &admin, &admin_a, &admin_b, &admin_lp, 0.0, 10.0);
+    process(&mut context, &[add_liquidity], &[&fixture.admin]).await.unwrap();
+    // 1000 A at 2 B per A, 1000 B and the 9.9 B of the 10 that arrived
+    assert_eq!(token_balance(&mut context, &admin_lp).await, 3_009_900_000);
+}
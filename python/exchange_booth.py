@@ -26,13 +26,13 @@ pack_str = lambda s: struct.pack("<I" + (len(s) * "B"), len(s), *s.encode("ascii
 admin_kp = Keypair()
 customer_kp = Keypair()
 oracle_kp = Keypair()
-exchange_booth_kp = Keypair()
 
 
 class InitExchangeBoothParams(NamedTuple):
     program_id: PublicKey
     admin_kp: Keypair  # [S]
     exchange_booth: PublicKey  # [W]
+    booth_index: int
     oracle: PublicKey
     vault_a: PublicKey  # [W]
     mint_a: PublicKey
@@ -40,12 +40,15 @@ class InitExchangeBoothParams(NamedTuple):
     vault_b: PublicKey  # [W]
     mint_b: PublicKey
     token_b: Token
+    lp_mint: PublicKey  # [W]
+    booth_registry: PublicKey  # [W]
 
 
 class SetExchangeRateParams(NamedTuple):
     program_id: PublicKey
     oracle: PublicKey
     exchange_rate_a_to_b: float
+    confidence: float = 0.0
 
 class WithdrawParams(NamedTuple):
     program_id: PublicKey
@@ -59,6 +62,23 @@ class WithdrawParams(NamedTuple):
     vault_b: PublicKey
 
 
+class AddLiquidityParams(NamedTuple):
+    program_id: PublicKey
+    exchange_booth: PublicKey
+    oracle: PublicKey
+    vault_a: PublicKey  # [W]
+    vault_b: PublicKey  # [W]
+    mint_a: PublicKey
+    mint_b: PublicKey
+    lp_mint: PublicKey  # [W]
+    provider: PublicKey  # [S]
+    provider_token_a_account: PublicKey  # [W]
+    provider_token_b_account: PublicKey  # [W]
+    provider_lp_token_account: PublicKey  # [W]
+    amount_a: float
+    amount_b: float
+
+
 class ExchangeParams(NamedTuple):
     program_id: PublicKey
     amount_to_exchange: int
@@ -76,11 +96,15 @@ class ExchangeParams(NamedTuple):
 def set_exchange_rate(params: SetExchangeRateParams) -> TransactionInstruction:
     # combine with exchange rate
     data = b"".join(
-        [struct.pack("<B", 5), struct.pack("<d", params.exchange_rate_a_to_b)]
+        [
+            struct.pack("<B", 5),
+            struct.pack("<d", params.exchange_rate_a_to_b),
+            struct.pack("<d", params.confidence),
+        ]
     )
 
     return TransactionInstruction(
-        keys=[AccountMeta(pubkey=params.oracle, is_signer=False, is_writable=True)],
+        keys=[AccountMeta(pubkey=params.oracle, is_signer=True, is_writable=True)],
         program_id=params.program_id,
         data=data,
     )
@@ -109,8 +133,7 @@ def exchange_instruction(params: ExchangeParams) -> TransactionInstruction:
     )
 
 def init_exchange_booth(params: InitExchangeBoothParams) -> TransactionInstruction:
-    # no data needed for init
-    data = struct.pack("<B", 0)
+    data = b"".join([struct.pack("<B", 0), struct.pack("<Q", params.booth_index)])
 
     return TransactionInstruction(
         keys=[
@@ -122,21 +145,23 @@ def init_exchange_booth(params: InitExchangeBoothParams) -> TransactionInstructi
             AccountMeta(pubkey=params.vault_b, is_signer=False, is_writable=True),
             AccountMeta(pubkey=params.mint_a, is_signer=False, is_writable=False),
             AccountMeta(pubkey=params.mint_b, is_signer=False, is_writable=False),
-            AccountMeta(pubkey=params.admin_kp.public_key, is_signer=True, is_writable=False),
+            AccountMeta(pubkey=params.admin_kp.public_key, is_signer=True, is_writable=True),
             AccountMeta(pubkey=SYS_PROGRAM_ID, is_signer=False, is_writable=False),
             AccountMeta(pubkey=TOKEN_PROGRAM_ID, is_signer=False, is_writable=False),
             AccountMeta(pubkey=SYSVAR_RENT_PUBKEY, is_signer=False, is_writable=False),
+            AccountMeta(pubkey=params.lp_mint, is_signer=False, is_writable=True),
+            AccountMeta(pubkey=params.booth_registry, is_signer=False, is_writable=True),
         ],
         program_id=params.program_id,
         data=data,
     )
 
-def init(program_id, client) -> CommandParams:
+def init(program_id, client, booth_index=0) -> CommandParams:
     program_id = PublicKey(program_id)
 
     ixs = []
-    # create accounts and allocate space
-    for _account, _space in [(oracle_kp, 16), (exchange_booth_kp, 32 * 4)]:
+    # create accounts and allocate space, the booth itself is a PDA created by the program
+    for _account, _space in [(oracle_kp, 24)]:
         ixs.append(
             create_account(
                 CreateAccountParams(
@@ -169,12 +194,23 @@ def init(program_id, client) -> CommandParams:
     )
     mint_b = token_b.pubkey
 
+    exchange_booth, _ = PublicKey.find_program_address(
+        [
+            b"exchange_booth",
+            bytes(admin_kp.public_key),
+            bytes(mint_a),
+            bytes(mint_b),
+            booth_index.to_bytes(8, "little"),
+        ],
+        program_id,
+    )
+
     # create PDA for 'vault_a'
     vault_a, _ = PublicKey.find_program_address(
         [
             b"exchange_booth",
             bytes(admin_kp.public_key),
-            bytes(exchange_booth_kp.public_key),
+            bytes(exchange_booth),
             bytes(mint_a),
         ],
         program_id,
@@ -185,16 +221,34 @@ def init(program_id, client) -> CommandParams:
         [
             b"exchange_booth",
             bytes(admin_kp.public_key),
-            bytes(exchange_booth_kp.public_key),
+            bytes(exchange_booth),
             bytes(mint_b),
         ],
         program_id,
     )
 
+    # create PDA for the lp share mint
+    lp_mint, _ = PublicKey.find_program_address(
+        [
+            b"exchange_booth",
+            bytes(admin_kp.public_key),
+            bytes(exchange_booth),
+            b"lp_mint",
+        ],
+        program_id,
+    )
+
+    # registry of every booth for this mint pair, the mints are sorted so A/B and B/A share it
+    booth_registry, _ = PublicKey.find_program_address(
+        [b"booth_registry", *sorted([bytes(mint_a), bytes(mint_b)])],
+        program_id,
+    )
+
     params = InitExchangeBoothParams(
         program_id=program_id,
         admin_kp=admin_kp,
-        exchange_booth=exchange_booth_kp.public_key,
+        exchange_booth=exchange_booth,
+        booth_index=booth_index,
         oracle=oracle_kp.public_key,
         vault_a=vault_a,
         mint_a=mint_a,
@@ -202,10 +256,12 @@ def init(program_id, client) -> CommandParams:
         vault_b=vault_b,
         mint_b=mint_b,
         token_b=token_b,
+        lp_mint=lp_mint,
+        booth_registry=booth_registry,
     )
 
     ixs.append(init_exchange_booth(params))
-    signers = [admin_kp, oracle_kp, exchange_booth_kp]
+    signers = [admin_kp, oracle_kp]
 
     return CommandParams(instructions=ixs, signers=signers, params=params)
 
@@ -214,7 +270,8 @@ def set_rate(
     program_id,
     client,
     exchange_rate_a_to_b: float,
-    oracle: Optional[PublicKey] = None
+    oracle: Optional[PublicKey] = None,
+    confidence: float = 0.0
 ):
     program_id = PublicKey(program_id)
     ixs = []
@@ -230,25 +287,56 @@ def set_rate(
                     lamports=client.get_minimum_balance_for_rent_exemption(40)[
                         "result"
                     ],
-                    space=16,
+                    space=24,
                     program_id=program_id,
                 )
             )
         )
-        signers.append(oracle_kp)
         oracle = oracle_kp.public_key
 
     params = SetExchangeRateParams(
         program_id=program_id,
         oracle=oracle,
         exchange_rate_a_to_b=exchange_rate_a_to_b,
+        confidence=confidence,
     )
 
     ixs.append(set_exchange_rate(params))
+    # the oracle signs its own updates
+    signers.append(oracle_kp)
     signers.append(admin_kp)
     print(f"set_rate signers {signers}")
     return CommandParams(instructions=ixs, signers=signers, params=params)
 
+def add_liquidity_instruction(params: AddLiquidityParams) -> TransactionInstruction:
+    data = b"".join(
+        [
+            struct.pack("<B", 7),
+            struct.pack("<d", params.amount_a),
+            struct.pack("<d", params.amount_b),
+        ]
+    )
+
+    return TransactionInstruction(
+        keys=[
+            AccountMeta(pubkey=params.exchange_booth, is_signer=False, is_writable=False),
+            AccountMeta(pubkey=params.oracle, is_signer=False, is_writable=False),
+            AccountMeta(pubkey=params.vault_a, is_signer=False, is_writable=True),
+            AccountMeta(pubkey=params.vault_b, is_signer=False, is_writable=True),
+            AccountMeta(pubkey=params.mint_a, is_signer=False, is_writable=False),
+            AccountMeta(pubkey=params.mint_b, is_signer=False, is_writable=False),
+            AccountMeta(pubkey=params.lp_mint, is_signer=False, is_writable=True),
+            AccountMeta(pubkey=params.provider, is_signer=True, is_writable=False),
+            AccountMeta(pubkey=params.provider_token_a_account, is_signer=False, is_writable=True),
+            AccountMeta(pubkey=params.provider_token_b_account, is_signer=False, is_writable=True),
+            AccountMeta(pubkey=params.provider_lp_token_account, is_signer=False, is_writable=True),
+            AccountMeta(pubkey=TOKEN_PROGRAM_ID, is_signer=False, is_writable=False),
+        ],
+        program_id=params.program_id,
+        data=data,
+    )
+
+
 def deposit(
     program_id, 
     client,
@@ -259,15 +347,49 @@ def deposit(
 
     token_to_deposit = init_params.token_a if chosen_token == 'a' else init_params.token_b
     vault_to_deposit = init_params.vault_a if chosen_token == 'a' else init_params.vault_b
+    decimals = 6 if chosen_token == 'a' else 9
 
     print(f'A={init_params.vault_a}, B={init_params.vault_b}, vault_to_deposit={vault_to_deposit}')
 
+    lp_token = Token(client, init_params.lp_mint, TOKEN_PROGRAM_ID, admin_kp)
+    if lp_token.get_mint_info().supply == 0:
+        # nobody holds shares yet, the first AddLiquidity (admin only) will count this as the admin's
+        Token.mint_to(
+            token_to_deposit,
+            PublicKey(vault_to_deposit),
+            admin_kp,
+            amount * 10 ** decimals
+        )
+        return CommandParams(instructions=[], signers=[], params=None)
+
+    # a direct transfer would now be split between the lp holders, so deposit for shares
+    provider_token_a_account = init_params.token_a.create_account(admin_kp.public_key)
+    provider_token_b_account = init_params.token_b.create_account(admin_kp.public_key)
+    provider_lp_token_account = lp_token.create_account(admin_kp.public_key)
     Token.mint_to(
         token_to_deposit,
-        PublicKey(vault_to_deposit),
+        provider_token_a_account if chosen_token == 'a' else provider_token_b_account,
         admin_kp,
-        amount*1000000
+        amount * 10 ** decimals
+    )
+
+    params = AddLiquidityParams(
+        program_id=PublicKey(program_id),
+        exchange_booth=init_params.exchange_booth,
+        oracle=init_params.oracle,
+        vault_a=init_params.vault_a,
+        vault_b=init_params.vault_b,
+        mint_a=init_params.mint_a,
+        mint_b=init_params.mint_b,
+        lp_mint=init_params.lp_mint,
+        provider=admin_kp.public_key,
+        provider_token_a_account=provider_token_a_account,
+        provider_token_b_account=provider_token_b_account,
+        provider_lp_token_account=provider_lp_token_account,
+        amount_a=float(amount) if chosen_token == 'a' else 0.0,
+        amount_b=float(amount) if chosen_token == 'b' else 0.0,
     )
+    return CommandParams(instructions=[add_liquidity_instruction(params)], signers=[admin_kp], params=params)
 
 def withdraw(
     chosen_token,
@@ -297,6 +419,7 @@ def withdraw(
             AccountMeta(pubkey=customer_to_token_account, is_signer=False, is_writable=True),
             AccountMeta(pubkey=admin_kp.public_key, is_signer=True, is_writable=False),
             AccountMeta(pubkey=TOKEN_PROGRAM_ID, is_signer=False, is_writable=False),
+            AccountMeta(pubkey=command_params.lp_mint, is_signer=False, is_writable=False),
         ],
         program_id=command_params.program_id,
         data=data_withdraw,
@@ -460,7 +583,7 @@ def main():
         else:
             raise RuntimeError(f"{command_input} not supported yet")
 
-        if command_input != "deposit":
+        if _params.instructions:
             result = client.send_transaction(
                 Transaction().add(*(ix for ix in _params.instructions)),
                 *_params.signers,